@@ -1,88 +1,175 @@
-use backend::shared::config::{load_config, MarketData, TradeSignal};
-use backend::shared::data_loader::{load_historical_data_alpaca, load_historical_data_db};
+use backend::shared::clock::{Clock, SimulatedClock};
+use backend::shared::config::{Config, MarketData, Side, TradeSignal};
+use backend::shared::corporate_actions::{adjust_bars, load_corporate_actions, PriceAdjustment};
+use backend::shared::data_loader::{historical_source, DataError, TimeframeUnit};
+use backend::shared::features::{
+    warmup_start, EnrichedBar, FeatureEngine, FeatureKind, FeatureSpec,
+};
+use backend::shared::instrument::{Instrument, InstrumentMaster, InstrumentSpec};
+use backend::shared::money::{Money, Price, Quantity};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
-use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+/// Features behind `MarketData::moving_average_50` and `moving_average_200`
+const STRATEGY_FEATURES: [FeatureSpec; 2] = [
+    FeatureSpec::new(FeatureKind::Sma, 50),
+    FeatureSpec::new(FeatureKind::Sma, 200),
+];
 
 pub struct BacktestEngine {
-    historical_data: Vec<MarketData>,
-    market_data_stream: mpsc::Receiver<MarketData>,
+    historical_data: Vec<(DateTime<Utc>, MarketData)>, // Bar time and the strategy's input
+    market_data_stream: mpsc::Receiver<(DateTime<Utc>, MarketData)>,
+    clock: SimulatedClock, // At the time of the bar being processed
     portfolio: Portfolio,
 }
 
 #[derive(Debug)]
 pub struct Portfolio {
-    cash: f64,
-    positions: HashMap<String, f64>, // Symbol -> Quantity
+    starting_cash: Money,
+    cash: Money,
+    positions: HashMap<Instrument, Quantity>,
+    last_prices: HashMap<Instrument, Price>, // Latest price per instrument, to value open positions
+    instruments: InstrumentMaster,           // Contract multipliers for notional and valuation
     trade_log: Vec<ExecutedTrade>,
+    clock: Arc<dyn Clock>, // Timestamps fills
 }
 
 #[derive(Debug)]
 pub struct ExecutedTrade {
-    pub symbol: String,
-    pub qty: f64,
-    pub price: f64,
-    pub side: String,
-    pub timestamp: String,
+    pub symbol: Instrument,
+    pub qty: Quantity,
+    pub price: Price,
+    pub side: Side,
+    pub cash_flow: Money, // Signed, rounded to the cent: negative for buys
+    pub timestamp: DateTime<Utc>,
 }
 
 impl Portfolio {
-    pub fn new(starting_cash: f64) -> Self {
+    pub fn new(starting_cash: Money, instruments: InstrumentMaster, clock: Arc<dyn Clock>) -> Self {
         Self {
+            starting_cash,
             cash: starting_cash,
             positions: HashMap::new(),
+            last_prices: HashMap::new(),
+            instruments,
             trade_log: Vec::new(),
+            clock,
         }
     }
 
+    /// Records the latest price for `instrument`
+    pub fn mark(&mut self, instrument: &Instrument, price: Price) {
+        self.last_prices.insert(instrument.clone(), price);
+    }
+
     pub fn execute_trade(&mut self, signal: &TradeSignal, market_data: &MarketData) {
-        let cost = market_data.price * signal.qty as f64;
-
-        match signal.side.as_str() {
-            "buy" => {
-                if self.cash >= cost {
-                    *self.positions.entry(signal.symbol.clone()).or_insert(0.0) +=
-                        signal.qty as f64;
-                    self.cash -= cost;
-                    println!(
-                        "Bought {} shares of {} at ${}",
-                        signal.qty, signal.symbol, market_data.price
-                    );
-                } else {
-                    println!("Insufficient cash to buy {}", signal.symbol);
+        self.mark(&market_data.symbol, market_data.price);
+        // Cash moves in whole cents, as it does at the broker
+        let cost = self
+            .instruments
+            .spec(&signal.symbol)
+            .notional(market_data.price, signal.qty)
+            .round_cents();
+
+        let side = match signal.action.side() {
+            Some(side) => side,
+            None => {
+                info!(action = ?signal.action, symbol = %signal.symbol, "Ignoring signal in backtest");
+                return;
+            }
+        };
+
+        let cash_flow = match side {
+            Side::Buy => {
+                if self.cash < cost {
+                    info!(symbol = %signal.symbol, %cost, cash = %self.cash, "Insufficient cash to buy");
+                    return;
                 }
+                *self.positions.entry(signal.symbol.clone()).or_default() += signal.qty;
+                self.cash -= cost;
+                info!(
+                    symbol = %signal.symbol,
+                    qty = %signal.qty,
+                    price = %market_data.price,
+                    "Filled simulated buy"
+                );
+                -cost
             }
-            "sell" => {
-                if let Some(position) = self.positions.get_mut(&signal.symbol) {
-                    if *position >= signal.qty as f64 {
-                        *position -= signal.qty as f64;
-                        self.cash += cost;
-                        println!(
-                            "Sold {} shares of {} at ${}",
-                            signal.qty, signal.symbol, market_data.price
-                        );
-                    } else {
-                        println!("Insufficient shares to sell {}", signal.symbol);
+            Side::Sell => {
+                match self.positions.get_mut(&signal.symbol) {
+                    Some(position) if *position >= signal.qty => *position -= signal.qty,
+                    _ => {
+                        info!(symbol = %signal.symbol, qty = %signal.qty, "Insufficient shares to sell");
+                        return;
                     }
                 }
+                self.cash += cost;
+                info!(
+                    symbol = %signal.symbol,
+                    qty = %signal.qty,
+                    price = %market_data.price,
+                    "Filled simulated sell"
+                );
+                cost
             }
-            _ => println!("Unknown trade signal: {:?}", signal),
-        }
+        };
 
         // Log the trade
         self.trade_log.push(ExecutedTrade {
             symbol: signal.symbol.clone(),
-            qty: signal.qty as f64,
+            qty: signal.qty,
             price: market_data.price,
-            side: signal.side.clone(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(), // Replace with real timestamp
+            side,
+            cash_flow,
+            timestamp: self.clock.now(),
         });
     }
 
+    /// Open positions at their last price
+    pub fn market_value(&self) -> Money {
+        self.positions
+            .iter()
+            .map(|(instrument, qty)| {
+                let price = self
+                    .last_prices
+                    .get(instrument)
+                    .copied()
+                    .unwrap_or_default();
+                self.instruments
+                    .spec(instrument)
+                    .notional(price, *qty)
+                    .round_cents()
+            })
+            .sum()
+    }
+
+    /// Cash plus open positions, less the starting cash
+    pub fn pnl(&self) -> Money {
+        self.cash + self.market_value() - self.starting_cash
+    }
+
     pub fn print_summary(&self) {
         println!("📊 Portfolio Summary:");
         println!("💰 Cash: ${:.2}", self.cash);
-        println!("📈 Positions: {:?}", self.positions);
+        for (instrument, qty) in &self.positions {
+            println!("📈 Position: {} {}", qty, instrument);
+        }
+        println!("💼 Market value: ${:.2}", self.market_value());
+        println!("🧾 P&L: ${:.2}", self.pnl());
+
+        // Every cent in the cash balance must be explained by a logged trade
+        let traded: Money = self.trade_log.iter().map(|trade| trade.cash_flow).sum();
+        if self.starting_cash + traded != self.cash {
+            warn!(
+                starting_cash = %self.starting_cash,
+                traded = %traded,
+                cash = %self.cash,
+                "Cash does not reconcile with the trade log"
+            );
+        }
 
         if self.trade_log.is_empty() {
             println!("📜 No trades were executed.");
@@ -91,7 +178,7 @@ impl Portfolio {
             for trade in &self.trade_log {
                 println!(
                     "📊 {} {} shares of {} at ${:.2} on {}",
-                    trade.side.to_uppercase(),
+                    trade.side.as_str().to_uppercase(),
                     trade.qty,
                     trade.symbol,
                     trade.price,
@@ -103,60 +190,123 @@ impl Portfolio {
 }
 
 impl BacktestEngine {
-    /// Creates a new backtesting engine, fetching historical market data
-    pub async fn new(symbol: &str, start_time: &str, end_time: &str, starting_cash: f64) -> Self {
-        let config = load_config();
-        let data_source = config.backtest.data_source.as_str();
-
+    /// Creates a new backtesting engine, fetching historical bars from the configured source
+    pub async fn new(
+        config: &Config,
+        instruments: &InstrumentMaster,
+        instrument: &Instrument,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        starting_cash: Money,
+    ) -> Result<Self, DataError> {
         let (tx, rx) = mpsc::channel(100);
+        let spec = instruments.spec(instrument);
+        let symbol = instrument.to_string();
 
-        // Choose historical data source based on config
-        let historical_data = match data_source {
-            "db" => {
-                println!("📥 Loading historical data from TimescaleDB...");
-                load_historical_data_db(symbol, start_time, end_time)
-                    .await
-                    .unwrap_or_else(|_| vec![])
-            }
-            "alpaca" => {
-                println!("📥 Loading historical data from Alpaca API...");
-                load_historical_data_alpaca(symbol, start_time, end_time)
-                    .await
-                    .unwrap_or_else(|_| vec![])
-            }
-            _ => {
-                eprintln!(
-                    "⚠️ Unknown data source '{}'. Defaulting to DB.",
-                    data_source
-                );
-                load_historical_data_db(symbol, start_time, end_time)
-                    .await
-                    .unwrap_or_else(|_| vec![])
+        // `MarketData` always carries the moving averages the built-in strategy reads
+        let mut specs = config.backtest.features.clone();
+        specs.extend(STRATEGY_FEATURES);
+        let mut features = FeatureEngine::new(&specs);
+
+        // Load enough bars before the start for every feature to be warm on the first one
+        let lookback = features.lookback();
+        let load_from = warmup_start(start_time, config.backtest.timeframe, lookback);
+
+        let source = historical_source(config).await?;
+        info!(
+            source = source.name(),
+            warmup_bars = lookback,
+            from = %load_from,
+            "Loading historical data"
+        );
+        let mut bars = source.load_bars(&symbol, load_from, end_time).await?;
+
+        // Drop bars outside the trading calendar (holidays, extended hours unless enabled)
+        // so they neither reach the strategy nor skew the features
+        let calendar = config.calendar.calendar(instrument.market());
+        let extended_hours = config.calendar.extended_hours;
+        let daily = !matches!(
+            config.backtest.timeframe.unit,
+            TimeframeUnit::Minute | TimeframeUnit::Hour
+        );
+        let loaded = bars.len();
+        bars.retain(|bar| {
+            if daily {
+                calendar.is_trading_day(calendar.local_date(bar.timestamp))
+            } else {
+                calendar.is_open(bar.timestamp, extended_hours)
             }
-        };
+        });
+        if bars.len() < loaded {
+            info!(
+                dropped = loaded - bars.len(),
+                market = %calendar.market(),
+                "Dropped bars outside trading hours"
+            );
+        }
+
+        let adjustment = config.backtest.price_adjustment;
+        if adjustment != PriceAdjustment::None {
+            let actions = load_corporate_actions(config, &symbol, load_from, end_time).await?;
+            let actions = actions.for_symbol(&symbol);
+            info!(
+                %adjustment,
+                actions = actions.len(),
+                "Applying corporate action adjustment"
+            );
+            adjust_bars(&mut bars, actions, adjustment);
+        }
+
+        let warmup = bars.iter().filter(|b| b.timestamp < start_time).count();
+        if warmup < lookback {
+            warn!(
+                available = warmup,
+                needed = lookback,
+                "Not enough warm-up bars; skipping bars until features are ready"
+            );
+        }
+
+        let enriched = features.enrich(&bars, start_time);
+        let total = enriched.len();
+        let historical_data: Vec<(DateTime<Utc>, MarketData)> = enriched
+            .into_iter()
+            .filter(|bar| features.is_complete(bar))
+            .map(|bar| (bar.bar.timestamp, to_market_data(bar, &spec)))
+            .collect();
+        if historical_data.len() < total {
+            warn!(
+                skipped = total - historical_data.len(),
+                "Skipped bars without a full feature set"
+            );
+        }
 
         let data_count = historical_data.len();
-        println!("📊 Loaded {} records for backtesting", data_count);
+        info!(
+            records = data_count,
+            "Loaded historical data for backtesting"
+        );
 
-        // Spawn a task to simulate real-time market data streaming
+        // Spawn a task to simulate real-time market data streaming. Bars go out as
+        // fast as they are taken; time in the backtest is the bar's, not the wall clock's.
         tokio::spawn({
             let historical_data = historical_data.clone(); // Clone to move into the async block
             async move {
-                for market_data in historical_data {
-                    if tx.send(market_data).await.is_err() {
-                        println!("Backtest Engine: Receiver dropped, stopping data stream.");
+                for bar in historical_data {
+                    if tx.send(bar).await.is_err() {
+                        debug!("Receiver dropped, stopping data stream");
                         break;
                     }
-                    sleep(Duration::from_millis(500)).await;
                 }
             }
         });
 
-        Self {
+        let clock = SimulatedClock::new(start_time);
+        Ok(Self {
             historical_data,
             market_data_stream: rx,
-            portfolio: Portfolio::new(starting_cash),
-        }
+            portfolio: Portfolio::new(starting_cash, instruments.clone(), Arc::new(clock.clone())),
+            clock,
+        })
     }
 
     /// Returns the number of historical data points available
@@ -169,9 +319,13 @@ impl BacktestEngine {
         }
     }
 
-    /// Retrieves the next piece of historical market data, simulating a live stream
+    /// Retrieves the next piece of historical market data, simulating a live stream,
+    /// and moves the backtest clock to its bar
     pub async fn get_next_market_data(&mut self) -> Option<MarketData> {
-        self.market_data_stream.recv().await
+        let (timestamp, market_data) = self.market_data_stream.recv().await?;
+        self.clock.observe(timestamp);
+        self.portfolio.mark(&market_data.symbol, market_data.price);
+        Some(market_data)
     }
 
     /// Executes a simulated trade based on trade signals
@@ -184,3 +338,87 @@ impl BacktestEngine {
         self.portfolio.print_summary();
     }
 }
+
+/// Turns an enriched bar into the strategy's input
+fn to_market_data(enriched: EnrichedBar, instrument: &InstrumentSpec) -> MarketData {
+    let feature = |spec: FeatureSpec| {
+        enriched
+            .features
+            .get(&spec.to_string())
+            .copied()
+            .unwrap_or_default()
+    };
+    MarketData {
+        symbol: instrument.instrument.clone(),
+        // Bar closes can carry float noise or sub-tick digits after adjustment; fills happen on the tick grid
+        price: instrument.round_price(Price::from_f64(enriched.bar.close).unwrap_or_default()),
+        moving_average_50: feature(STRATEGY_FEATURES[0]),
+        moving_average_200: feature(STRATEGY_FEATURES[1]),
+        features: enriched.features,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::shared::clock::ManualClock;
+    use backend::shared::config::SignalAction;
+    use chrono::{Duration, TimeZone};
+
+    fn bar(symbol: &Instrument, price: &str) -> MarketData {
+        MarketData {
+            symbol: symbol.clone(),
+            price: price.parse().unwrap(),
+            moving_average_50: 0.0,
+            moving_average_200: 0.0,
+            features: Default::default(),
+        }
+    }
+
+    fn signal(symbol: &Instrument, qty: i64, action: SignalAction) -> TradeSignal {
+        TradeSignal {
+            symbol: symbol.clone(),
+            qty: Quantity::from(qty),
+            action,
+        }
+    }
+
+    #[test]
+    fn fills_are_stamped_with_the_clock_time() {
+        let aapl = Instrument::equity("AAPL").unwrap();
+        let opened = Utc.with_ymd_and_hms(2025, 3, 3, 14, 30, 0).unwrap();
+        let clock = ManualClock::new(opened);
+        let mut portfolio = Portfolio::new(
+            Money::from(10_000),
+            InstrumentMaster::new(),
+            Arc::new(clock.clone()),
+        );
+
+        portfolio.execute_trade(
+            &signal(&aapl, 10, SignalAction::Buy),
+            &bar(&aapl, "100.005"),
+        );
+        clock.advance(Duration::days(1));
+        portfolio.execute_trade(&signal(&aapl, 4, SignalAction::Sell), &bar(&aapl, "110"));
+        // Rejected for lack of shares, so nothing is logged at this time
+        clock.advance(Duration::days(1));
+        portfolio.execute_trade(&signal(&aapl, 100, SignalAction::Sell), &bar(&aapl, "120"));
+
+        let fills: Vec<(Side, DateTime<Utc>, Money)> = portfolio
+            .trade_log
+            .iter()
+            .map(|trade| (trade.side, trade.timestamp, trade.cash_flow))
+            .collect();
+        assert_eq!(
+            fills,
+            vec![
+                (Side::Buy, opened, "-1000.05".parse().unwrap()),
+                (Side::Sell, opened + Duration::days(1), Money::from(440)),
+            ]
+        );
+        assert_eq!(portfolio.cash, "9439.95".parse().unwrap());
+        // Six shares left, marked at the last price seen
+        assert_eq!(portfolio.market_value(), Money::from(720));
+        assert_eq!(portfolio.pnl(), "159.95".parse().unwrap());
+    }
+}
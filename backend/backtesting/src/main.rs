@@ -1,65 +1,114 @@
 mod backtest_engine;
-mod kafka_producer;
 mod strategy_runner;
 
+use backend::shared::config::load_config;
+use backend::shared::config::MarketData;
+use backend::shared::instrument::{load_instruments, Instrument};
+use backend::shared::message_bus::{Bus, TRADE_SIGNALS};
+use backend::shared::money::Money;
+use backend::shared::telemetry::{init_logging, CorrelationId};
 use backtest_engine::BacktestEngine;
-use kafka_producer::publish_trade_signal;
+use chrono::NaiveDate;
 use strategy_runner::run_strategy;
+use tracing::{debug, error, info, info_span, warn, Instrument as _};
 
 #[tokio::main]
 async fn main() {
-    println!("🔵 Backtesting Agent Started");
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            std::process::exit(1);
+        }
+    };
+    init_logging(&config.logging);
+    info!("Backtesting agent started");
+
+    let bus = match Bus::connect(&config).await {
+        Ok(bus) => bus,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
 
     // Define backtest parameters
-    let symbol = "AAPL"; // Set your test stock
-    let start_time = "2024-01-01T00:00:00Z";
-    let end_time = "2024-02-01T00:00:00Z";
-    let starting_cash = 10_000.0; // Initial portfolio balance
+    let instruments = match load_instruments(&config) {
+        Ok(instruments) => instruments,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let symbol = Instrument::Equity {
+        symbol: "AAPL".to_string(), // Set your test stock
+    };
+    let first_day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let last_day = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+    let starting_cash = Money::from(10_000); // Initial portfolio balance
+
+    // Snap the range to whole trading days on the symbol's exchange
+    let calendar = config.calendar.calendar(symbol.market());
+    let Some((start_time, end_time)) = calendar.trading_range(first_day, last_day) else {
+        warn!(
+            market = %calendar.market(),
+            %first_day,
+            %last_day,
+            "No trading days in backtest range"
+        );
+        return;
+    };
 
     // Initialize backtest engine with symbol, time range, and starting cash
-    let mut engine = BacktestEngine::new(symbol, start_time, end_time, starting_cash).await;
+    let mut engine = match BacktestEngine::new(
+        &config,
+        &instruments,
+        &symbol,
+        start_time,
+        end_time,
+        starting_cash,
+    )
+    .await
+    {
+        Ok(engine) => engine,
+        Err(err) => {
+            error!(error = %err, "Failed to load historical data");
+            std::process::exit(1);
+        }
+    };
 
-    println!(
-        "⏳ Running backtest from {} to {} on {}",
-        start_time, end_time, symbol
-    );
+    info!(start = %start_time, end = %end_time, %symbol, "Running backtest");
 
     // Get historical data count
     let historical_data_count = match engine.get_historical_data_count().await {
         Some(count) if count > 0 => count,
         _ => {
-            println!("⚠️ No historical data available! Exiting...");
+            warn!("No historical data available, exiting");
             return;
         }
     };
 
-    println!("📊 Total historical data points: {}", historical_data_count);
+    info!(
+        data_points = historical_data_count,
+        "Total historical data points"
+    );
 
     let mut total_data_points = 0;
 
     // Run strategy loop
     while let Some(market_data) = engine.get_next_market_data().await {
-        println!("📊 Processing market data: {:?}", market_data);
         total_data_points += 1;
 
-        // Run strategy logic on this market data
-        if let Some(trade_signal) = run_strategy(&market_data) {
-            println!("📈 Generated Trade Signal: {:?}", trade_signal);
-
-            // Execute the simulated trade within the backtest engine
-            engine.execute_trade(&trade_signal, &market_data);
-
-            // Publish trade signal to Kafka topic (for analysis or logging)
-            if let Err(e) = publish_trade_signal(&trade_signal).await {
-                eprintln!("❌ Failed to publish trade signal: {:?}", e);
-            } else {
-                println!("✅ Trade Signal Published: {:?}", trade_signal);
-            }
-        }
+        // Everything this bar leads to, here and in the execution agent, logs its ID
+        let correlation_id = CorrelationId::new();
+        let span = info_span!("bar", %correlation_id, symbol = %market_data.symbol);
+        process_bar(&mut engine, &bus, &market_data, &correlation_id)
+            .instrument(span)
+            .await;
 
         // Stop backtesting when all data has been processed
         if total_data_points >= historical_data_count {
-            println!("🏁 All historical data processed. Backtest complete.");
+            info!("All historical data processed, backtest complete");
             break;
         }
     }
@@ -67,3 +116,31 @@ async fn main() {
     // Print final backtest portfolio summary
     engine.print_summary();
 }
+
+/// Runs the strategy on one bar and trades and publishes any signal it generates
+async fn process_bar(
+    engine: &mut BacktestEngine,
+    bus: &Bus,
+    market_data: &MarketData,
+    correlation_id: &CorrelationId,
+) {
+    debug!(price = %market_data.price, "Processing market data");
+
+    // Run strategy logic on this market data
+    let Some(trade_signal) = run_strategy(market_data) else {
+        return;
+    };
+    info!(?trade_signal, "Generated trade signal");
+
+    // Execute the simulated trade within the backtest engine
+    engine.execute_trade(&trade_signal, market_data);
+
+    // Publish trade signal to the bus (for analysis or logging)
+    match bus
+        .publish_correlated(&TRADE_SIGNALS, &trade_signal, correlation_id)
+        .await
+    {
+        Ok(()) => debug!("Trade signal published"),
+        Err(e) => error!(error = %e, "Failed to publish trade signal"),
+    }
+}
@@ -1,21 +1,26 @@
-use backend::shared::config::{MarketData, TradeSignal};
+use backend::shared::config::{MarketData, SignalAction, TradeSignal};
+use backend::shared::money::Quantity;
+use tracing::trace;
+
+const ORDER_QTY: i64 = 10;
 
 pub fn run_strategy(market_data: &MarketData) -> Option<TradeSignal> {
-    println!("Running strategy on {:?}", market_data);
+    trace!(?market_data, "Running strategy");
 
-    if market_data.price > market_data.moving_average_50 {
+    let price = market_data.price.to_f64();
+    if price > market_data.moving_average_50 {
         return Some(TradeSignal {
             symbol: market_data.symbol.clone(),
-            qty: 10,
-            side: "buy".to_string(),
+            qty: Quantity::from(ORDER_QTY),
+            action: SignalAction::Buy,
         });
     }
 
-    if market_data.price < market_data.moving_average_200 {
+    if price < market_data.moving_average_200 {
         return Some(TradeSignal {
             symbol: market_data.symbol.clone(),
-            qty: 10,
-            side: "sell".to_string(),
+            qty: Quantity::from(ORDER_QTY),
+            action: SignalAction::Sell,
         });
     }
 
@@ -1,39 +1,184 @@
-mod kafka_consumer;
 mod order_executor;
 mod risk_checker;
+mod session_gate;
+mod signal_consumer;
 
-use kafka_consumer::consume_trade_signals;
-use order_executor::{cancel_order, place_order};
-use risk_checker::validate_trade;
+use backend::shared::clock::WallClock;
+use backend::shared::config::load_config;
+use backend::shared::dead_letter::DeadLetterQueue;
+use backend::shared::instrument::load_instruments;
+use backend::shared::message_bus::Bus;
+use backend::shared::metrics::ORDERS;
+use backend::shared::runtime::AgentRuntime;
+use backend::shared::telemetry::init_logging;
+use order_executor::OrderExecutor;
+use risk_checker::RiskChecker;
+use session_gate::SessionGate;
+use signal_consumer::{consume_trade_signals, ReceivedSignal, CONSUMER_NAME};
+use std::sync::Arc;
+use tracing::{error, info, info_span, warn, Instrument as _};
 
 #[tokio::main]
 async fn main() {
-    println!("Execution Agent Started - Connected to Alpaca");
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            std::process::exit(1);
+        }
+    };
+    init_logging(&config.logging);
+    let runtime = match AgentRuntime::start("execution_agent", &config).await {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            error!(listen = %config.runtime.listen, error = %err, "Failed to start health server");
+            std::process::exit(1);
+        }
+    };
+
+    // Live trading runs on the system clock; tests and replays substitute their own
+    let clock = WallClock::shared();
+    let executor = OrderExecutor::new(config.alpaca.clone(), clock.clone());
+    let instruments = match load_instruments(&config) {
+        Ok(instruments) => instruments,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let mut risk_checker = RiskChecker::new(instruments, clock.clone());
 
-    // Start consuming trade signals from Kafka
-    let mut trade_stream = consume_trade_signals().await;
+    info!(base_url = %config.alpaca.base_url, "Execution agent started");
 
-    while let Some(trade_signal) = trade_stream.recv().await {
-        println!("Received Trade Signal: {:?}", trade_signal);
+    // Start consuming trade signals from the message bus
+    let bus = Arc::new(match Bus::connect(&config).await {
+        Ok(bus) => bus,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    });
+    let probe_bus = Arc::clone(&bus);
+    runtime.watch("bus", move || {
+        let bus = Arc::clone(&probe_bus);
+        async move { bus.health_check().await.is_ok() }
+    });
+    // On shutdown the stream ends after the signals already taken off the bus
+    let mut trade_stream = match consume_trade_signals(&bus, runtime.shutdown()).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let mut session_gate = SessionGate::new(&config);
+
+    loop {
+        // Wake up for queued orders when their market opens
+        let release_at = session_gate.next_release();
+
+        tokio::select! {
+            received = trade_stream.recv() => {
+                let Some(received) = received else {
+                    break;
+                };
+                let span = signal_span(&received);
+                let admitted = span.in_scope(|| {
+                    info!(signal = ?received.signal, "Received trade signal");
+                    session_gate.admit(received, clock.now())
+                });
+                if let Some(received) = admitted {
+                    handle_signal(&executor, &mut risk_checker, received)
+                        .instrument(span)
+                        .await;
+                }
+            }
+            _ = clock.sleep_until(release_at.unwrap_or_default()), if release_at.is_some() => {
+                for received in session_gate.release_due(clock.now()) {
+                    let span = signal_span(&received);
+                    span.in_scope(|| info!("Market open, submitting queued signal"));
+                    handle_signal(&executor, &mut risk_checker, received)
+                        .instrument(span)
+                        .await;
+                }
+            }
+        }
+    }
+
+    // Their signals are already committed, so park them on the dead-letter
+    // topic where `optitrade_dlq` can replay them after a restart
+    if session_gate.queued() > 0 {
+        warn!(
+            queued = session_gate.queued(),
+            "Dead-lettering orders queued for the market open"
+        );
+        let dead_letters = DeadLetterQueue::new(&bus, CONSUMER_NAME);
+        for received in session_gate.drain() {
+            let span = signal_span(&received);
+            if let Err(err) = dead_letters
+                .send(&received.message, "stopped before the market opened")
+                .await
+            {
+                span.in_scope(|| error!(error = %err, "Failed to dead-letter queued order"));
+            }
+        }
+    }
+    if let Err(err) = bus.flush(runtime.shutdown_timeout()).await {
+        error!(error = %err, "Failed to flush the message bus");
+    }
+    info!("Execution agent stopped");
+}
+
+/// Span tagging every log line about one signal with its correlation ID
+fn signal_span(received: &ReceivedSignal) -> tracing::Span {
+    info_span!(
+        "signal",
+        correlation_id = %received.correlation_id,
+        symbol = %received.signal.symbol,
+        action = ?received.signal.action
+    )
+}
 
-        // Example: Check if an order should be canceled
-        if trade_signal.side == "CANCEL" {
-            println!("Cancelling order for: {:?}", trade_signal.symbol);
-            match cancel_order(&trade_signal.symbol).await {
-                Ok(_) => println!("Order canceled successfully."),
-                Err(e) => eprintln!("Order cancellation failed: {:?}", e),
+async fn handle_signal(
+    executor: &OrderExecutor,
+    risk_checker: &mut RiskChecker,
+    received: ReceivedSignal,
+) {
+    let trade_signal = &received.signal;
+    let side = match trade_signal.action.side() {
+        Some(side) => side,
+        None => {
+            info!("Cancelling order");
+            if let Err(e) = executor
+                .cancel_order(&trade_signal.symbol.alpaca_symbol())
+                .await
+            {
+                error!(error = %e, "Order cancellation failed");
             }
-            continue; // Skip placing a new order if it's a cancel signal
+            return; // Skip placing a new order if it's a cancel signal
         }
+    };
 
-        // Validate trade before execution
-        if validate_trade(&trade_signal) {
-            match place_order(&trade_signal.symbol, trade_signal.qty, &trade_signal.side).await {
-                Ok(_) => println!("Trade executed successfully."),
-                Err(e) => eprintln!("Trade execution failed: {:?}", e),
+    // Validate trade before execution
+    if risk_checker.validate_trade(trade_signal, side) {
+        match executor
+            .place_order(
+                &trade_signal.symbol,
+                trade_signal.qty,
+                side,
+                &received.correlation_id,
+            )
+            .await
+        {
+            Ok(_) => ORDERS.with_label_values(&["placed"]).inc(),
+            Err(e) => {
+                ORDERS.with_label_values(&["failed"]).inc();
+                error!(error = %e, "Trade execution failed");
             }
-        } else {
-            println!("Trade rejected by risk checker: {:?}", trade_signal);
         }
+    } else {
+        ORDERS.with_label_values(&["rejected"]).inc();
+        warn!(signal = ?trade_signal, "Trade rejected by risk checker");
     }
 }
@@ -1,66 +1,124 @@
-use backend::shared::config::load_config;
-use lazy_static::lazy_static;
+use backend::shared::clock::Clock;
+use backend::shared::config::{AlpacaConfig, Side};
+use backend::shared::instrument::Instrument;
+use backend::shared::money::{Price, Quantity};
+use backend::shared::telemetry::CorrelationId;
 use reqwest::Client;
-use serde_json::json; // Ensures config is loaded only once
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
 
-// Load config once and reuse it
-lazy_static! {
-    static ref CONFIG: backend::shared::config::Config = load_config();
+/// Longest correlation ID prefix kept in a client order ID; Alpaca allows 128 characters
+const MAX_CLIENT_ORDER_PREFIX: usize = 64;
+
+/// Places and cancels orders through the Alpaca REST API
+pub struct OrderExecutor {
+    client: Client,
+    config: AlpacaConfig,
+    clock: Arc<dyn Clock>, // Times each order from submission to acknowledgement
 }
 
-pub async fn place_order(
-    symbol: &str,
-    qty: i32,
-    side: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let alpaca_config = &CONFIG.alpaca; // Use preloaded config
+/// Alpaca's answer to a new order
+#[derive(Debug, Deserialize)]
+pub struct OrderAck {
+    pub id: String,
+    pub client_order_id: String,
+    pub status: String,
+    pub filled_qty: Option<Quantity>,
+    pub filled_avg_price: Option<Price>,
+}
 
-    let client = Client::new();
-    let url = format!("{}/orders", alpaca_config.base_url);
+impl OrderExecutor {
+    pub fn new(config: AlpacaConfig, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+            clock,
+        }
+    }
 
-    let order = json!({
-        "symbol": symbol,
-        "qty": qty,
-        "side": side,
-        "type": "market",
-        "time_in_force": "gtc"
-    });
+    /// Submits a market order whose client order ID starts with `correlation_id`, so
+    /// Alpaca's order and fill updates can be traced back to the signal. Alpaca requires
+    /// client order IDs to be unique, so each order adds its own suffix
+    pub async fn place_order(
+        &self,
+        instrument: &Instrument,
+        qty: Quantity,
+        side: Side,
+        correlation_id: &CorrelationId,
+    ) -> Result<OrderAck, Box<dyn std::error::Error>> {
+        let url = format!("{}/orders", self.config.base_url);
+        let client_order_id = client_order_id(correlation_id);
 
-    let response = client
-        .post(&url)
-        .header("APCA-API-KEY-ID", &alpaca_config.api_key)
-        .header("APCA-API-SECRET-KEY", &alpaca_config.api_secret)
-        .json(&order)
-        .send()
-        .await?;
+        let order = json!({
+            "symbol": instrument.alpaca_symbol(),
+            "qty": qty, // Sent as a decimal string, which also covers fractional shares
+            "side": side,
+            "type": "market",
+            "time_in_force": "gtc",
+            "client_order_id": client_order_id
+        });
 
-    if response.status().is_success() {
-        println!("Order placed successfully: {:?}", response.text().await?);
-    } else {
-        eprintln!("Order failed: {:?}", response.text().await?);
-    }
+        let submitted_at = self.clock.now();
+        let response = self
+            .client
+            .post(&url)
+            .header("APCA-API-KEY-ID", self.config.api_key.expose())
+            .header("APCA-API-SECRET-KEY", self.config.api_secret.expose())
+            .json(&order)
+            .send()
+            .await?;
 
-    Ok(())
-}
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(format!("Alpaca rejected order ({}): {}", status, body).into());
+        }
+
+        let ack: OrderAck = serde_json::from_str(&body)?;
+        info!(
+            order_id = %ack.id,
+            client_order_id = %ack.client_order_id,
+            %correlation_id,
+            status = %ack.status,
+            filled_qty = ?ack.filled_qty,
+            filled_avg_price = ?ack.filled_avg_price,
+            %submitted_at,
+            ack_ms = (self.clock.now() - submitted_at).num_milliseconds(),
+            "Order accepted"
+        );
+        Ok(ack)
+    }
 
-pub async fn cancel_order(order_id: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let alpaca_config = &CONFIG.alpaca; // Use preloaded config
+    pub async fn cancel_order(&self, order_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!("{}/orders/{}", self.config.base_url, order_id);
 
-    let client = Client::new();
-    let url = format!("{}/orders/{}", alpaca_config.base_url, order_id);
+        let response = self
+            .client
+            .delete(&url)
+            .header("APCA-API-KEY-ID", self.config.api_key.expose())
+            .header("APCA-API-SECRET-KEY", self.config.api_secret.expose())
+            .send()
+            .await?;
 
-    let response = client
-        .delete(&url)
-        .header("APCA-API-KEY-ID", &alpaca_config.api_key)
-        .header("APCA-API-SECRET-KEY", &alpaca_config.api_secret)
-        .send()
-        .await?;
+        if response.status().is_success() {
+            info!(order_id, "Order canceled");
+        } else {
+            warn!(order_id, response = %response.text().await?, "Failed to cancel order");
+        }
 
-    if response.status().is_success() {
-        println!("Order {} canceled successfully.", order_id);
-    } else {
-        eprintln!("Failed to cancel order: {:?}", response.text().await?);
+        Ok(())
     }
+}
 
-    Ok(())
+/// `<correlation ID>-<random suffix>`, unique for every order placed for a signal
+fn client_order_id(correlation_id: &CorrelationId) -> String {
+    let prefix: String = correlation_id
+        .as_str()
+        .chars()
+        .take(MAX_CLIENT_ORDER_PREFIX)
+        .collect();
+    format!("{}-{}", prefix, Uuid::new_v4().simple())
 }
@@ -1,17 +1,241 @@
-use backend::shared::config::TradeSignal;
+use backend::shared::clock::Clock;
+use backend::shared::config::{Side, TradeSignal};
+use backend::shared::instrument::InstrumentMaster;
+use backend::shared::metrics::RISK_REJECTIONS;
+use backend::shared::mmap_buffer::{SnapshotReader, SymbolSnapshot, DEFAULT_SNAPSHOT_PATH};
+use backend::shared::money::{Money, Price, Quantity};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
 
-pub fn validate_trade(trade_signal: &TradeSignal) -> bool {
-    // Example rule: Prevent trades over 1000 shares
-    if trade_signal.qty > 1000 {
-        println!("Trade rejected: Order size too large");
-        return false;
+const MAX_ORDER_QTY: i64 = 1000;
+const MAX_ORDER_NOTIONAL: i64 = 250_000;
+/// Quotes and trades older than this are not used to price an order
+const MAX_PRICE_AGE: Duration = Duration::from_secs(300);
+
+pub struct RiskChecker {
+    prices: Option<SnapshotReader>,
+    prices_path: PathBuf, // Shared price table written by market_data
+    instruments: InstrumentMaster,
+    clock: Arc<dyn Clock>, // Judges how old prices are
+}
+
+impl RiskChecker {
+    pub fn new(instruments: InstrumentMaster, clock: Arc<dyn Clock>) -> Self {
+        Self::with_price_table(DEFAULT_SNAPSHOT_PATH, instruments, clock)
+    }
+
+    /// Prices orders from the table at `path` instead of the default one
+    pub fn with_price_table(
+        path: impl Into<PathBuf>,
+        instruments: InstrumentMaster,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let prices_path = path.into();
+        RiskChecker {
+            prices: open_prices(&prices_path),
+            prices_path,
+            instruments,
+            clock,
+        }
+    }
+
+    pub fn validate_trade(&mut self, trade_signal: &TradeSignal, side: Side) -> bool {
+        if trade_signal.qty <= Quantity::ZERO {
+            warn!(qty = %trade_signal.qty, "Trade rejected: order size must be positive");
+            return reject("non_positive_qty");
+        }
+
+        let spec = self.instruments.spec(&trade_signal.symbol);
+        if !spec.is_valid_quantity(trade_signal.qty) {
+            warn!(
+                qty = %trade_signal.qty,
+                lot_size = %spec.lot_size,
+                "Trade rejected: quantity is not a multiple of the lot size"
+            );
+            return reject("lot_size");
+        }
+
+        // Example rule: Prevent trades over 1000 shares
+        if trade_signal.qty > Quantity::from(MAX_ORDER_QTY) {
+            warn!(
+                qty = %trade_signal.qty,
+                max = MAX_ORDER_QTY,
+                "Trade rejected: order size too large"
+            );
+            return reject("max_qty");
+        }
+
+        // Example rule: Prevent trading penny stocks
+        if trade_signal.symbol.underlying().starts_with("OTC") {
+            warn!(symbol = %trade_signal.symbol, "Trade rejected: penny stock");
+            return reject("penny_stock");
+        }
+
+        // market_data may have started after us
+        if self.prices.is_none() {
+            self.prices = open_prices(&self.prices_path);
+        }
+        let Some(prices) = &self.prices else {
+            warn!(
+                symbol = %trade_signal.symbol,
+                path = %self.prices_path.display(),
+                "Trade rejected: no shared price table"
+            );
+            return reject("no_price_table");
+        };
+
+        let snapshot = match prices.get(&trade_signal.symbol.alpaca_symbol()) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => {
+                warn!(symbol = %trade_signal.symbol, "Trade rejected: no market price");
+                return reject("no_price");
+            }
+            Err(err) => {
+                warn!(symbol = %trade_signal.symbol, error = %err, "Trade rejected: market price unreadable");
+                return reject("no_price");
+            }
+        };
+        let Some(price) = reference_price(&snapshot, side, self.clock.now_ns()) else {
+            if snapshot.has_quote() || snapshot.has_trade() {
+                warn!(
+                    symbol = %trade_signal.symbol,
+                    max_age_secs = MAX_PRICE_AGE.as_secs(),
+                    "Trade rejected: market price is stale"
+                );
+                return reject("stale_price");
+            }
+            warn!(symbol = %trade_signal.symbol, "Trade rejected: no market price");
+            return reject("no_price");
+        };
+
+        let notional = spec.notional(price, trade_signal.qty);
+        let limit = Money::from(MAX_ORDER_NOTIONAL);
+        if notional > limit {
+            warn!(
+                notional = %notional.round_cents(),
+                %limit,
+                "Trade rejected: notional exceeds limit"
+            );
+            return reject("max_notional");
+        }
+
+        info!(%price, notional = %notional.round_cents(), "Trade passed risk checks");
+        true
+    }
+}
+
+/// Counts a rejection by `rule`; always `false`
+fn reject(rule: &str) -> bool {
+    RISK_REJECTIONS.with_label_values(&[rule]).inc();
+    false
+}
+
+/// Price the order would most likely fill at: the far side of the quote,
+/// falling back to the last trade; either must be recent at `now_ns`
+fn reference_price(snapshot: &SymbolSnapshot, side: Side, now_ns: u64) -> Option<Price> {
+    let quoted = match side {
+        Side::Buy => snapshot.ask_price,
+        Side::Sell => snapshot.bid_price,
+    };
+    let fresh = |ts_ns: u64| now_ns.saturating_sub(ts_ns) <= MAX_PRICE_AGE.as_nanos() as u64;
+    if snapshot.has_quote() && fresh(snapshot.quote_ts_ns) && quoted > 0.0 {
+        Price::from_f64(quoted)
+    } else if snapshot.has_trade() && fresh(snapshot.trade_ts_ns) && snapshot.last_price > 0.0 {
+        Price::from_f64(snapshot.last_price)
+    } else {
+        None
     }
+}
 
-    // Example rule: Prevent trading penny stocks
-    if trade_signal.symbol.starts_with("OTC") {
-        println!("Trade rejected: Penny stock detected");
-        return false;
+fn open_prices(path: &Path) -> Option<SnapshotReader> {
+    match SnapshotReader::open(path) {
+        Ok(reader) => Some(reader),
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "Price table unavailable");
+            None
+        }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::shared::clock::ManualClock;
+    use backend::shared::config::SignalAction;
+    use backend::shared::instrument::Instrument;
+    use backend::shared::market_event::{Quote, Trade};
+    use backend::shared::mmap_buffer::SnapshotWriter;
+    use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+
+    fn signal(symbol: &str, qty: i64) -> TradeSignal {
+        TradeSignal {
+            symbol: Instrument::equity(symbol).unwrap(),
+            qty: Quantity::from(qty),
+            action: SignalAction::Buy,
+        }
+    }
+
+    #[test]
+    fn prices_go_stale_as_the_clock_moves() {
+        let path =
+            std::env::temp_dir().join(format!("optitrade_risk_prices_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let quoted_at = Utc.with_ymd_and_hms(2025, 3, 3, 15, 0, 0).unwrap();
+        let quoted_at_ns = quoted_at.timestamp_nanos_opt().unwrap() as u64;
+
+        let mut writer = SnapshotWriter::create(&path, 16).unwrap();
+        let quote = Quote {
+            bid_price: 99.0,
+            ask_price: 100.0,
+            bid_size: 100.0,
+            ask_size: 100.0,
+        };
+        writer.update_quote("AAPL", &quote, quoted_at_ns).unwrap();
+        let trade = Trade {
+            price: 50.0,
+            quantity: 10.0,
+            trade_id: 1,
+        };
+        writer.update_trade("MSFT", &trade, quoted_at_ns).unwrap();
 
-    true
+        let clock = ManualClock::new(quoted_at + ChronoDuration::seconds(10));
+        let mut checker =
+            RiskChecker::with_price_table(&path, InstrumentMaster::new(), Arc::new(clock.clone()));
+
+        assert!(checker.validate_trade(&signal("AAPL", 100), Side::Buy));
+        assert!(checker.validate_trade(&signal("MSFT", 100), Side::Buy));
+        // 3000 shares at the $100 ask is over the notional limit even under the size limit
+        assert!(!checker.validate_trade(&signal("AAPL", 3000), Side::Buy));
+        assert!(!checker.validate_trade(&signal("NVDA", 100), Side::Buy));
+
+        clock.set(quoted_at + ChronoDuration::from_std(MAX_PRICE_AGE).unwrap());
+        assert!(checker.validate_trade(&signal("AAPL", 100), Side::Buy));
+
+        clock.advance(ChronoDuration::seconds(1));
+        let stale_before = RISK_REJECTIONS.with_label_values(&["stale_price"]).get();
+        assert!(!checker.validate_trade(&signal("AAPL", 100), Side::Buy));
+        assert!(!checker.validate_trade(&signal("MSFT", 100), Side::Sell));
+        assert_eq!(
+            RISK_REJECTIONS.with_label_values(&["stale_price"]).get(),
+            stale_before + 2
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn rejects_orders_without_a_price_table() {
+        let path =
+            std::env::temp_dir().join(format!("optitrade_risk_missing_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2025, 3, 3, 15, 0, 0).unwrap());
+        let mut checker =
+            RiskChecker::with_price_table(&path, InstrumentMaster::new(), Arc::new(clock));
+
+        assert!(!checker.validate_trade(&signal("AAPL", 0), Side::Buy));
+        assert!(!checker.validate_trade(&signal("AAPL", 5000), Side::Buy));
+        assert!(!checker.validate_trade(&signal("AAPL", 100), Side::Buy));
+    }
 }
@@ -0,0 +1,196 @@
+use crate::signal_consumer::ReceivedSignal;
+use backend::shared::calendar::{Market, TradingCalendar};
+use backend::shared::config::{Config, OutOfSession, SignalAction};
+use backend::shared::instrument::Instrument;
+use backend::shared::metrics::ORDERS;
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+/// Holds orders back while their market is closed: rejects them, or queues
+/// them until the next open, depending on `[execution] out_of_session`
+pub struct SessionGate {
+    equities: TradingCalendar,
+    crypto: TradingCalendar,
+    extended_hours: bool,
+    policy: OutOfSession,
+    queued: Vec<(DateTime<Utc>, ReceivedSignal)>, // Sorted by release time
+}
+
+impl SessionGate {
+    pub fn new(config: &Config) -> Self {
+        SessionGate {
+            equities: config.calendar.calendar(Market::Nyse),
+            crypto: config.calendar.calendar(Market::Crypto),
+            extended_hours: config.calendar.extended_hours,
+            policy: config.execution.out_of_session,
+            queued: Vec::new(),
+        }
+    }
+
+    /// Returns the signal if it can go to the broker now.
+    /// Cancels always pass, and also drop any orders queued for the symbol.
+    pub fn admit(
+        &mut self,
+        received: ReceivedSignal,
+        now: DateTime<Utc>,
+    ) -> Option<ReceivedSignal> {
+        let signal = &received.signal;
+        if signal.action == SignalAction::Cancel {
+            let before = self.queued.len();
+            self.queued
+                .retain(|(_, queued)| queued.signal.symbol != signal.symbol);
+            if self.queued.len() < before {
+                info!(
+                    dropped = before - self.queued.len(),
+                    symbol = %signal.symbol,
+                    "Dropped queued orders"
+                );
+            }
+            return Some(received);
+        }
+
+        let calendar = self.calendar_for(&signal.symbol);
+        let session = calendar.session_at(now);
+        if session.is_open(self.extended_hours) {
+            return Some(received);
+        }
+
+        let next_open = calendar.next_open(now, self.extended_hours);
+        match (self.policy, next_open) {
+            (OutOfSession::Queue, Some(at)) => {
+                info!(%session, symbol = %signal.symbol, until = %at, "Market closed, order queued");
+                let index = self.queued.partition_point(|(release, _)| *release <= at);
+                self.queued.insert(index, (at, received));
+            }
+            _ => {
+                warn!(%session, ?signal, "Trade rejected: market closed");
+                ORDERS.with_label_values(&["rejected"]).inc();
+            }
+        }
+        None
+    }
+
+    /// Orders waiting for their market to open
+    pub fn queued(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// When the earliest queued order becomes due
+    pub fn next_release(&self) -> Option<DateTime<Utc>> {
+        self.queued.first().map(|(at, _)| *at)
+    }
+
+    /// Removes and returns every queued order due at `now`, oldest first
+    pub fn release_due(&mut self, now: DateTime<Utc>) -> Vec<ReceivedSignal> {
+        let due = self.queued.partition_point(|(at, _)| *at <= now);
+        self.queued.drain(..due).map(|(_, signal)| signal).collect()
+    }
+
+    /// Removes and returns every queued order, due or not
+    pub fn drain(&mut self) -> Vec<ReceivedSignal> {
+        self.queued.drain(..).map(|(_, signal)| signal).collect()
+    }
+
+    fn calendar_for(&self, instrument: &Instrument) -> &TradingCalendar {
+        match instrument.market() {
+            Market::Crypto => &self.crypto,
+            Market::Nyse | Market::Nasdaq => &self.equities,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::shared::clock::{Clock, ManualClock};
+    use backend::shared::config::TradeSignal;
+    use backend::shared::message_bus::Message;
+    use backend::shared::money::Quantity;
+    use backend::shared::telemetry::CorrelationId;
+    use chrono::{Duration, TimeZone};
+
+    fn received(symbol: Instrument, action: SignalAction) -> ReceivedSignal {
+        ReceivedSignal {
+            signal: TradeSignal {
+                symbol,
+                qty: Quantity::from(10),
+                action,
+            },
+            correlation_id: CorrelationId::default(),
+            message: Message::new("trade_signals", Vec::new()),
+        }
+    }
+
+    fn gate(policy: OutOfSession) -> SessionGate {
+        let mut config = Config::default();
+        config.execution.out_of_session = policy;
+        SessionGate::new(&config)
+    }
+
+    #[tokio::test]
+    async fn queued_orders_are_released_at_the_next_open() {
+        let aapl = Instrument::equity("AAPL").unwrap();
+        // Friday evening after the close; DST starts over the weekend
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2025, 3, 7, 22, 0, 0).unwrap());
+        let mut gate = gate(OutOfSession::Queue);
+
+        assert!(gate
+            .admit(received(aapl.clone(), SignalAction::Buy), clock.now())
+            .is_none());
+        let btc = Instrument::crypto("BTC", "USD").unwrap();
+        assert!(gate
+            .admit(received(btc, SignalAction::Buy), clock.now())
+            .is_some());
+        assert_eq!(gate.queued(), 1);
+
+        // 09:30 New York is 13:30 UTC once daylight saving time has started
+        let open = Utc.with_ymd_and_hms(2025, 3, 10, 13, 30, 0).unwrap();
+        assert_eq!(gate.next_release(), Some(open));
+
+        let sleeper = {
+            let clock = clock.clone();
+            tokio::spawn(async move { clock.sleep_until(open).await })
+        };
+        clock.set(open - Duration::seconds(1));
+        assert!(gate.release_due(clock.now()).is_empty());
+        tokio::task::yield_now().await;
+        assert!(!sleeper.is_finished());
+
+        clock.set(open);
+        sleeper.await.unwrap();
+        let released = gate.release_due(clock.now());
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].signal.symbol, aapl);
+        assert_eq!(gate.queued(), 0);
+        assert_eq!(gate.next_release(), None);
+
+        assert!(gate
+            .admit(received(aapl, SignalAction::Sell), clock.now())
+            .is_some());
+    }
+
+    #[test]
+    fn cancels_drop_queued_orders_and_reject_policy_queues_nothing() {
+        let aapl = Instrument::equity("AAPL").unwrap();
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2025, 3, 8, 15, 0, 0).unwrap());
+
+        let mut queueing = gate(OutOfSession::Queue);
+        queueing.admit(received(aapl.clone(), SignalAction::Buy), clock.now());
+        queueing.admit(
+            received(Instrument::equity("MSFT").unwrap(), SignalAction::Buy),
+            clock.now(),
+        );
+        assert_eq!(queueing.queued(), 2);
+        assert!(queueing
+            .admit(received(aapl.clone(), SignalAction::Cancel), clock.now())
+            .is_some());
+        assert_eq!(queueing.queued(), 1);
+        assert_eq!(queueing.drain().len(), 1);
+
+        let mut rejecting = gate(OutOfSession::Reject);
+        assert!(rejecting
+            .admit(received(aapl, SignalAction::Buy), clock.now())
+            .is_none());
+        assert_eq!(rejecting.queued(), 0);
+    }
+}
@@ -0,0 +1,88 @@
+use backend::shared::config::TradeSignal;
+use backend::shared::dead_letter::DeadLetterQueue;
+use backend::shared::message_bus::{Bus, BusError, Message, TRADE_SIGNALS};
+use backend::shared::runtime::Shutdown;
+use backend::shared::telemetry::CorrelationId;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+
+pub const CONSUMER_NAME: &str = "execution_agent";
+
+/// A trade signal with the correlation ID it was published under
+#[derive(Debug, Clone)]
+pub struct ReceivedSignal {
+    pub signal: TradeSignal,
+    pub correlation_id: CorrelationId,
+    pub message: Message, // As received, so an order that is never placed can be dead-lettered
+}
+
+/// Forwards signals until `shutdown` fires; the channel then closes once the
+/// subscription has committed and left the group
+pub async fn consume_trade_signals(
+    bus: &Bus,
+    shutdown: Shutdown,
+) -> Result<mpsc::Receiver<ReceivedSignal>, BusError> {
+    let (tx, rx) = mpsc::channel(100);
+    let mut subscription = bus.subscribe(&TRADE_SIGNALS, CONSUMER_NAME).await?;
+    let dead_letters = DeadLetterQueue::new(bus, CONSUMER_NAME);
+
+    tokio::spawn(async move {
+        loop {
+            let delivery = tokio::select! {
+                delivery = subscription.recv() => match delivery {
+                    Some(delivery) => delivery,
+                    None => break,
+                },
+                _ = shutdown.wait() => {
+                    info!("Stopping trade signal consumer");
+                    break;
+                }
+            };
+            let delivery = match delivery {
+                Ok(delivery) => delivery,
+                Err(e) => {
+                    error!(error = %e, "Message bus error");
+                    continue;
+                }
+            };
+
+            match delivery.value {
+                Ok(signal) => {
+                    // Signals from publishers that don't tag them still get an ID from here on
+                    let correlation_id = delivery.message.correlation_id().unwrap_or_default();
+                    let received = ReceivedSignal {
+                        signal,
+                        correlation_id,
+                        message: delivery.message.clone(),
+                    };
+                    if tx.send(received).await.is_err() {
+                        debug!("Receiver dropped, stopping trade signal consumer");
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        correlation_id = delivery.message.correlation_id().as_ref().map(|id| id.as_str()),
+                        error = %e,
+                        payload = %String::from_utf8_lossy(&delivery.message.payload),
+                        "Rejected trade signal"
+                    );
+                    if let Err(e) = dead_letters.send(&delivery.message, &e).await {
+                        error!(error = %e, "Failed to dead-letter trade signal");
+                    }
+                }
+            }
+
+            if let Err(e) = subscription.commit(&delivery.message).await {
+                error!(error = %e, "Failed to commit trade signal");
+            }
+        }
+
+        if let Err(e) = subscription.close().await {
+            error!(error = %e, "Failed to close trade signal subscription");
+        }
+        // `tx` drops here, ending the receiver after the signals already sent
+    });
+
+    Ok(rx)
+}
@@ -1,97 +1,765 @@
+use crate::backoff::Backoff;
+use crate::feed::FeedMessage;
 use backend::shared::config::AlpacaConfig;
+use backend::shared::instrument::{Instrument, InstrumentKind};
+use backend::shared::market_event::{
+    now_ns, EventPayload, Gap, MarketEvent, Quote, Status, Trade, TradingStatus,
+};
+use chrono::DateTime;
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
-use reqwest::Client;
 use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
-use tokio::time::{sleep, Duration};
-use tokio_tungstenite::connect_async;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep_until, timeout_at, Duration, Instant};
 use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{connect_async, tungstenite, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
 
-/// Connects to Alpaca WebSocket and streams live market data
-pub async fn stream_alpaca_market_data(
+// One supervised WebSocket connection per feed carries every symbol on it:
+//
+//   connect -> auth (wait for "authenticated") -> subscribe all -> read
+//
+// `AlpacaStream` handles add and remove symbols at any time; changes queued
+// together go out as one subscribe and one unsubscribe message. Quotes,
+// trades and trading statuses are mapped to `MarketEvent`s here, and anything
+// for a symbol no longer subscribed is dropped.
+//
+// A dropped, silent or refused connection is retried with jittered
+// exponential backoff and the whole set is subscribed again. Once the feed is
+// back, each symbol gets a `Gap` event covering the outage. Alpaca errors
+// that a retry cannot fix (bad credentials, subscription limits) end the
+// stream instead.
+
+/// First reconnect delay; doubles per failed attempt up to `MAX_BACKOFF`
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A session that lasted this long resets the backoff
+const STABLE_SESSION: Duration = Duration::from_secs(60);
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often we ping; Alpaca answers with a pong
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+/// No frame at all, not even a pong, for this long means the connection is dead
+const READ_TIMEOUT: Duration = Duration::from_secs(45);
+/// Symbols per subscribe or unsubscribe message
+const SUBSCRIBE_BATCH: usize = 100;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Alpaca's real-time data feeds, one connection each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlpacaFeed {
+    Stocks,
+    Crypto,
+}
+
+impl AlpacaFeed {
+    /// The feed that streams `instrument`; option chains are polled instead
+    pub fn for_instrument(instrument: &Instrument) -> Option<Self> {
+        match instrument.kind() {
+            InstrumentKind::Equity => Some(AlpacaFeed::Stocks),
+            InstrumentKind::Crypto => Some(AlpacaFeed::Crypto),
+            InstrumentKind::Option => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlpacaFeed::Stocks => "stocks",
+            AlpacaFeed::Crypto => "crypto",
+        }
+    }
+
+    /// Stream channels we subscribe each symbol to; crypto has no trading statuses
+    fn channels(&self) -> &'static [&'static str] {
+        match self {
+            AlpacaFeed::Stocks => &["trades", "quotes", "statuses"],
+            AlpacaFeed::Crypto => &["trades", "quotes"],
+        }
+    }
+
+    fn url<'a>(&self, config: &'a AlpacaConfig) -> &'a str {
+        match self {
+            AlpacaFeed::Stocks => &config.websocket_url,
+            AlpacaFeed::Crypto => &config.crypto_websocket_url,
+        }
+    }
+}
+
+impl fmt::Display for AlpacaFeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    WebSocket(tungstenite::Error),
+    /// An `{"T": "error"}` message from Alpaca
+    Alpaca {
+        code: i64,
+        msg: String,
+    },
+    Timeout(&'static str),
+    Closed,
+}
+
+impl StreamError {
+    /// Credentials were refused
+    pub fn is_auth_failure(&self) -> bool {
+        matches!(
+            self,
+            StreamError::Alpaca {
+                code: 401 | 402,
+                ..
+            }
+        )
+    }
+
+    /// Reconnecting cannot help: bad credentials, symbol limit, no access to the feed
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            StreamError::Alpaca {
+                code: 401 | 402 | 405 | 408 | 409 | 410,
+                ..
+            }
+        )
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::WebSocket(e) => write!(f, "WebSocket error: {}", e),
+            StreamError::Alpaca { code, msg } if self.is_auth_failure() => {
+                write!(f, "Alpaca authentication failed ({}): {}", code, msg)
+            }
+            StreamError::Alpaca { code, msg } => write!(f, "Alpaca error {}: {}", code, msg),
+            StreamError::Timeout(stage) => write!(f, "timed out waiting for {}", stage),
+            StreamError::Closed => write!(f, "connection closed by server"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<tungstenite::Error> for StreamError {
+    fn from(e: tungstenite::Error) -> Self {
+        StreamError::WebSocket(e)
+    }
+}
+
+enum Command {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+/// Handle to the connection for one feed; clones share it
+#[derive(Clone)]
+pub struct AlpacaStream {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl AlpacaStream {
+    /// Starts the connection for `feed`. It runs until the receiver behind
+    /// `sender` is dropped, and fails only if Alpaca refuses us for good.
+    pub fn spawn(
+        feed: AlpacaFeed,
+        config: AlpacaConfig,
+        sender: mpsc::Sender<FeedMessage>,
+    ) -> (Self, JoinHandle<Result<(), StreamError>>) {
+        let (commands, rx) = mpsc::unbounded_channel();
+        let connection = Connection {
+            feed,
+            config,
+            sender,
+            commands: Some(rx),
+            symbols: BTreeSet::new(),
+            last_seen_ns: HashMap::new(),
+        };
+        let handle = tokio::spawn(connection.supervise());
+        (Self { commands }, handle)
+    }
+
+    /// Adds symbols, in Alpaca's format (`AAPL`, `BTC/USD`)
+    pub fn subscribe(&self, symbols: impl IntoIterator<Item = String>) {
+        // Only fails once the connection task has ended, which it logs
+        let _ = self
+            .commands
+            .send(Command::Subscribe(symbols.into_iter().collect()));
+    }
+
+    pub fn unsubscribe(&self, symbols: impl IntoIterator<Item = String>) {
+        let _ = self
+            .commands
+            .send(Command::Unsubscribe(symbols.into_iter().collect()));
+    }
+}
+
+/// State of one feed's connection, kept across reconnects
+struct Connection {
+    feed: AlpacaFeed,
+    config: AlpacaConfig,
+    sender: mpsc::Sender<FeedMessage>,
+    commands: Option<mpsc::UnboundedReceiver<Command>>, // None once every handle is dropped
+    symbols: BTreeSet<String>,
+    last_seen_ns: HashMap<String, u64>, // Latest message per symbol, to date a gap
+}
+
+impl Connection {
+    /// Runs sessions back to back until the receiver is gone or an error is fatal
+    async fn supervise(mut self) -> Result<(), StreamError> {
+        let mut backoff = Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF);
+        // Symbols that were streaming when the last session dropped, and since when
+        let mut gaps: BTreeMap<String, u64> = BTreeMap::new();
+
+        loop {
+            let started = Instant::now();
+            let started_ns = now_ns();
+            let err = match self.run_session(&mut gaps).await {
+                Ok(()) => return Ok(()), // Receiver dropped
+                Err(err) => err,
+            };
+            if err.is_fatal() {
+                error!(feed = %self.feed, error = %err, "Alpaca refused the stream, not reconnecting");
+                return Err(err);
+            }
+
+            // Symbols still waiting for their gap from an earlier drop keep the earlier start
+            for symbol in &self.symbols {
+                let since = self.last_seen_ns.get(symbol).copied().unwrap_or(started_ns);
+                gaps.entry(symbol.clone()).or_insert(since);
+            }
+            if started.elapsed() >= STABLE_SESSION {
+                backoff.reset();
+            }
+            let delay = backoff.next_delay();
+            warn!(
+                feed = %self.feed,
+                symbols = self.symbols.len(),
+                error = %err,
+                retry_in_ms = delay.as_millis() as u64,
+                "Alpaca stream disconnected"
+            );
+
+            let retry_at = Instant::now() + delay;
+            loop {
+                tokio::select! {
+                    _ = sleep_until(retry_at) => break,
+                    _ = self.sender.closed() => return Ok(()),
+                    command = recv_command(&mut self.commands) => {
+                        // Nothing to send while disconnected; the next session subscribes the set
+                        let _ = self.apply(command);
+                    }
+                }
+            }
+            gaps.retain(|symbol, _| self.symbols.contains(symbol));
+        }
+    }
+
+    /// One connection: authenticates, subscribes, then forwards messages until
+    /// it fails; `gaps` are announced and cleared once the feed is back
+    async fn run_session(&mut self, gaps: &mut BTreeMap<String, u64>) -> Result<(), StreamError> {
+        let url = self.feed.url(&self.config);
+        info!(feed = %self.feed, %url, "Connecting to Alpaca WebSocket");
+        let (ws_stream, _) = connect_async(url).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        authenticate(&self.config, &mut write, &mut read).await?;
+        let symbols: Vec<String> = self.symbols.iter().cloned().collect();
+        send_action(&mut write, self.feed, "subscribe", &symbols).await?;
+        info!(feed = %self.feed, symbols = symbols.len(), "Subscribed to Alpaca market data");
+
+        // Tell consumers what they missed while we were away
+        for (symbol, since_ns) in std::mem::take(gaps) {
+            let gap = MarketEvent::new(symbol, now_ns(), EventPayload::Gap(Gap { since_ns }));
+            if self.sender.send(FeedMessage::Event(gap)).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        ping.tick().await; // The first tick is immediate
+        let mut read_deadline = Instant::now() + READ_TIMEOUT;
+
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    read_deadline = Instant::now() + READ_TIMEOUT;
+                    match msg {
+                        Some(Ok(Message::Text(text))) => {
+                            if !self.forward(&text).await? {
+                                return Ok(()); // Shutting down
+                            }
+                        }
+                        Some(Ok(Message::Close(_))) | None => return Err(StreamError::Closed),
+                        Some(Ok(_)) => {} // Pings are answered by tungstenite; pongs only prove liveness
+                        Some(Err(e)) => return Err(e.into()),
+                    }
+                }
+                command = recv_command(&mut self.commands) => {
+                    // Take everything queued so it goes out in one pair of messages
+                    let mut changes = self.apply(command);
+                    while let Some(command) = self.commands.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                        changes.merge(self.apply(Some(command)));
+                    }
+                    send_action(&mut write, self.feed, "subscribe", &changes.added).await?;
+                    send_action(&mut write, self.feed, "unsubscribe", &changes.removed).await?;
+                }
+                _ = ping.tick() => write.send(Message::Ping(Vec::new())).await?,
+                _ = self.sender.closed() => return Ok(()),
+                _ = sleep_until(read_deadline) => return Err(StreamError::Timeout("data or pong")),
+            }
+        }
+    }
+
+    /// Updates the subscription set; returns what actually changed
+    fn apply(&mut self, command: Option<Command>) -> Changes {
+        let mut changes = Changes::default();
+        match command {
+            Some(Command::Subscribe(symbols)) => {
+                for symbol in symbols {
+                    if self.symbols.insert(symbol.clone()) {
+                        changes.added.push(symbol);
+                    }
+                }
+            }
+            Some(Command::Unsubscribe(symbols)) => {
+                for symbol in symbols {
+                    if self.symbols.remove(&symbol) {
+                        self.last_seen_ns.remove(&symbol);
+                        changes.removed.push(symbol);
+                    }
+                }
+            }
+            None => self.commands = None,
+        }
+        changes
+    }
+
+    /// Maps a frame to events and sends them on; `false` once the receiver is gone
+    async fn forward(&mut self, text: &str) -> Result<bool, StreamError> {
+        let messages: Vec<Value> = match serde_json::from_str(text) {
+            Ok(messages) => messages,
+            Err(_) => return Ok(self.send(FeedMessage::Unparsed(text.to_string())).await),
+        };
+
+        for message in messages {
+            let feed_message = match message["T"].as_str() {
+                Some("error") => return Err(alpaca_error(&message)),
+                Some("success") | Some("subscription") => {
+                    debug!(feed = %self.feed, %message, "Alpaca control message");
+                    continue;
+                }
+                kind => match parse_alpaca_event(&message) {
+                    Some(event) => {
+                        // In flight when it was unsubscribed
+                        if !self.symbols.contains(&event.symbol) {
+                            continue;
+                        }
+                        self.last_seen_ns.insert(event.symbol.clone(), now_ns());
+                        FeedMessage::Event(event)
+                    }
+                    // A quote or trade we cannot read is worth keeping
+                    None if matches!(kind, Some("q") | Some("t")) => {
+                        FeedMessage::Unparsed(message.to_string())
+                    }
+                    // Other status codes (imbalances, short sale restrictions) and message types
+                    None => {
+                        debug!(feed = %self.feed, %message, "Ignoring Alpaca message");
+                        continue;
+                    }
+                },
+            };
+            if !self.send(feed_message).await {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn send(&self, message: FeedMessage) -> bool {
+        self.sender.send(message).await.is_ok()
+    }
+}
+
+/// Subscription changes from a batch of commands
+#[derive(Default)]
+struct Changes {
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl Changes {
+    fn merge(&mut self, other: Changes) {
+        for symbol in other.added {
+            match self.removed.iter().position(|s| *s == symbol) {
+                Some(i) => {
+                    self.removed.swap_remove(i);
+                }
+                None => self.added.push(symbol),
+            }
+        }
+        for symbol in other.removed {
+            match self.added.iter().position(|s| *s == symbol) {
+                Some(i) => {
+                    self.added.swap_remove(i);
+                }
+                None => self.removed.push(symbol),
+            }
+        }
+    }
+}
+
+/// The next command, or never once every handle is gone
+async fn recv_command(commands: &mut Option<mpsc::UnboundedReceiver<Command>>) -> Option<Command> {
+    match commands {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Sends a subscribe or unsubscribe for every channel of `feed`
+async fn send_action(
+    write: &mut SplitSink<WsStream, Message>,
+    feed: AlpacaFeed,
+    action: &str,
+    symbols: &[String],
+) -> Result<(), StreamError> {
+    for msg in action_messages(feed, action, symbols) {
+        write.send(Message::Text(msg.to_string())).await?;
+    }
+    Ok(())
+}
+
+/// The messages for `action` on `symbols`, `SUBSCRIBE_BATCH` symbols at a time
+fn action_messages(feed: AlpacaFeed, action: &str, symbols: &[String]) -> Vec<Value> {
+    symbols
+        .chunks(SUBSCRIBE_BATCH)
+        .map(|batch| {
+            let mut msg = serde_json::Map::new();
+            msg.insert("action".to_string(), action.into());
+            for channel in feed.channels() {
+                msg.insert(channel.to_string(), batch.into());
+            }
+            Value::Object(msg)
+        })
+        .collect()
+}
+
+/// Sends credentials and waits for Alpaca to accept them
+async fn authenticate(
     config: &AlpacaConfig,
-    symbol: &str,
-    sender: mpsc::Sender<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let url = &config.websocket_url;
-    println!("[Alpaca] 🔗 Connecting to WebSocket: {}", url);
-
-    let (ws_stream, _) = connect_async(url)
-        .await
-        .expect("Failed to connect to Alpaca WebSocket");
-    let (mut write, mut read) = ws_stream.split();
-
-    // Authenticate with Alpaca WebSocket
+    write: &mut SplitSink<WsStream, Message>,
+    read: &mut SplitStream<WsStream>,
+) -> Result<(), StreamError> {
     let auth_msg = serde_json::json!({
         "action": "auth",
-        "key": config.api_key,
-        "secret": config.api_secret
+        "key": config.api_key.expose(),
+        "secret": config.api_secret.expose()
     })
     .to_string();
-    write
-        .send(Message::Text(auth_msg))
-        .await
-        .expect("Failed to send auth");
-
-    // Subscribe to real-time market data
-    let subscribe_msg = serde_json::json!({
-        "action": "subscribe",
-        "trades": [symbol],
-        "quotes": [symbol]
-    })
-    .to_string();
-    write
-        .send(Message::Text(subscribe_msg))
-        .await
-        .expect("Failed to send subscribe");
-
-    println!("[Alpaca] ✅ Subscribed to market data for {}", symbol);
-
-    // Spawn a task to periodically fetch options chain
-    let options_config = config.clone();
-    let symbol_clone = symbol.to_string();
-    let sender_clone = sender.clone();
-    tokio::spawn(async move {
-        loop {
-            match fetch_alpaca_options_chain(&options_config, &symbol_clone).await {
-                Ok(options_data) => {
-                    let json_str = serde_json::to_string(&options_data).unwrap();
-                    sender_clone
-                        .send(json_str)
-                        .await
-                        .expect("Failed to send options data");
+    write.send(Message::Text(auth_msg)).await?;
+
+    // Alpaca first greets with "connected", then answers the auth message
+    let deadline = Instant::now() + AUTH_TIMEOUT;
+    loop {
+        let msg = timeout_at(deadline, read.next())
+            .await
+            .map_err(|_| StreamError::Timeout("authentication"))?;
+        match msg {
+            Some(Ok(Message::Text(text))) => {
+                let messages: Vec<Value> = serde_json::from_str(&text).unwrap_or_default();
+                if let Some(error) = messages.iter().find(|m| m["T"] == "error") {
+                    return Err(alpaca_error(error));
+                }
+                if messages
+                    .iter()
+                    .any(|m| m["T"] == "success" && m["msg"] == "authenticated")
+                {
+                    return Ok(());
                 }
-                Err(err) => eprintln!("[Alpaca] ❌ Error fetching options data: {}", err),
             }
-            sleep(Duration::from_secs(30)).await; // Fetch options data every 30 seconds
+            Some(Ok(Message::Close(_))) | None => return Err(StreamError::Closed),
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e.into()),
         }
-    });
+    }
+}
 
-    // Read messages from WebSocket
-    while let Some(msg) = read.next().await {
-        if let Ok(Message::Text(text)) = msg {
-            sender.send(text).await.expect("Failed to send market data");
-        }
+/// `{"T": "error", "code": ..., "msg": ...}` as an error
+fn alpaca_error(message: &Value) -> StreamError {
+    StreamError::Alpaca {
+        code: message["code"].as_i64().unwrap_or_default(),
+        msg: message["msg"].as_str().unwrap_or_default().to_string(),
     }
+}
 
-    Ok(())
+/// Maps an Alpaca stream message (`T` = "q", "t" or "s") to a `MarketEvent`.
+/// Control messages such as `success` and `subscription`, and status codes
+/// other than halts, pauses and resumptions, yield `None`.
+pub fn parse_alpaca_event(msg: &Value) -> Option<MarketEvent> {
+    let symbol = msg["S"].as_str()?;
+    let exchange_ts_ns = msg["t"]
+        .as_str()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .and_then(|dt| dt.timestamp_nanos_opt())
+        .map(|ns| ns as u64)?;
+
+    let payload = match msg["T"].as_str()? {
+        "q" => EventPayload::Quote(Quote {
+            bid_price: msg["bp"].as_f64()?,
+            ask_price: msg["ap"].as_f64()?,
+            bid_size: msg["bs"].as_f64().unwrap_or(0.0),
+            ask_size: msg["as"].as_f64().unwrap_or(0.0),
+        }),
+        "t" => EventPayload::Trade(Trade {
+            price: msg["p"].as_f64()?,
+            quantity: msg["s"].as_f64()?,
+            trade_id: msg["i"].as_u64().unwrap_or(0),
+        }),
+        "s" => EventPayload::Status(Status {
+            // CTA and UTP codes, both of which Alpaca passes through
+            status: match msg["sc"].as_str()? {
+                "T" | "3" => TradingStatus::Trading,
+                "H" | "2" => TradingStatus::Halted,
+                "P" => TradingStatus::Paused,
+                "Q" => TradingStatus::QuotationOnly,
+                _ => return None,
+            },
+            reason: match msg["rm"].as_str() {
+                Some(reason) if !reason.is_empty() => reason.to_string(),
+                _ => msg["sm"].as_str().unwrap_or_default().to_string(),
+            },
+        }),
+        _ => return None,
+    };
+
+    Some(MarketEvent::new(symbol, exchange_ts_ns, payload))
 }
 
-/// Fetches options chain from Alpaca REST API
-pub async fn fetch_alpaca_options_chain(
-    config: &AlpacaConfig,
-    symbol: &str,
-) -> Result<Value, reqwest::Error> {
-    let client = Client::new();
-    let options_url = format!("{}/v2/options?symbol={}", config.base_url, symbol);
-
-    let res = client
-        .get(&options_url)
-        .header("APCA-API-KEY-ID", &config.api_key)
-        .header("APCA-API-SECRET-KEY", &config.api_secret)
-        .send()
-        .await?;
-
-    let options_json: Value = res.json().await?;
-    Ok(options_json)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TS: &str = "2025-03-03T14:30:00.123456789Z";
+
+    fn ts_ns() -> u64 {
+        DateTime::parse_from_rfc3339(TS)
+            .unwrap()
+            .timestamp_nanos_opt()
+            .unwrap() as u64
+    }
+
+    /// A connection streaming `symbols`, and what it forwards
+    fn connection(symbols: &[&str]) -> (Connection, mpsc::Receiver<FeedMessage>) {
+        let (sender, rx) = mpsc::channel(16);
+        let connection = Connection {
+            feed: AlpacaFeed::Stocks,
+            config: AlpacaConfig::default(),
+            sender,
+            commands: None,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            last_seen_ns: HashMap::new(),
+        };
+        (connection, rx)
+    }
+
+    fn drain(rx: &mut mpsc::Receiver<FeedMessage>) -> Vec<FeedMessage> {
+        let mut messages = Vec::new();
+        while let Ok(message) = rx.try_recv() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn parses_quotes_trades_and_statuses() {
+        let quote =
+            json!({"T": "q", "S": "AAPL", "bp": 187.3, "bs": 2, "ap": 187.35, "as": 5, "t": TS});
+        let event = parse_alpaca_event(&quote).unwrap();
+        assert_eq!(event.symbol, "AAPL");
+        assert_eq!(event.exchange_ts_ns, ts_ns());
+        assert_eq!(
+            event.payload,
+            EventPayload::Quote(Quote {
+                bid_price: 187.3,
+                ask_price: 187.35,
+                bid_size: 2.0,
+                ask_size: 5.0,
+            })
+        );
+
+        let trade = json!({"T": "t", "S": "BTC/USD", "p": 64000.5, "s": 0.25, "i": 9001, "t": TS});
+        assert_eq!(
+            parse_alpaca_event(&trade).unwrap().payload,
+            EventPayload::Trade(Trade {
+                price: 64000.5,
+                quantity: 0.25,
+                trade_id: 9001,
+            })
+        );
+
+        let halt = json!({"T": "s", "S": "AAPL", "sc": "H", "sm": "Trading Halt", "rc": "LUDP", "rm": "Volatility Trading Pause", "t": TS});
+        assert_eq!(
+            parse_alpaca_event(&halt).unwrap().payload,
+            EventPayload::Status(Status {
+                status: TradingStatus::Halted,
+                reason: "Volatility Trading Pause".to_string(),
+            })
+        );
+        let resumed = json!({"T": "s", "S": "AAPL", "sc": "3", "sm": "Resume", "rm": "", "t": TS});
+        assert_eq!(
+            parse_alpaca_event(&resumed).unwrap().payload,
+            EventPayload::Status(Status {
+                status: TradingStatus::Trading,
+                reason: "Resume".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_incomplete_messages() {
+        for message in [
+            json!({"T": "s", "S": "AAPL", "sc": "I", "sm": "Order Imbalance", "t": TS}),
+            json!({"T": "b", "S": "AAPL", "o": 1.0, "t": TS}),
+            json!({"T": "success", "msg": "authenticated"}),
+            json!({"T": "q", "S": "AAPL", "ap": 187.35, "t": TS}),
+            json!({"T": "t", "S": "AAPL", "p": 187.3, "s": 100, "t": "yesterday"}),
+            json!({"T": "t", "p": 187.3, "s": 100, "t": TS}),
+        ] {
+            assert!(parse_alpaca_event(&message).is_none(), "{}", message);
+        }
+    }
+
+    #[tokio::test]
+    async fn forwards_events_and_keeps_unreadable_quotes_and_trades() {
+        let (mut connection, mut rx) = connection(&["AAPL"]);
+        let frame = json!([
+            {"T": "subscription", "quotes": ["AAPL"]},
+            {"T": "q", "S": "AAPL", "bp": 187.3, "ap": 187.35, "t": TS},
+            {"T": "t", "S": "AAPL", "p": "oops", "s": 100, "t": TS},
+            {"T": "q", "S": "MSFT", "bp": 410.0, "ap": 410.1, "t": TS},
+            {"T": "s", "S": "AAPL", "sc": "I", "t": TS},
+            {"T": "d", "S": "AAPL"}
+        ]);
+        assert!(connection.forward(&frame.to_string()).await.unwrap());
+
+        let messages = drain(&mut rx);
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(&messages[0], FeedMessage::Event(event) if event.symbol == "AAPL"));
+        match &messages[1] {
+            FeedMessage::Unparsed(text) => assert!(text.contains("oops")),
+            other => panic!("expected the bad trade to be kept, got {:?}", other),
+        }
+        assert!(connection.last_seen_ns.contains_key("AAPL"));
+        assert!(!connection.last_seen_ns.contains_key("MSFT"));
+    }
+
+    #[tokio::test]
+    async fn frames_that_are_not_json_arrays_are_kept_unparsed() {
+        let (mut connection, mut rx) = connection(&["AAPL"]);
+        for frame in ["not json", r#"{"T": "q"}"#] {
+            assert!(connection.forward(frame).await.unwrap());
+            match drain(&mut rx).as_slice() {
+                [FeedMessage::Unparsed(text)] => assert_eq!(text, frame),
+                other => panic!("expected {} unparsed, got {:?}", frame, other),
+            }
+        }
+    }
+
+    #[test]
+    fn classifies_errors_a_reconnect_cannot_fix() {
+        let alpaca = |code| StreamError::Alpaca {
+            code,
+            msg: String::new(),
+        };
+        for code in [401, 402, 405, 408, 409, 410] {
+            assert!(alpaca(code).is_fatal(), "{}", code);
+        }
+        for code in [400, 404, 406, 407, 500] {
+            assert!(!alpaca(code).is_fatal(), "{}", code);
+        }
+        assert!(alpaca(401).is_auth_failure());
+        assert!(alpaca(402).is_auth_failure());
+        assert!(!alpaca(405).is_auth_failure());
+        assert!(!StreamError::Closed.is_fatal());
+        assert!(!StreamError::Timeout("authentication").is_fatal());
+    }
+
+    fn symbols(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn merged_changes_cancel_out_a_subscribe_and_unsubscribe() {
+        let (mut connection, _rx) = connection(&[]);
+        let mut changes = connection.apply(Some(Command::Subscribe(symbols(&["AAPL", "MSFT"]))));
+        changes.merge(connection.apply(Some(Command::Unsubscribe(symbols(&["AAPL", "TSLA"])))));
+        assert_eq!(changes.added, symbols(&["MSFT"]));
+        assert!(changes.removed.is_empty(), "TSLA was never subscribed");
+
+        // Unsubscribing a live symbol and taking it back sends nothing either
+        let mut changes = connection.apply(Some(Command::Unsubscribe(symbols(&["MSFT"]))));
+        changes.merge(connection.apply(Some(Command::Subscribe(symbols(&["MSFT", "NVDA"])))));
+        assert_eq!(changes.added, symbols(&["NVDA"]));
+        assert!(changes.removed.is_empty());
+        assert_eq!(
+            connection.symbols,
+            ["MSFT", "NVDA"].map(String::from).into()
+        );
+
+        let mut changes = Changes {
+            added: symbols(&["AAPL"]),
+            removed: symbols(&["TSLA"]),
+        };
+        changes.merge(Changes {
+            added: symbols(&["TSLA", "AMD"]),
+            removed: symbols(&["AAPL", "INTC"]),
+        });
+        assert_eq!(changes.added, symbols(&["AMD"]));
+        assert_eq!(changes.removed, symbols(&["INTC"]));
+    }
+
+    #[test]
+    fn subscriptions_are_sent_in_batches() {
+        let many: Vec<String> = (0..250).map(|i| format!("S{:03}", i)).collect();
+        let messages = action_messages(AlpacaFeed::Stocks, "subscribe", &many);
+        assert_eq!(messages.len(), 3);
+        let mut sent = Vec::new();
+        for (message, size) in messages.iter().zip([100, 100, 50]) {
+            assert_eq!(message["action"], "subscribe");
+            let quotes = message["quotes"].as_array().unwrap();
+            assert_eq!(quotes.len(), size);
+            for channel in AlpacaFeed::Stocks.channels() {
+                assert_eq!(&message[*channel], &message["quotes"]);
+            }
+            sent.extend(quotes.iter().map(|s| s.as_str().unwrap().to_string()));
+        }
+        assert_eq!(sent, many);
+
+        let crypto = action_messages(AlpacaFeed::Crypto, "unsubscribe", &symbols(&["BTC/USD"]));
+        assert_eq!(crypto.len(), 1);
+        assert_eq!(crypto[0]["action"], "unsubscribe");
+        assert_eq!(crypto[0]["trades"], json!(["BTC/USD"]));
+        assert!(action_messages(AlpacaFeed::Stocks, "subscribe", &[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn alpaca_errors_end_the_session() {
+        let (mut connection, _rx) = connection(&["AAPL"]);
+        let frame = json!([{"T": "error", "code": 406, "msg": "connection limit exceeded"}]);
+        match connection.forward(&frame.to_string()).await {
+            Err(StreamError::Alpaca { code, msg }) => {
+                assert_eq!(code, 406);
+                assert_eq!(msg, "connection limit exceeded");
+            }
+            other => panic!("expected an Alpaca error, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,327 @@
+use crate::feed::FeedMessage;
+use backend::shared::config::AlpacaConfig;
+use backend::shared::instrument::OptionContract;
+use backend::shared::market_event::{EventPayload, Greeks, MarketEvent, OptionQuote};
+use chrono::DateTime;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+// Option chains come from Alpaca's REST API rather than the stream. One task
+// polls the snapshots of every watched underlying in turn each
+// `[alpaca] options_poll_secs`, spacing requests (one per page) to stay under
+// `options_requests_per_minute` however many underlyings there are. Each
+// contract's snapshot becomes an `OptionQuote` event.
+
+/// Snapshots per page, the most Alpaca allows
+const PAGE_LIMIT: u32 = 1000;
+
+enum Command {
+    Watch(String),
+    Unwatch(String),
+}
+
+/// Handle to the options polling task; clones share it
+#[derive(Clone)]
+pub struct OptionsPoller {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl OptionsPoller {
+    /// Starts polling; runs until the receiver behind `sender` is dropped
+    pub fn spawn(
+        config: AlpacaConfig,
+        sender: mpsc::Sender<FeedMessage>,
+    ) -> (Self, JoinHandle<()>) {
+        let (commands, rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(poll(config, sender, rx));
+        (Self { commands }, handle)
+    }
+
+    /// Starts polling the option chain of `underlying`
+    pub fn watch(&self, underlying: &str) {
+        // Only fails once the task has ended
+        let _ = self.commands.send(Command::Watch(underlying.to_string()));
+    }
+
+    pub fn unwatch(&self, underlying: &str) {
+        let _ = self.commands.send(Command::Unwatch(underlying.to_string()));
+    }
+}
+
+async fn poll(
+    config: AlpacaConfig,
+    sender: mpsc::Sender<FeedMessage>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+) {
+    let client = Client::new();
+    let poll_every = Duration::from_secs(config.options_poll_secs);
+    let mut rounds = interval(poll_every);
+    rounds.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // One request per tick keeps us under the account's rate limit
+    let mut requests =
+        interval(Duration::from_secs(60) / config.options_requests_per_minute.max(1));
+    requests.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut underlyings: BTreeSet<String> = BTreeSet::new();
+    info!(
+        every_secs = poll_every.as_secs(),
+        requests_per_minute = config.options_requests_per_minute,
+        "Polling Alpaca option chains"
+    );
+
+    loop {
+        tokio::select! {
+            _ = rounds.tick() => {}
+            command = commands.recv() => match command {
+                Some(command) => {
+                    apply(&mut underlyings, command);
+                    continue;
+                }
+                None => return, // Every handle dropped
+            },
+            _ = sender.closed() => return,
+        }
+        while let Ok(command) = commands.try_recv() {
+            apply(&mut underlyings, command);
+        }
+
+        let started = Instant::now();
+        for underlying in underlyings.clone() {
+            let mut page_token = None;
+            loop {
+                requests.tick().await;
+                let page = match fetch_alpaca_options_chain(
+                    &client,
+                    &config,
+                    &underlying,
+                    page_token.as_deref(),
+                )
+                .await
+                {
+                    Ok(page) => page,
+                    Err(err) => {
+                        error!(symbol = %underlying, error = %err, "Error fetching options data");
+                        break;
+                    }
+                };
+                for message in option_quotes(&page) {
+                    if sender.send(message).await.is_err() {
+                        return; // Shutting down
+                    }
+                }
+                page_token = page["next_page_token"].as_str().map(str::to_string);
+                if page_token.is_none() {
+                    break;
+                }
+            }
+        }
+        if started.elapsed() > poll_every {
+            warn!(
+                underlyings = underlyings.len(),
+                round_secs = started.elapsed().as_secs(),
+                every_secs = poll_every.as_secs(),
+                "Option chains polled less often than configured; the rate limit is too low for this many underlyings"
+            );
+        }
+    }
+}
+
+fn apply(underlyings: &mut BTreeSet<String>, command: Command) {
+    match command {
+        Command::Watch(underlying) => {
+            if underlyings.insert(underlying.clone()) {
+                debug!(symbol = %underlying, "Polling option chain");
+            }
+        }
+        Command::Unwatch(underlying) => {
+            if underlyings.remove(&underlying) {
+                debug!(symbol = %underlying, "Stopped polling option chain");
+            }
+        }
+    }
+}
+
+/// Fetches one page of option snapshots for `underlying` from Alpaca's market data API
+pub async fn fetch_alpaca_options_chain(
+    client: &Client,
+    config: &AlpacaConfig,
+    underlying: &str,
+    page_token: Option<&str>,
+) -> Result<Value, reqwest::Error> {
+    let options_url = format!(
+        "{}/v1beta1/options/snapshots/{}",
+        config.historic_url, underlying
+    );
+    let mut query = vec![("limit", PAGE_LIMIT.to_string())];
+    if let Some(token) = page_token {
+        query.push(("page_token", token.to_string()));
+    }
+
+    let res = client
+        .get(&options_url)
+        .query(&query)
+        .header("APCA-API-KEY-ID", config.api_key.expose())
+        .header("APCA-API-SECRET-KEY", config.api_secret.expose())
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let options_json: Value = res.json().await?;
+    Ok(options_json)
+}
+
+/// An event per quoted contract in a page of `{"snapshots": {OCC symbol: snapshot}}`;
+/// contracts we cannot read are passed on as unparsed
+fn option_quotes(page: &Value) -> Vec<FeedMessage> {
+    let Some(snapshots) = page["snapshots"].as_object() else {
+        return vec![FeedMessage::Unparsed(page.to_string())];
+    };
+    let mut messages = Vec::with_capacity(snapshots.len());
+    for (symbol, snapshot) in snapshots {
+        // Contracts nobody has quoted yet have nothing to report
+        if snapshot["latestQuote"].is_null() {
+            continue;
+        }
+        messages.push(match parse_alpaca_option_snapshot(symbol, snapshot) {
+            Some(event) => FeedMessage::Event(event),
+            None => FeedMessage::Unparsed(serde_json::json!({ symbol: snapshot }).to_string()),
+        });
+    }
+    messages
+}
+
+/// Maps one contract's snapshot (latest quote and trade, greeks, implied
+/// volatility) to an `OptionQuote`
+pub fn parse_alpaca_option_snapshot(symbol: &str, snapshot: &Value) -> Option<MarketEvent> {
+    let contract = OptionContract::from_occ(symbol).ok()?;
+    let quote = &snapshot["latestQuote"];
+    let exchange_ts_ns = quote["t"]
+        .as_str()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .and_then(|dt| dt.timestamp_nanos_opt())
+        .map(|ns| ns as u64)?;
+    let greeks = &snapshot["greeks"];
+
+    let option_quote = OptionQuote {
+        bid_price: quote["bp"].as_f64()?,
+        ask_price: quote["ap"].as_f64()?,
+        last_price: snapshot["latestTrade"]["p"].as_f64().unwrap_or(0.0),
+        implied_volatility: snapshot["impliedVolatility"].as_f64().unwrap_or(0.0),
+        greeks: Greeks {
+            delta: greeks["delta"].as_f64().unwrap_or(0.0),
+            gamma: greeks["gamma"].as_f64().unwrap_or(0.0),
+            theta: greeks["theta"].as_f64().unwrap_or(0.0),
+            vega: greeks["vega"].as_f64().unwrap_or(0.0),
+            rho: greeks["rho"].as_f64().unwrap_or(0.0),
+        },
+        ..OptionQuote::for_contract(&contract)
+    };
+    Some(MarketEvent::new(
+        contract.compact_occ_symbol(),
+        exchange_ts_ns,
+        EventPayload::OptionQuote(option_quote),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::shared::market_event::OptionRight;
+    use serde_json::json;
+
+    const TS: &str = "2025-02-14T20:59:59.5Z";
+
+    fn snapshot() -> Value {
+        json!({
+            "latestQuote": {"bp": 4.1, "ap": 4.25, "bs": 10, "as": 12, "t": TS},
+            "latestTrade": {"p": 4.2, "s": 1, "t": TS},
+            "impliedVolatility": 0.27,
+            "greeks": {"delta": 0.52, "gamma": 0.03, "theta": -0.11, "vega": 0.19, "rho": 0.04}
+        })
+    }
+
+    #[test]
+    fn maps_a_contract_snapshot_to_an_option_quote() {
+        let event = parse_alpaca_option_snapshot("AAPL250221C00200000", &snapshot()).unwrap();
+        assert_eq!(event.symbol, "AAPL250221C00200000");
+        assert_eq!(
+            event.exchange_ts_ns,
+            DateTime::parse_from_rfc3339(TS)
+                .unwrap()
+                .timestamp_nanos_opt()
+                .unwrap() as u64
+        );
+        assert_eq!(
+            event.payload,
+            EventPayload::OptionQuote(OptionQuote {
+                underlying: "AAPL".to_string(),
+                strike: 200.0,
+                expiry: 20250221,
+                right: OptionRight::Call,
+                bid_price: 4.1,
+                ask_price: 4.25,
+                last_price: 4.2,
+                implied_volatility: 0.27,
+                greeks: Greeks {
+                    delta: 0.52,
+                    gamma: 0.03,
+                    theta: -0.11,
+                    vega: 0.19,
+                    rho: 0.04,
+                },
+            })
+        );
+
+        // Untraded contracts have no last price, greeks or volatility yet
+        let quote_only = json!({"latestQuote": {"bp": 0.05, "ap": 0.1, "t": TS}});
+        let event = parse_alpaca_option_snapshot("AAPL250221P00150000", &quote_only).unwrap();
+        match event.payload {
+            EventPayload::OptionQuote(quote) => {
+                assert_eq!(quote.right, OptionRight::Put);
+                assert_eq!(quote.last_price, 0.0);
+                assert_eq!(quote.greeks, Greeks::default());
+            }
+            other => panic!("expected an option quote, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn pages_keep_unreadable_contracts_and_skip_unquoted_ones() {
+        let mut bad_quote = snapshot();
+        bad_quote["latestQuote"]["bp"] = json!("n/a");
+        let page = json!({
+            "snapshots": {
+                "AAPL250221C00200000": snapshot(),
+                "AAPL250221C00205000": {"latestQuote": null},
+                "AAPL250221C00210000": bad_quote,
+                "NOT-AN-OCC-SYMBOL": snapshot()
+            },
+            "next_page_token": null
+        });
+
+        let messages = option_quotes(&page);
+        assert_eq!(messages.len(), 3);
+        assert!(
+            matches!(&messages[0], FeedMessage::Event(event) if event.symbol == "AAPL250221C00200000")
+        );
+        for (message, symbol) in messages[1..]
+            .iter()
+            .zip(["AAPL250221C00210000", "NOT-AN-OCC-SYMBOL"])
+        {
+            match message {
+                FeedMessage::Unparsed(text) => assert!(text.contains(symbol), "{}", text),
+                other => panic!("expected {} unparsed, got {:?}", symbol, other),
+            }
+        }
+
+        let error = json!({"message": "forbidden"});
+        match option_quotes(&error).as_slice() {
+            [FeedMessage::Unparsed(text)] => assert_eq!(*text, error.to_string()),
+            other => panic!("expected the page unparsed, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff with jitter: each delay is random between half the
+/// initial delay and a ceiling that doubles per attempt, so clients that
+/// dropped together don't reconnect together
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            attempt: 0,
+        }
+    }
+
+    /// The delay before the next attempt
+    pub fn next_delay(&mut self) -> Duration {
+        let ceiling = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(self.attempt))
+            .min(self.max);
+        self.attempt = self.attempt.saturating_add(1);
+        let floor = self.initial / 2;
+        floor
+            + ceiling
+                .saturating_sub(floor)
+                .mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    /// Starts over after a connection that stayed up
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INITIAL: Duration = Duration::from_secs(1);
+    const MAX: Duration = Duration::from_secs(8);
+
+    #[test]
+    fn delays_stay_between_half_the_initial_delay_and_a_doubling_ceiling() {
+        let floor = INITIAL / 2;
+        for _ in 0..100 {
+            let mut backoff = Backoff::new(INITIAL, MAX);
+            for ceiling_secs in [1, 2, 4, 8, 8, 8] {
+                let delay = backoff.next_delay();
+                let ceiling = Duration::from_secs(ceiling_secs);
+                assert!(
+                    delay >= floor && delay <= ceiling,
+                    "{:?} outside {:?}..={:?}",
+                    delay,
+                    floor,
+                    ceiling
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn delays_grow_towards_the_cap() {
+        let mut backoff = Backoff::new(INITIAL, MAX);
+        for _ in 0..10 {
+            backoff.next_delay();
+        }
+        // Capped at 8s: a hundred draws below the first ceiling would be vanishingly unlikely
+        let longest = (0..100).map(|_| backoff.next_delay()).max().unwrap();
+        assert!(longest > INITIAL && longest <= MAX, "{:?}", longest);
+
+        // Many failures in a row must not overflow
+        let mut backoff = Backoff::new(INITIAL, MAX);
+        for _ in 0..1000 {
+            assert!(backoff.next_delay() <= MAX);
+        }
+    }
+
+    #[test]
+    fn reset_starts_over_from_the_initial_delay() {
+        let mut backoff = Backoff::new(INITIAL, MAX);
+        for _ in 0..10 {
+            backoff.next_delay();
+        }
+        for _ in 0..100 {
+            backoff.reset();
+            let delay = backoff.next_delay();
+            assert!(delay >= INITIAL / 2 && delay <= INITIAL, "{:?}", delay);
+        }
+    }
+}
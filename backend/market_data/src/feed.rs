@@ -0,0 +1,14 @@
+use backend::shared::market_event::MarketEvent;
+
+// Provider adapters (`alpaca_api`, `alpaca_options`, `ib_api`) map everything
+// they receive to `MarketEvent`s themselves; the publishing loop and every
+// consumer downstream only ever see the normalized form.
+
+/// What a provider adapter hands to the publishing loop
+#[derive(Debug)]
+pub enum FeedMessage {
+    Event(MarketEvent),
+    /// A provider message the adapter could not make sense of, kept as
+    /// received for the dead-letter topic
+    Unparsed(String),
+}
@@ -1,82 +1,268 @@
 use backend::shared::config::IbConfig;
-use ibapi::contracts::Contract;
-use ibapi::market_data::realtime::{BarSize, WhatToShow};
+use backend::shared::instrument::{IbContract, Instrument};
+use backend::shared::market_event::{self, EventPayload, Greeks, MarketEvent, OptionQuote};
+use ibapi::contracts::tick_types::TickType;
+use ibapi::contracts::{Contract, SecurityType};
+use ibapi::market_data::realtime::{Bar, BarSize, TickTypes, WhatToShow};
 use ibapi::client::Client;
-use std::sync::Arc;
-use std::thread;
+use ibapi::Error;
+use crate::feed::FeedMessage;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+
+/// Length of the real-time bars we request (`BarSize::Sec5`)
+const BAR_INTERVAL_NS: u64 = 5_000_000_000;
 
 pub struct IBMarketData {
     config: IbConfig,
+    client: Mutex<Option<Arc<Client>>>, // One connection for every stream; TWS refuses a second one with the same client id
 }
 
 impl IBMarketData {
     pub fn new(config: IbConfig) -> Arc<Self> {
-        println!("[IB] 🔵 Initializing IB Market Data Agent...");
-        Arc::new(Self { config })
+        info!(host = %config.host, port = config.port, "Initializing IB market data");
+        Arc::new(Self { config, client: Mutex::new(None) })
     }
 
-    /// Fetch real-time stock market data (borrows `self`)
-    pub fn stream_market_data(&self, symbols: Vec<String>, sender: mpsc::Sender<String>) {
-        let mut handles = vec![];
+    /// The shared connection, made on first use or after the last one failed
+    fn client(&self) -> Result<Arc<Client>, Error> {
+        let mut client = self.client.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(client) = client.as_ref() {
+            return Ok(Arc::clone(client));
+        }
+        let connection_url = format!("{}:{}", self.config.host, self.config.port);
+        let connected = Arc::new(Client::connect(&connection_url, self.config.client_id as i32)?);
+        info!(url = %connection_url, client_id = self.config.client_id, "Connected to IB");
+        *client = Some(Arc::clone(&connected));
+        Ok(connected)
+    }
 
-        for symbol in symbols {
-            let config = self.config.clone();
-            let sender = sender.clone();
+    /// Drops the shared connection after an error, so the next request reconnects
+    fn reset_client(&self) {
+        self.client.lock().unwrap_or_else(PoisonError::into_inner).take();
+    }
 
-            let handle = thread::spawn(move || {
-                let connection_url = format!("{}:{}", config.host, config.port);
-                let client = Client::connect(&connection_url, config.client_id as i32)
-                    .expect("Connection to IB Gateway/TWS failed!");
+    /// Stream real-time bars for one contract until `stop` is set (blocks the calling thread).
+    /// Fails if the connection or the request does, or if IB ends the stream.
+    pub fn stream_market_data(&self, contract: IbContract, sender: mpsc::Sender<FeedMessage>, stop: Arc<AtomicBool>) -> Result<(), Error> {
+        let result = self.stream_bars(contract, sender, stop);
+        if result.is_err() {
+            self.reset_client();
+        }
+        result
+    }
 
-                let contract = Contract::stock(&symbol);
-                let subscription = client
-                    .realtime_bars(&contract, BarSize::Sec5, WhatToShow::Trades, false)
-                    .expect("Realtime bars request failed!");
+    fn stream_bars(&self, contract: IbContract, sender: mpsc::Sender<FeedMessage>, stop: Arc<AtomicBool>) -> Result<(), Error> {
+        let client = self.client()?;
 
-                for bar in subscription {
-                    let message = format!(r#"{{"symbol": "{}", "bar": {:?}}}"#, symbol, bar);
+        let symbol = event_symbol(&contract);
+        let contract = to_contract(&contract);
+        let subscription = client.realtime_bars(&contract, BarSize::Sec5, WhatToShow::Trades, false)?;
 
-                    // ✅ Send data via `mpsc::Sender`
-                    if let Err(err) = sender.blocking_send(message) {
-                        eprintln!("[IB] ❌ Failed to send market data: {:?}", err);
-                    }
-                }
-            });
+        for bar in subscription.iter() {
+            // Checked as each bar arrives; dropping the subscription cancels it with IB
+            if stop.load(Ordering::Relaxed) {
+                info!(%symbol, "Stopped IB market data");
+                return Ok(());
+            }
 
-            handles.push(handle);
+            // ✅ Send data via `mpsc::Sender`
+            let event = bar_event(&symbol, &bar);
+            if let Err(err) = sender.blocking_send(FeedMessage::Event(event)) {
+                debug!(%symbol, error = %err, "IB market data receiver closed");
+                return Ok(()); // Shutting down
+            }
         }
 
-        for handle in handles {
-            let _ = handle.join();
+        if stop.load(Ordering::Relaxed) {
+            info!(%symbol, "Stopped IB market data");
+            return Ok(());
         }
+        Err(subscription.error().unwrap_or(Error::UnexpectedEndOfStream))
     }
 
-    /// Fetch options chain data (borrows `self`)
-    /// TODO: move this to separate 'options' agent?
-    pub fn fetch_options_chain(&self, symbol: String, sender: mpsc::Sender<String>) {
-        let config = self.config.clone();
+    /// Snapshot every option matching `contracts` once, until `stop` is set (blocks the calling thread).
+    /// Each contract is either a listed option or a whole chain from `IbContract::option_chain`.
+    /// Fails if the connection or a chain request does.
+    pub fn fetch_options_chain(&self, contracts: Vec<IbContract>, sender: mpsc::Sender<FeedMessage>, stop: Arc<AtomicBool>) -> Result<(), Error> {
+        let result = self.snapshot_chains(contracts, sender, stop);
+        if result.is_err() {
+            self.reset_client();
+        }
+        result
+    }
 
-        let handle = thread::spawn(move || {
-            let connection_url = format!("{}:{}", config.host, config.port);
-            let client = Client::connect(&connection_url, config.client_id as i32)
-                .expect("Connection to IB Gateway/TWS failed!");
+    fn snapshot_chains(&self, contracts: Vec<IbContract>, sender: mpsc::Sender<FeedMessage>, stop: Arc<AtomicBool>) -> Result<(), Error> {
+        let client = self.client()?;
 
-            let contract = Contract::option(&symbol, "202502", 200.0, "C"); 
-            let options_chain = client
-                .contract_details(&contract)
-                .expect("Failed to fetch options chain");
+        for contract in contracts {
+            let symbol = contract.symbol.clone();
+            let options_chain = client.contract_details(&to_contract(&contract))?;
 
+            // The chain only lists contracts; a snapshot of each gives its prices and greeks
             for option in options_chain {
-                let message = format!(r#"{{"symbol": "{}", "option": {:?}}}"#, symbol, option);
+                if stop.load(Ordering::Relaxed) {
+                    info!(%symbol, "Stopped IB option chain snapshot");
+                    return Ok(());
+                }
+                let Some(event) = option_snapshot(&client, &option.contract) else {
+                    continue;
+                };
 
                 // ✅ Send data via `mpsc::Sender`
-                if let Err(err) = sender.blocking_send(message) {
-                    eprintln!("[IB] ❌ Failed to send options data: {:?}", err);
+                if let Err(err) = sender.blocking_send(FeedMessage::Event(event)) {
+                    debug!(%symbol, error = %err, "IB options data receiver closed");
+                    return Ok(()); // Shutting down
                 }
             }
-        });
+        }
+        Ok(())
+    }
+}
+
+/// Builds the `ibapi` contract for reference data from `shared::instrument`
+fn to_contract(contract: &IbContract) -> Contract {
+    Contract {
+        symbol: contract.symbol.clone(),
+        security_type: SecurityType::from(&contract.security_type),
+        exchange: contract.exchange.clone(),
+        currency: contract.currency.clone(),
+        last_trade_date_or_contract_month: contract.last_trade_date.clone(),
+        strike: contract.strike,
+        right: contract.right.clone(),
+        multiplier: contract.multiplier.clone(),
+        ..Default::default()
+    }
+}
+
+/// Maps an `ibapi` contract back to reference data, for options in a chain
+fn from_contract(contract: &Contract) -> IbContract {
+    IbContract {
+        security_type: "OPT".to_string(),
+        symbol: contract.symbol.clone(),
+        exchange: contract.exchange.clone(),
+        currency: contract.currency.clone(),
+        last_trade_date: contract.last_trade_date_or_contract_month.clone(),
+        strike: contract.strike,
+        right: contract.right.clone(),
+        multiplier: contract.multiplier.clone(),
+    }
+}
+
+/// The symbol events for `contract` carry, the same one Alpaca uses
+fn event_symbol(contract: &IbContract) -> String {
+    Instrument::from_ib(contract)
+        .map(|instrument| instrument.alpaca_symbol())
+        .unwrap_or_else(|_| contract.symbol.clone())
+}
+
+/// Maps a 5-second real-time bar; IB stamps bars with their start
+fn bar_event(symbol: &str, bar: &Bar) -> MarketEvent {
+    let payload = EventPayload::Bar(market_event::Bar {
+        open: bar.open,
+        high: bar.high,
+        low: bar.low,
+        close: bar.close,
+        volume: bar.volume,
+        vwap: bar.wap,
+        trade_count: bar.count.max(0) as u64,
+        interval_ns: BAR_INTERVAL_NS,
+    });
+    MarketEvent::new(symbol, bar.date.unix_timestamp_nanos().max(0) as u64, payload)
+}
+
+/// Requests a one-off snapshot of an option and maps it to an `OptionQuote`.
+/// Implied volatility and greeks come from IB's model; IB does not send rho.
+fn option_snapshot(client: &Client, contract: &Contract) -> Option<MarketEvent> {
+    let option = match Instrument::from_ib(&from_contract(contract)) {
+        Ok(Instrument::Option(option)) => option,
+        Ok(_) => return None,
+        Err(err) => {
+            debug!(symbol = %contract.local_symbol, error = %err, "Skipping IB option");
+            return None;
+        }
+    };
+    let ticks = match client.market_data(contract, &[], true, false) {
+        Ok(ticks) => ticks,
+        Err(err) => {
+            error!(symbol = %contract.local_symbol, error = %err, "IB option snapshot request failed");
+            return None;
+        }
+    };
+
+    let mut quote = OptionQuote::for_contract(&option);
+    for tick in &ticks {
+        match tick {
+            TickTypes::Price(tick) => set_price(&mut quote, &tick.tick_type, tick.price),
+            TickTypes::PriceSize(tick) => set_price(&mut quote, &tick.price_tick_type, tick.price),
+            TickTypes::OptionComputation(model) if matches!(model.field, TickType::ModelOption | TickType::DelayedModelOption) => {
+                quote.implied_volatility = model.implied_volatility.unwrap_or(0.0);
+                quote.greeks = Greeks {
+                    delta: model.delta.unwrap_or(0.0),
+                    gamma: model.gamma.unwrap_or(0.0),
+                    theta: model.theta.unwrap_or(0.0),
+                    vega: model.vega.unwrap_or(0.0),
+                    rho: 0.0,
+                };
+            }
+            TickTypes::SnapshotEnd => break,
+            _ => {}
+        }
+    }
+
+    // Snapshots carry no venue timestamp; stamp them as received
+    Some(MarketEvent::new(option.compact_occ_symbol(), market_event::now_ns(), EventPayload::OptionQuote(quote)))
+}
+
+fn set_price(quote: &mut OptionQuote, tick_type: &TickType, price: f64) {
+    match tick_type {
+        TickType::Bid | TickType::DelayedBid => quote.bid_price = price,
+        TickType::Ask | TickType::DelayedAsk => quote.ask_price = price,
+        TickType::Last | TickType::DelayedLast => quote.last_price = price,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+
+    fn bar(count: i32) -> Bar {
+        Bar { date: OffsetDateTime::from_unix_timestamp(1_740_000_000).unwrap(), open: 187.1, high: 187.6, low: 186.9, close: 187.4, volume: 1200.0, wap: 187.3, count }
+    }
+
+    #[test]
+    fn maps_realtime_bars_to_five_second_bar_events() {
+        let event = bar_event("AAPL", &bar(42));
+        assert_eq!(event.symbol, "AAPL");
+        assert_eq!(event.exchange_ts_ns, 1_740_000_000_000_000_000);
+        assert_eq!(
+            event.payload,
+            EventPayload::Bar(market_event::Bar { open: 187.1, high: 187.6, low: 186.9, close: 187.4, volume: 1200.0, vwap: 187.3, trade_count: 42, interval_ns: BAR_INTERVAL_NS })
+        );
+
+        // A negative count means none was reported
+        match bar_event("AAPL", &bar(-1)).payload {
+            EventPayload::Bar(bar) => assert_eq!(bar.trade_count, 0),
+            other => panic!("expected a bar, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn events_carry_the_alpaca_symbol_of_the_contract() {
+        let stock = Instrument::equity("BRK.B").unwrap().ib_contract(None);
+        assert_eq!(event_symbol(&stock), "BRK.B");
+
+        let option: Instrument = "AAPL250221C00200000".parse().unwrap();
+        let contract = option.ib_contract(None);
+        assert_eq!(event_symbol(&contract), "AAPL250221C00200000");
+        // Chain contracts come back from IB and map to the same option
+        assert_eq!(Instrument::from_ib(&from_contract(&to_contract(&contract))).unwrap(), option);
 
-        handle.join().unwrap();
+        let unknown = IbContract { security_type: "FUT".to_string(), symbol: "ES".to_string(), ..Default::default() };
+        assert_eq!(event_symbol(&unknown), "ES");
     }
 }
@@ -1,95 +1,291 @@
 mod alpaca_api;
+mod alpaca_options;
+mod backoff;
+mod feed;
 mod ib_api;
+mod universe;
 
-use alpaca_api::stream_alpaca_market_data;
-use backend::shared::config::load_config;
-use backend::shared::kafka_producer::publish_to_kafka;
-use backend::shared::mmap_buffer::write_to_mmap;
+use backend::shared::calendar::Market;
+use backend::shared::codec::{codec_for, WireFormat};
+use backend::shared::config::{load_config, Provider};
+use backend::shared::dead_letter::DeadLetterQueue;
+use backend::shared::instrument::{load_instruments, Instrument};
+use backend::shared::market_event::MarketEvent;
+use backend::shared::message_bus::{Bus, Message, MARKET_DATA, MARKET_DATA_CONTROL};
+use backend::shared::metrics::{symbol_label, MESSAGES_RECEIVED, PARSE_FAILURES};
+use backend::shared::mmap_buffer::{
+    RingWriter, SnapshotWriter, DEFAULT_CAPACITY, DEFAULT_PATH, DEFAULT_SNAPSHOT_PATH,
+    DEFAULT_SNAPSHOT_SLOTS,
+};
+use backend::shared::runtime::AgentRuntime;
+use backend::shared::telemetry::{init_logging, CorrelationId};
+use chrono::Utc;
+use feed::FeedMessage;
 use ib_api::IBMarketData;
-use serde_json::Value;
 use std::sync::Arc;
-use std::thread;
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tracing::{error, info, trace, warn};
+use universe::{Universe, CONFIG_REQUESTER};
 
-const KAFKA_TOPIC: &str = "market_data"; // Kafka topic for publishing data
-const SYMBOLS: [&str; 3] = ["AAPL", "TSLA", "NVDA"];
+/// Consumer group reading `market_data_control`
+const CONSUMER_NAME: &str = "market_data";
+/// Metrics source label for messages from the provider stream
+const FEED_SOURCE: &str = "feed";
+/// Source topic recorded on dead-lettered provider messages. Nothing consumes
+/// it, so replaying vendor text can never feed it to `market_data` consumers.
+const RAW_FEED_TOPIC: &str = "market_data.raw";
+/// How long the feed may go quiet during trading hours before we warn
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() {
-    let config = load_config();
-    println!(
-        "[MarketData] ✅ Loaded config. Selected provider: {}",
-        config.data_provider.use_provider
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("[MarketData] ❌ {}", err);
+            std::process::exit(1);
+        }
+    };
+    init_logging(&config.logging);
+    info!(
+        profile = %config.profile,
+        provider = %config.data_provider.use_provider,
+        "Loaded config"
     );
+    let runtime = match AgentRuntime::start("market_data", &config).await {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            error!(listen = %config.runtime.listen, error = %err, "Failed to start health server");
+            std::process::exit(1);
+        }
+    };
 
-    let (tx, mut rx) = mpsc::channel::<String>(100);
+    let instruments = match load_instruments(&config) {
+        Ok(instruments) => instruments,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    // Validated with the config, so these parse
+    let watchlist = config
+        .market_data
+        .symbols
+        .iter()
+        .filter_map(|symbol| symbol.parse().ok())
+        .collect::<Vec<Instrument>>();
+    info!(
+        instruments = instruments.len(),
+        watching = %config.market_data.symbols.join(", "),
+        "Loaded reference data"
+    );
 
-    match config.data_provider.use_provider.as_str() {
-        "alpaca" => {
-            println!("[MarketData] 🟢 Using Alpaca WebSocket for real-time market data");
+    let bus = Arc::new(match Bus::connect(&config).await {
+        Ok(bus) => bus,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    });
+    let probe_bus = Arc::clone(&bus);
+    runtime.watch("bus", move || {
+        let bus = Arc::clone(&probe_bus);
+        async move { bus.health_check().await.is_ok() }
+    });
+    let dead_letters = DeadLetterQueue::new(&bus, "market_data");
+    info!(
+        topic = MARKET_DATA.name,
+        format = %bus.format(&MARKET_DATA),
+        "Publishing market data"
+    );
 
-            for symbol in SYMBOLS {
-                let alpaca_config = config.alpaca.clone();
-                let sender = tx.clone();
-                tokio::spawn(async move {
-                    match stream_alpaca_market_data(&alpaca_config, symbol, sender).await {
-                        Ok(_) => println!("[Alpaca] ✅ Streaming data for {}", symbol),
-                        Err(err) => eprintln!("[Alpaca] ❌ Error: {}", err),
-                    }
-                });
-            }
+    // Co-located strategies follow this ring instead of the bus
+    let mut ring = match RingWriter::create(DEFAULT_PATH, DEFAULT_CAPACITY) {
+        Ok(ring) => ring,
+        Err(err) => {
+            error!(path = DEFAULT_PATH, error = %err, "Failed to open ring buffer");
+            std::process::exit(1);
+        }
+    };
+    info!(
+        path = DEFAULT_PATH,
+        seq = ring.next_seq(),
+        "Writing FlatBuffers events to ring buffer"
+    );
+
+    // Latest quote and trade per symbol for risk checks and strategies
+    let mut snapshots = match SnapshotWriter::create(DEFAULT_SNAPSHOT_PATH, DEFAULT_SNAPSHOT_SLOTS)
+    {
+        Ok(snapshots) => snapshots,
+        Err(err) => {
+            error!(
+                path = DEFAULT_SNAPSHOT_PATH,
+                error = %err,
+                "Failed to open snapshot table"
+            );
+            std::process::exit(1);
         }
-        "ib" => {
-            println!("[MarketData] 🔵 Using Interactive Brokers API for market data streaming");
-
-            let ib_market_data = Arc::new(IBMarketData::new(config.ib.clone()));
-
-            // ✅ Stream Market Data
-            let symbols = SYMBOLS
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>();
-            let ib_clone = Arc::clone(&ib_market_data);
-            let tx_clone = tx.clone();
-            thread::spawn(move || {
-                ib_clone.stream_market_data(symbols, tx_clone);
-            });
-
-            // ✅ Fetch Options Chain Data
-            let options_symbol = "AAPL".to_string();
-            let ib_clone = Arc::clone(&ib_market_data);
-            let tx_clone = tx.clone();
-            thread::spawn(move || {
-                ib_clone.fetch_options_chain(options_symbol, tx_clone);
-            });
+    };
+
+    let (tx, mut rx) = mpsc::channel::<FeedMessage>(100);
+
+    // Up while data arrives; down when a stream ends or goes quiet in trading hours
+    let feed = runtime.check("feed");
+    feed.set(true);
+
+    let mut universe = match config.data_provider.use_provider {
+        Provider::Alpaca => {
+            info!("Using Alpaca WebSocket for real-time market data");
+            Universe::alpaca(config.alpaca.clone(), instruments, tx.clone(), feed.clone())
         }
-        _ => {
-            eprintln!("[MarketData] ❌ Invalid data provider in config");
+        Provider::Ib => {
+            info!("Using Interactive Brokers API for market data streaming");
+
+            let ib_market_data = IBMarketData::new(config.ib.clone());
+            Universe::ib(ib_market_data, instruments, tx.clone(), feed.clone())
         }
+    };
+    // ✅ Stream Market Data
+    for instrument in &watchlist {
+        universe.subscribe(CONFIG_REQUESTER, instrument);
     }
+    drop(tx); // The universe holds the senders the streams need
+
+    // Strategies subscribe to what they trade at runtime
+    let mut control = match bus.subscribe(&MARKET_DATA_CONTROL, CONSUMER_NAME).await {
+        Ok(control) => control,
+        Err(err) => {
+            error!(topic = MARKET_DATA_CONTROL.name, error = %err, "Failed to subscribe to control topic");
+            std::process::exit(1);
+        }
+    };
+
+    // Silence is only suspicious while the market is open
+    let calendar = config.calendar.calendar(Market::Nyse);
+    let extended_hours = config.calendar.extended_hours;
+    let mut closed_logged = false;
+    let shutdown = runtime.shutdown();
 
     // Process incoming WebSocket messages
-    while let Some(text) = rx.recv().await {
-        process_market_data(&text).await;
+    loop {
+        let received = tokio::select! {
+            received = tokio::time::timeout(SILENCE_TIMEOUT, rx.recv()) => received,
+            Some(delivery) = control.recv() => {
+                match delivery {
+                    Ok(delivery) => {
+                        match delivery.value {
+                            Ok(request) => universe.apply(&request),
+                            Err(err) => {
+                                error!(error = %err, "Failed to decode subscription request");
+                                if let Err(err) = dead_letters.send(&delivery.message, &err).await {
+                                    error!(error = %err, "Failed to dead-letter subscription request");
+                                }
+                            }
+                        }
+                        if let Err(err) = control.commit(&delivery.message).await {
+                            error!(error = %err, "Failed to commit subscription request");
+                        }
+                    }
+                    Err(err) => error!(error = %err, "Message bus error"),
+                }
+                continue;
+            }
+            _ = shutdown.wait() => break,
+        };
+        match received {
+            Ok(Some(message)) => {
+                closed_logged = false;
+                feed.set(true);
+                match message {
+                    FeedMessage::Event(event) => {
+                        MESSAGES_RECEIVED
+                            .with_label_values(&[FEED_SOURCE, &symbol_label(&event.symbol)])
+                            .inc();
+                        publish_event(event, &bus, &mut ring, &mut snapshots).await
+                    }
+                    FeedMessage::Unparsed(text) => dead_letter(&text, &dead_letters).await,
+                }
+            }
+            Ok(None) => break,
+            Err(_) => {
+                let now = Utc::now();
+                let session = calendar.session_at(now);
+                if session.is_open(extended_hours) {
+                    closed_logged = false;
+                    feed.set(false);
+                    warn!(
+                        silent_secs = SILENCE_TIMEOUT.as_secs(),
+                        %session,
+                        "No market data while the market is open"
+                    );
+                } else if !closed_logged {
+                    closed_logged = true;
+                    match calendar.next_open(now, extended_hours) {
+                        Some(open) => info!(next_open = %open, "Market closed, silence expected"),
+                        None => info!("Market closed, silence expected"),
+                    }
+                }
+            }
+        }
+    }
+
+    // Stream tasks stop once their sends fail; what was published must still reach the broker
+    drop(universe);
+    drop(rx);
+    if let Err(err) = control.close().await {
+        error!(error = %err, "Failed to close control subscription");
+    }
+    if let Err(err) = bus.flush(runtime.shutdown_timeout()).await {
+        error!(error = %err, "Failed to flush the message bus");
+    }
+    info!("Market data stopped");
+}
+
+/// Sends a provider message the adapter could not map to `market_data.raw.dlq`,
+/// as received, so the adapter can be fixed against it
+async fn dead_letter(text: &str, dead_letters: &DeadLetterQueue) {
+    PARSE_FAILURES.with_label_values(&[FEED_SOURCE]).inc();
+    error!(message = text, "Failed to parse provider message");
+    let raw = Message::new(RAW_FEED_TOPIC, text);
+    if let Err(err) = dead_letters
+        .send(&raw, "not a market event this adapter understands")
+        .await
+    {
+        error!(error = %err, "Failed to dead-letter feed message");
     }
 }
 
-/// Processes incoming market data and writes it to memory-mapped buffer & Kafka.
-async fn process_market_data(text: &str) {
-    if let Ok(json_array) = serde_json::from_str::<Vec<Value>>(&text) {
-        for json_msg in json_array {
-            let json_str = json_msg.to_string();
+/// Writes one event to the snapshot table, ring buffer and bus under a new
+/// correlation ID that follows it downstream
+async fn publish_event(
+    event: MarketEvent,
+    bus: &Bus,
+    ring: &mut RingWriter,
+    snapshots: &mut SnapshotWriter,
+) {
+    let correlation_id = CorrelationId::new();
+    trace!(%correlation_id, symbol = %event.symbol, ?event.payload, "Market event");
 
-            // Write to Shared Memory-Mapped Buffer
-            write_to_mmap(&json_str);
+    if let Err(err) = snapshots.apply(&event) {
+        error!(%correlation_id, error = %err, "Failed to update snapshot table");
+    }
 
-            // Publish full JSON object to Kafka
-            publish_to_kafka(KAFKA_TOPIC, &json_str).await;
+    // Ring readers always get FlatBuffers so they can read in place
+    match codec_for(WireFormat::Flatbuffers).encode(&event) {
+        Ok(bytes) => {
+            if let Err(err) = ring.write(&bytes) {
+                error!(%correlation_id, error = %err, "Failed to write ring buffer");
+            }
         }
-    } else {
-        eprintln!(
-            "[MarketData] ❌ Failed to parse WebSocket message as JSON array: {}",
-            text
-        );
+        Err(err) => error!(%correlation_id, error = %err, "Failed to encode event"),
+    }
+
+    // Publish using the topic's codec
+    if let Err(err) = bus
+        .publish_correlated(&MARKET_DATA, &event, &correlation_id)
+        .await
+    {
+        error!(%correlation_id, error = %err, "Failed to publish market event");
     }
 }
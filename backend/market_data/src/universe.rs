@@ -0,0 +1,260 @@
+use crate::alpaca_api::{AlpacaFeed, AlpacaStream};
+use crate::alpaca_options::OptionsPoller;
+use crate::feed::FeedMessage;
+use crate::ib_api::IBMarketData;
+use backend::shared::config::AlpacaConfig;
+use backend::shared::instrument::{
+    IbContract, Instrument, InstrumentKind, InstrumentMaster, InstrumentSpec,
+};
+use backend::shared::runtime::HealthCheck;
+use backend::shared::subscription::{SubscriptionAction, SubscriptionRequest, Subscriptions};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+// The instruments the agent streams, and the provider subscriptions behind
+// them. An instrument's first reference opens its provider subscription and
+// its last closes it:
+//
+//   Alpaca  one multiplexed stream per feed, plus the polled option chain of
+//           every streamed underlying
+//   IB      one real-time bar stream per instrument over a shared connection,
+//           plus a snapshot of the option chain of every streamed underlying
+//           taken when it is first subscribed
+
+/// Holds `[market_data] symbols` for the agent itself
+pub const CONFIG_REQUESTER: &str = "config";
+
+pub struct Universe {
+    subscriptions: Subscriptions,
+    instruments: InstrumentMaster,
+    sender: mpsc::Sender<FeedMessage>,
+    provider: ProviderFeeds,
+}
+
+enum ProviderFeeds {
+    Alpaca {
+        config: AlpacaConfig,
+        streams: HashMap<AlpacaFeed, AlpacaStream>, // Spawned on first use
+        options: OptionsPoller,
+        health: HealthCheck, // Fails when a stream ends
+    },
+    Ib {
+        market_data: Arc<IBMarketData>,
+        health: HealthCheck, // Fails when a stream or chain request does
+        streams: HashMap<Instrument, Arc<AtomicBool>>, // Stop flag per bar stream
+        chains: HashMap<String, Arc<AtomicBool>>, // Stop flag per underlying's chain snapshot
+    },
+}
+
+impl Universe {
+    /// Streams from Alpaca; `health` is failed if a stream gives up
+    pub fn alpaca(
+        config: AlpacaConfig,
+        instruments: InstrumentMaster,
+        sender: mpsc::Sender<FeedMessage>,
+        health: HealthCheck,
+    ) -> Self {
+        let (options, _) = OptionsPoller::spawn(config.clone(), sender.clone());
+        Self {
+            subscriptions: Subscriptions::new(),
+            instruments,
+            sender,
+            provider: ProviderFeeds::Alpaca {
+                config,
+                streams: HashMap::new(),
+                options,
+                health,
+            },
+        }
+    }
+
+    /// Streams from IB; `health` is failed if a stream or chain request fails
+    pub fn ib(
+        market_data: Arc<IBMarketData>,
+        instruments: InstrumentMaster,
+        sender: mpsc::Sender<FeedMessage>,
+        health: HealthCheck,
+    ) -> Self {
+        Self {
+            subscriptions: Subscriptions::new(),
+            instruments,
+            sender,
+            provider: ProviderFeeds::Ib {
+                market_data,
+                health,
+                streams: HashMap::new(),
+                chains: HashMap::new(),
+            },
+        }
+    }
+
+    /// Takes or drops `request.requester`'s reference on each symbol
+    pub fn apply(&mut self, request: &SubscriptionRequest) {
+        for instrument in &request.symbols {
+            match request.action {
+                SubscriptionAction::Subscribe => self.subscribe(&request.requester, instrument),
+                SubscriptionAction::Unsubscribe => self.unsubscribe(&request.requester, instrument),
+            }
+        }
+    }
+
+    pub fn subscribe(&mut self, requester: &str, instrument: &Instrument) {
+        if self.subscriptions.add(requester, instrument) {
+            info!(requester, %instrument, "Subscribing");
+            self.open(instrument);
+        } else {
+            info!(
+                requester,
+                %instrument,
+                holders = self.subscriptions.holders(instrument).count(),
+                "Already subscribed"
+            );
+        }
+    }
+
+    pub fn unsubscribe(&mut self, requester: &str, instrument: &Instrument) {
+        if self.subscriptions.remove(requester, instrument) {
+            info!(requester, %instrument, "Unsubscribing");
+            self.close(instrument);
+        } else if self.subscriptions.contains(instrument) {
+            info!(
+                requester,
+                %instrument,
+                holders = self.subscriptions.holders(instrument).count(),
+                "Still subscribed for other requesters"
+            );
+        }
+    }
+
+    /// Starts the provider subscription; `instrument` is already in `subscriptions`
+    fn open(&mut self, instrument: &Instrument) {
+        let polls_chain = self.polls_chain(instrument.underlying());
+        match &mut self.provider {
+            ProviderFeeds::Alpaca {
+                config,
+                streams,
+                options,
+                health,
+            } => {
+                if let Some(feed) = AlpacaFeed::for_instrument(instrument) {
+                    let stream = streams
+                        .entry(feed)
+                        .or_insert_with(|| spawn_stream(feed, config, &self.sender, health));
+                    stream.subscribe([instrument.alpaca_symbol()]);
+                }
+                // The first instrument on this underlying starts its chain
+                if instrument.kind() != InstrumentKind::Crypto && polls_chain == 1 {
+                    options.watch(instrument.underlying());
+                }
+            }
+            ProviderFeeds::Ib {
+                market_data,
+                health,
+                streams,
+                chains,
+            } => {
+                let contract = self.instruments.spec(instrument).ib_contract();
+                let stop = Arc::new(AtomicBool::new(false));
+                streams.insert(instrument.clone(), Arc::clone(&stop));
+                let ib = Arc::clone(market_data);
+                let sender = self.sender.clone();
+                let failed = health.clone();
+                let symbol = instrument.to_string();
+                thread::spawn(move || {
+                    if let Err(err) = ib.stream_market_data(contract, sender, stop) {
+                        error!(%symbol, error = %err, "IB market data stream failed");
+                        failed.set(false);
+                    }
+                });
+
+                if instrument.kind() != InstrumentKind::Crypto && polls_chain == 1 {
+                    // The listed contracts, or the whole chain if none are listed
+                    let underlying = instrument.underlying();
+                    let mut contracts: Vec<IbContract> = self
+                        .instruments
+                        .options_on(underlying)
+                        .map(InstrumentSpec::ib_contract)
+                        .collect();
+                    if contracts.is_empty() {
+                        contracts.push(IbContract::option_chain(underlying));
+                    }
+                    let stop = Arc::new(AtomicBool::new(false));
+                    chains.insert(underlying.to_string(), Arc::clone(&stop));
+                    let ib = Arc::clone(market_data);
+                    let sender = self.sender.clone();
+                    let failed = health.clone();
+                    let underlying = underlying.to_string();
+                    thread::spawn(move || {
+                        if let Err(err) = ib.fetch_options_chain(contracts, sender, stop) {
+                            error!(%underlying, error = %err, "IB option chain snapshot failed");
+                            failed.set(false);
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Stops the provider subscription; `instrument` is already gone from `subscriptions`
+    fn close(&mut self, instrument: &Instrument) {
+        let polls_chain = self.polls_chain(instrument.underlying());
+        match &mut self.provider {
+            ProviderFeeds::Alpaca {
+                streams, options, ..
+            } => {
+                if let Some(stream) =
+                    AlpacaFeed::for_instrument(instrument).and_then(|feed| streams.get(&feed))
+                {
+                    stream.unsubscribe([instrument.alpaca_symbol()]);
+                }
+                if instrument.kind() != InstrumentKind::Crypto && polls_chain == 0 {
+                    options.unwatch(instrument.underlying());
+                }
+            }
+            ProviderFeeds::Ib {
+                streams, chains, ..
+            } => {
+                if let Some(stop) = streams.remove(instrument) {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                if polls_chain == 0 {
+                    if let Some(stop) = chains.remove(instrument.underlying()) {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Subscribed stocks and options on `underlying`, whose chain is polled or snapshotted
+    fn polls_chain(&self, underlying: &str) -> usize {
+        self.subscriptions
+            .instruments()
+            .filter(|i| i.kind() != InstrumentKind::Crypto && i.underlying() == underlying)
+            .count()
+    }
+}
+
+/// Starts the connection for `feed` and fails `health` if it ever gives up
+fn spawn_stream(
+    feed: AlpacaFeed,
+    config: &AlpacaConfig,
+    sender: &mpsc::Sender<FeedMessage>,
+    health: &HealthCheck,
+) -> AlpacaStream {
+    let (stream, handle) = AlpacaStream::spawn(feed, config.clone(), sender.clone());
+    let health = health.clone();
+    tokio::spawn(async move {
+        match handle.await {
+            Ok(Ok(())) => info!(%feed, "Alpaca stream ended"),
+            Ok(Err(err)) => error!(%feed, error = %err, "Alpaca stream failed"),
+            Err(err) => error!(%feed, error = %err, "Alpaca stream task panicked"),
+        }
+        health.set(false);
+    });
+    stream
+}
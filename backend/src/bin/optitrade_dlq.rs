@@ -0,0 +1,93 @@
+//! Inspects and replays dead-lettered messages.
+//!
+//! `dump` drains `<topic>.dlq` into JSON lines on stdout, stopping once the topic has been idle
+//! for a few seconds. Edit the payloads that need fixing, drop the lines that should stay dead,
+//! then `replay` the file (or `-` for stdin) to publish each message back to its source topic.
+//!
+//! Provider messages `market_data` could not map are dead-lettered from `market_data.raw`, so
+//! `dump market_data.raw` shows them; replaying them only republishes to that unconsumed topic.
+
+use backend::shared::config::load_config;
+use backend::shared::dead_letter::{dead_letter_topic, DeadLetter};
+use backend::shared::message_bus::{Bus, OffsetReset};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "usage: optitrade_dlq dump <topic> [idle-seconds] | replay <file.jsonl|->";
+const CONSUMER_GROUP: &str = "optitrade_dlq";
+const DEFAULT_IDLE: Duration = Duration::from_secs(5);
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (command, target) = match args {
+        [command, target, ..] => (command.as_str(), target.as_str()),
+        _ => return Err(USAGE.into()),
+    };
+
+    let config = load_config()?;
+    let bus = Bus::connect(&config).await?;
+
+    match command {
+        "dump" => {
+            let idle = match args.get(2) {
+                Some(secs) => Duration::from_secs(secs.parse().map_err(|_| USAGE)?),
+                None => DEFAULT_IDLE,
+            };
+            let topic = dead_letter_topic(target);
+            // The first dump starts at the oldest dead letter still retained
+            let mut subscription = bus
+                .transport()
+                .subscribe(&[topic.as_str()], CONSUMER_GROUP, OffsetReset::Earliest)
+                .await?;
+
+            let mut count = 0;
+            while let Ok(Some(message)) = tokio::time::timeout(idle, subscription.recv()).await {
+                let message = message?;
+                let letter = DeadLetter::from_message(&message)?;
+                println!("{}", letter.to_json()?);
+                subscription.commit(&message).await?;
+                count += 1;
+            }
+            // Commits the last offsets now and leaves the group instead of timing out of it
+            subscription.close().await?;
+            eprintln!("✅ Dumped {} dead letters from {}", count, topic);
+        }
+        "replay" => {
+            let reader: Box<dyn BufRead> = if target == "-" {
+                Box::new(io::stdin().lock())
+            } else {
+                Box::new(BufReader::new(File::open(target)?))
+            };
+
+            let mut count = 0;
+            for (number, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                    continue;
+                }
+                let letter = DeadLetter::from_json(&line)
+                    .map_err(|e| format!("line {}: {}", number + 1, e))?;
+                bus.transport().publish(letter.replay_message()).await?;
+                count += 1;
+            }
+            bus.flush(Duration::from_secs(10)).await?;
+            eprintln!("✅ Replayed {} messages", count);
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Manages the passphrase-encrypted local keystore used by `[secrets] provider = "keystore"`.
+//!
+//! The passphrase is read from `OPTITRADE_KEYSTORE_PASSPHRASE`; values for `set` are read from stdin
+//! so they never appear in shell history.
+
+use backend::shared::secrets::{Keystore, Secret, SecretError, KEYSTORE_PASSPHRASE_ENV};
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: optitrade_keystore <keystore-path> list | set <key> | remove <key>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, command) = match args {
+        [path, command, ..] => (Path::new(path), command.as_str()),
+        _ => return Err(USAGE.into()),
+    };
+    let passphrase = std::env::var(KEYSTORE_PASSPHRASE_ENV)
+        .map(Secret::new)
+        .map_err(|_| SecretError::MissingPassphrase)?;
+
+    let open_or_create = || {
+        if path.exists() {
+            Keystore::open(path, &passphrase)
+        } else {
+            Ok(Keystore::create(path))
+        }
+    };
+
+    match (command, args.get(2)) {
+        ("list", None) => {
+            for key in Keystore::open(path, &passphrase)?.keys() {
+                println!("{}", key);
+            }
+        }
+        ("set", Some(key)) => {
+            let mut keystore = open_or_create()?;
+            let mut value = String::new();
+            io::stdin().lock().read_line(&mut value)?;
+            let value = Secret::new(value.trim_end_matches(['\r', '\n']));
+            if value.is_empty() {
+                return Err("refusing to store an empty value".into());
+            }
+            keystore.set(key, value)?;
+            keystore.save(&passphrase)?;
+            println!("✅ Stored {}", key);
+        }
+        ("remove", Some(key)) => {
+            let mut keystore = Keystore::open(path, &passphrase)?;
+            if keystore.remove(key).is_none() {
+                return Err(format!("no entry named '{}'", key).into());
+            }
+            keystore.save(&passphrase)?;
+            println!("✅ Removed {}", key);
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
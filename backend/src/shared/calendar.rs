@@ -0,0 +1,494 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::America::New_York;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+// US equity sessions in New York time:
+//
+//   pre-market   04:00 - 09:30
+//   regular      09:30 - 16:00   (13:00 on early-close days)
+//   after-hours  16:00 - 20:00   (13:00 - 17:00 on early-close days)
+//
+// NYSE and Nasdaq share holidays and early closes. Holidays follow NYSE Rule
+// 7.2 and are computed rather than listed, so the calendar never runs out;
+// one-off closures (national days of mourning, hurricanes) are listed below
+// and more can be added from `[calendar] closures`. Crypto trades around the
+// clock in UTC.
+
+/// Unscheduled full-day NYSE closures since 2000
+const UNSCHEDULED_CLOSURES: [(i32, u32, u32); 10] = [
+    (2001, 9, 11),
+    (2001, 9, 12),
+    (2001, 9, 13),
+    (2001, 9, 14),
+    (2004, 6, 11),
+    (2007, 1, 2),
+    (2012, 10, 29),
+    (2012, 10, 30),
+    (2018, 12, 5),
+    (2025, 1, 9),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Market {
+    #[default]
+    Nyse,
+    Nasdaq,
+    Crypto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Session {
+    Closed,
+    PreMarket,
+    Regular,
+    AfterHours,
+}
+
+/// One session on one trading day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionWindow {
+    pub session: Session,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// When a market is open
+#[derive(Debug, Clone)]
+pub struct TradingCalendar {
+    market: Market,
+    closures: BTreeSet<NaiveDate>,
+}
+
+impl Market {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Market::Nyse => "nyse",
+            Market::Nasdaq => "nasdaq",
+            Market::Crypto => "crypto",
+        }
+    }
+}
+
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Session {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Session::Closed => "closed",
+            Session::PreMarket => "pre-market",
+            Session::Regular => "regular",
+            Session::AfterHours => "after-hours",
+        }
+    }
+
+    /// Whether orders and data are expected in this session
+    pub fn is_open(&self, extended_hours: bool) -> bool {
+        match self {
+            Session::Regular => true,
+            Session::PreMarket | Session::AfterHours => extended_hours,
+            Session::Closed => false,
+        }
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TradingCalendar {
+    pub fn new(market: Market) -> Self {
+        Self {
+            market,
+            closures: BTreeSet::new(),
+        }
+    }
+
+    /// Adds full-day closures on top of the built-in holidays
+    pub fn with_closures(mut self, dates: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.closures.extend(dates);
+        self
+    }
+
+    pub fn market(&self) -> Market {
+        self.market
+    }
+
+    /// The exchange's calendar date at `ts`
+    pub fn local_date(&self, ts: DateTime<Utc>) -> NaiveDate {
+        match self.market {
+            Market::Crypto => ts.date_naive(),
+            Market::Nyse | Market::Nasdaq => ts.with_timezone(&New_York).date_naive(),
+        }
+    }
+
+    /// Midnight at the exchange at the start of `date`
+    pub fn day_start(&self, date: NaiveDate) -> DateTime<Utc> {
+        self.at(date, NaiveTime::MIN)
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        match self.market {
+            Market::Crypto => false,
+            Market::Nyse | Market::Nasdaq => {
+                self.closures.contains(&date) || us_equity_holidays(date.year()).contains(&date)
+            }
+        }
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        match self.market {
+            Market::Crypto => true,
+            Market::Nyse | Market::Nasdaq => !is_weekend(date) && !self.is_holiday(date),
+        }
+    }
+
+    /// Regular session ends at 13:00 (July 3, the day after Thanksgiving, Christmas Eve)
+    pub fn is_early_close(&self, date: NaiveDate) -> bool {
+        if self.market == Market::Crypto || !self.is_trading_day(date) {
+            return false;
+        }
+        let year = date.year();
+        date == ymd(year, 7, 3)
+            || date == nth_weekday(year, 11, Weekday::Thu, 4) + Duration::days(1)
+            || date == ymd(year, 12, 24)
+    }
+
+    /// The sessions on `date`, in order; empty when the market is closed all day
+    pub fn sessions(&self, date: NaiveDate) -> Vec<SessionWindow> {
+        if !self.is_trading_day(date) {
+            return Vec::new();
+        }
+        if self.market == Market::Crypto {
+            let start = self.day_start(date);
+            return vec![SessionWindow {
+                session: Session::Regular,
+                start,
+                end: start + Duration::days(1),
+            }];
+        }
+
+        let (close, after_close) = if self.is_early_close(date) {
+            (hm(13, 0), hm(17, 0))
+        } else {
+            (hm(16, 0), hm(20, 0))
+        };
+        [
+            (Session::PreMarket, hm(4, 0), hm(9, 30)),
+            (Session::Regular, hm(9, 30), close),
+            (Session::AfterHours, close, after_close),
+        ]
+        .into_iter()
+        .map(|(session, start, end)| SessionWindow {
+            session,
+            start: self.at(date, start),
+            end: self.at(date, end),
+        })
+        .collect()
+    }
+
+    /// The regular session on `date`, if the market opens that day
+    pub fn regular_session(&self, date: NaiveDate) -> Option<SessionWindow> {
+        self.sessions(date)
+            .into_iter()
+            .find(|w| w.session == Session::Regular)
+    }
+
+    pub fn session_at(&self, ts: DateTime<Utc>) -> Session {
+        self.sessions(self.local_date(ts))
+            .into_iter()
+            .find(|w| w.start <= ts && ts < w.end)
+            .map(|w| w.session)
+            .unwrap_or(Session::Closed)
+    }
+
+    pub fn is_open(&self, ts: DateTime<Utc>, extended_hours: bool) -> bool {
+        self.session_at(ts).is_open(extended_hours)
+    }
+
+    /// `ts` if the market is open then, otherwise the start of the next session that counts
+    pub fn next_open(&self, ts: DateTime<Utc>, extended_hours: bool) -> Option<DateTime<Utc>> {
+        let mut date = self.local_date(ts);
+        // The longest US closure on record (September 2001) is well inside this
+        for _ in 0..30 {
+            for window in self.sessions(date) {
+                if window.session.is_open(extended_hours) && window.end > ts {
+                    return Some(window.start.max(ts));
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// `date` if it is a trading day, otherwise the next one
+    pub fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_trading_day(date) {
+            match date.succ_opt() {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+        date
+    }
+
+    /// Trading days from `start` to `end`, both inclusive
+    pub fn trading_days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter(|date| self.is_trading_day(*date))
+            .collect()
+    }
+
+    /// `[start, end)` covering every trading day from `first` to `last`, midnight to midnight
+    /// exchange time; `None` if there are none
+    pub fn trading_range(
+        &self,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let days = self.trading_days(first, last);
+        let start = *days.first()?;
+        let end = days.last()?.succ_opt()?;
+        Some((self.day_start(start), self.day_start(end)))
+    }
+
+    fn at(&self, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+        let local = date.and_time(time);
+        match self.market {
+            Market::Crypto => local.and_utc(),
+            // Session boundaries never fall in a DST gap, but midnight can't be ruled out forever
+            Market::Nyse | Market::Nasdaq => New_York
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|ts| ts.with_timezone(&Utc))
+                .unwrap_or_else(|| local.and_utc()),
+        }
+    }
+}
+
+/// NYSE full-day holidays observed in `year`
+pub fn us_equity_holidays(year: i32) -> Vec<NaiveDate> {
+    let mut holidays = vec![
+        nth_weekday(year, 1, Weekday::Mon, 3), // Martin Luther King Jr. Day
+        nth_weekday(year, 2, Weekday::Mon, 3), // Washington's Birthday
+        easter_sunday(year) - Duration::days(2), // Good Friday
+        last_weekday(year, 5, Weekday::Mon),   // Memorial Day
+        observed(ymd(year, 7, 4)),             // Independence Day
+        nth_weekday(year, 9, Weekday::Mon, 1), // Labor Day
+        nth_weekday(year, 11, Weekday::Thu, 4), // Thanksgiving
+        observed(ymd(year, 12, 25)),           // Christmas
+    ];
+
+    // New Year's Day on a Saturday is not moved back into the previous year
+    let new_year = ymd(year, 1, 1);
+    if new_year.weekday() != Weekday::Sat {
+        holidays.push(observed(new_year));
+    }
+    if year >= 2022 {
+        holidays.push(observed(ymd(year, 6, 19))); // Juneteenth
+    }
+
+    holidays.extend(
+        UNSCHEDULED_CLOSURES
+            .iter()
+            .filter(|(y, _, _)| *y == year)
+            .map(|(y, m, d)| ymd(*y, *m, *d)),
+    );
+    holidays.sort();
+    holidays
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Saturday holidays move to Friday, Sunday holidays to Monday
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap_or_default()
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default()
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).unwrap_or_default()
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, 5)
+        .unwrap_or_else(|| nth_weekday(year, month, weekday, 4))
+}
+
+/// Gregorian Easter (anonymous Gregorian algorithm)
+fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    ymd(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn nyse() -> TradingCalendar {
+        TradingCalendar::new(Market::Nyse)
+    }
+
+    #[test]
+    fn computes_nyse_holidays() {
+        assert_eq!(
+            us_equity_holidays(2025),
+            [
+                ymd(2025, 1, 1),
+                ymd(2025, 1, 9), // National day of mourning
+                ymd(2025, 1, 20),
+                ymd(2025, 2, 17),
+                ymd(2025, 4, 18),
+                ymd(2025, 5, 26),
+                ymd(2025, 6, 19),
+                ymd(2025, 7, 4),
+                ymd(2025, 9, 1),
+                ymd(2025, 11, 27),
+                ymd(2025, 12, 25),
+            ]
+        );
+        // Independence Day on a Saturday is observed on Friday
+        assert!(us_equity_holidays(2026).contains(&ymd(2026, 7, 3)));
+        assert!(us_equity_holidays(2026).contains(&ymd(2026, 4, 3)));
+    }
+
+    #[test]
+    fn saturday_new_year_is_not_observed_the_year_before() {
+        let calendar = nyse();
+        assert!(calendar.is_trading_day(ymd(2021, 12, 31)));
+        assert!(!calendar.is_trading_day(ymd(2022, 1, 1)));
+        // Juneteenth on a Sunday moves to Monday
+        assert!(calendar.is_holiday(ymd(2022, 6, 20)));
+    }
+
+    #[test]
+    fn configured_closures_close_the_market() {
+        let calendar = nyse().with_closures([ymd(2025, 3, 12)]);
+        assert!(!calendar.is_trading_day(ymd(2025, 3, 12)));
+        assert!(calendar.sessions(ymd(2025, 3, 12)).is_empty());
+        assert!(nyse().is_trading_day(ymd(2025, 3, 12)));
+    }
+
+    #[test]
+    fn sessions_follow_new_york_time_across_dst() {
+        let winter = nyse().regular_session(ymd(2025, 1, 6)).unwrap();
+        assert_eq!(
+            (winter.start, winter.end),
+            (utc("2025-01-06T14:30:00Z"), utc("2025-01-06T21:00:00Z"))
+        );
+        let summer = nyse().regular_session(ymd(2025, 3, 10)).unwrap();
+        assert_eq!(
+            (summer.start, summer.end),
+            (utc("2025-03-10T13:30:00Z"), utc("2025-03-10T20:00:00Z"))
+        );
+
+        let sessions = nyse().sessions(ymd(2025, 11, 28));
+        assert!(nyse().is_early_close(ymd(2025, 11, 28)));
+        assert_eq!(sessions[1].end, utc("2025-11-28T18:00:00Z"));
+        assert_eq!(sessions[2].end, utc("2025-11-28T22:00:00Z"));
+    }
+
+    #[test]
+    fn classifies_timestamps_by_session() {
+        let calendar = nyse();
+        assert_eq!(
+            calendar.session_at(utc("2025-01-06T08:59:00Z")),
+            Session::Closed
+        );
+        assert_eq!(
+            calendar.session_at(utc("2025-01-06T14:00:00Z")),
+            Session::PreMarket
+        );
+        assert_eq!(
+            calendar.session_at(utc("2025-01-06T14:30:00Z")),
+            Session::Regular
+        );
+        assert_eq!(
+            calendar.session_at(utc("2025-01-06T21:00:00Z")),
+            Session::AfterHours
+        );
+        assert_eq!(
+            calendar.session_at(utc("2025-01-11T15:00:00Z")),
+            Session::Closed
+        ); // Saturday
+        assert!(!calendar.is_open(utc("2025-01-06T14:00:00Z"), false));
+        assert!(calendar.is_open(utc("2025-01-06T14:00:00Z"), true));
+    }
+
+    #[test]
+    fn next_open_skips_weekends_and_holidays() {
+        let calendar = nyse();
+        // Friday after the close, with Monday a holiday
+        let friday_evening = utc("2025-01-17T22:00:00Z");
+        assert_eq!(
+            calendar.next_open(friday_evening, false),
+            Some(utc("2025-01-21T14:30:00Z"))
+        );
+        assert_eq!(
+            calendar.next_open(friday_evening, true),
+            Some(friday_evening)
+        );
+        assert_eq!(
+            calendar.next_open(utc("2025-01-18T12:00:00Z"), true),
+            Some(utc("2025-01-21T09:00:00Z"))
+        );
+        assert_eq!(
+            calendar.next_trading_day(ymd(2025, 1, 18)),
+            ymd(2025, 1, 21)
+        );
+        assert_eq!(
+            calendar.trading_days(ymd(2025, 1, 17), ymd(2025, 1, 21)),
+            [ymd(2025, 1, 17), ymd(2025, 1, 21)]
+        );
+    }
+
+    #[test]
+    fn crypto_never_closes() {
+        let calendar = TradingCalendar::new(Market::Crypto);
+        let saturday = utc("2025-01-11T03:00:00Z");
+        assert_eq!(calendar.session_at(saturday), Session::Regular);
+        assert_eq!(calendar.next_open(saturday, false), Some(saturday));
+        assert!(calendar.is_trading_day(ymd(2025, 12, 25)));
+    }
+}
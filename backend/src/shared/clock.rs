@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+// Code that needs the time asks a `Clock` instead of calling `Utc::now()`, so
+// the same logic runs live and in replay:
+//
+//   WallClock       the system clock; live agents
+//   SimulatedClock  event time: moves forward to each replayed event's timestamp
+//   ManualClock     moved only by the caller; deterministic tests
+//
+// `sleep_until` on the last two waits until the clock is moved past the
+// deadline, however long that takes in real time.
+
+/// Source of the current time, shared as `Arc<dyn Clock>`
+#[async_trait]
+pub trait Clock: fmt::Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Resolves once `now()` has reached `deadline`
+    async fn sleep_until(&self, deadline: DateTime<Utc>);
+
+    /// `now()` in Unix nanoseconds, the unit of `MarketEvent` timestamps
+    fn now_ns(&self) -> u64 {
+        self.now().timestamp_nanos_opt().unwrap_or_default().max(0) as u64
+    }
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WallClock;
+
+impl WallClock {
+    /// A `WallClock` ready to hand to the components that take a clock
+    pub fn shared() -> Arc<dyn Clock> {
+        Arc::new(WallClock)
+    }
+}
+
+#[async_trait]
+impl Clock for WallClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        let wait = (deadline - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Time held in a watch channel, so sleepers wake when it moves
+#[derive(Debug, Clone)]
+struct SharedTime {
+    tx: Arc<watch::Sender<DateTime<Utc>>>,
+}
+
+impl SharedTime {
+    fn new(start: DateTime<Utc>) -> Self {
+        let (tx, _) = watch::channel(start);
+        Self { tx: Arc::new(tx) }
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        *self.tx.borrow()
+    }
+
+    /// Moves to `ts` unless that would go backwards
+    fn advance_to(&self, ts: DateTime<Utc>) {
+        self.tx.send_if_modified(|now| {
+            let later = ts > *now;
+            if later {
+                *now = ts;
+            }
+            later
+        });
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        let mut rx = self.tx.subscribe();
+        // Only fails if the sender is gone, and `self` holds it
+        let _ = rx.wait_for(|now| *now >= deadline).await;
+    }
+}
+
+/// Event time for replays: reads as the timestamp of the latest event seen
+#[derive(Debug, Clone)]
+pub struct SimulatedClock {
+    time: SharedTime,
+}
+
+impl SimulatedClock {
+    /// Starts at `start`, typically the first event's timestamp
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            time: SharedTime::new(start),
+        }
+    }
+
+    /// Records an event at `ts`; out-of-order events never move the clock back
+    pub fn observe(&self, ts: DateTime<Utc>) {
+        self.time.advance_to(ts);
+    }
+}
+
+#[async_trait]
+impl Clock for SimulatedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.time.now()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        self.time.sleep_until(deadline).await;
+    }
+}
+
+/// A clock that stands still until the caller moves it
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    time: SharedTime,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            time: SharedTime::new(start),
+        }
+    }
+
+    /// Moves forward by `by`; negative durations are ignored
+    pub fn advance(&self, by: Duration) {
+        self.time.advance_to(self.time.now() + by);
+    }
+
+    /// Moves forward to `ts`; earlier times are ignored
+    pub fn set(&self, ts: DateTime<Utc>) {
+        self.time.advance_to(ts);
+    }
+}
+
+#[async_trait]
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.time.now()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        self.time.sleep_until(deadline).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::Duration as StdDuration;
+    use tokio::time::timeout;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 3, 14, 30, 0).unwrap()
+    }
+
+    /// Whether `sleep_until(deadline)` resolves without the clock moving further
+    async fn wakes(clock: &dyn Clock, deadline: DateTime<Utc>) -> bool {
+        timeout(StdDuration::from_millis(50), clock.sleep_until(deadline))
+            .await
+            .is_ok()
+    }
+
+    #[test]
+    fn manual_clock_only_moves_forward() {
+        let clock = ManualClock::new(start());
+        assert_eq!(clock.now(), start());
+
+        clock.advance(Duration::seconds(90));
+        assert_eq!(clock.now(), start() + Duration::seconds(90));
+        clock.advance(Duration::seconds(-30));
+        assert_eq!(clock.now(), start() + Duration::seconds(90));
+
+        clock.set(start());
+        assert_eq!(clock.now(), start() + Duration::seconds(90));
+        clock.set(start() + Duration::hours(1));
+        assert_eq!(clock.now(), start() + Duration::hours(1));
+        assert_eq!(
+            clock.now_ns(),
+            (start() + Duration::hours(1))
+                .timestamp_nanos_opt()
+                .unwrap() as u64
+        );
+    }
+
+    #[tokio::test]
+    async fn manual_clock_wakes_sleepers_when_moved_past_the_deadline() {
+        let clock = ManualClock::new(start());
+        let deadline = start() + Duration::minutes(5);
+        assert!(!wakes(&clock, deadline).await);
+
+        let sleeper = {
+            let clock = clock.clone();
+            tokio::spawn(async move { clock.sleep_until(deadline).await })
+        };
+        clock.advance(Duration::minutes(4));
+        tokio::task::yield_now().await;
+        assert!(!sleeper.is_finished());
+
+        clock.advance(Duration::minutes(1));
+        timeout(StdDuration::from_secs(1), sleeper)
+            .await
+            .expect("sleeper woke")
+            .unwrap();
+
+        clock.set(start() + Duration::hours(1));
+        assert!(wakes(&clock, start() + Duration::minutes(30)).await);
+    }
+
+    #[tokio::test]
+    async fn simulated_clock_follows_observed_events() {
+        let clock = SimulatedClock::new(start());
+        let later = start() + Duration::seconds(10);
+        assert!(!wakes(&clock, later).await);
+
+        clock.observe(later);
+        assert_eq!(clock.now(), later);
+        assert!(wakes(&clock, later).await);
+
+        // A late event does not move event time back
+        clock.observe(start() + Duration::seconds(5));
+        assert_eq!(clock.now(), later);
+    }
+}
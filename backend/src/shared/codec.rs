@@ -0,0 +1,429 @@
+use crate::shared::market_data_generated::market_data as fb;
+use crate::shared::market_event::{
+    Bar, EventPayload, Gap, Greeks, MarketEvent, OptionQuote, OptionRight, OrderBookUpdate,
+    PriceLevel, Quote, Status, Trade, TradingStatus,
+};
+use flatbuffers::{FlatBufferBuilder, InvalidFlatbuffer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Message header naming the codec a payload was encoded with
+pub const CODEC_HEADER: &str = "optitrade-codec";
+
+/// Wire format of a topic, chosen per topic under `[topics.<name>]`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WireFormat {
+    #[default]
+    Json,
+    Flatbuffers,
+}
+
+impl WireFormat {
+    /// Value written to the `optitrade-codec` header
+    pub fn header_value(&self) -> &'static str {
+        match self {
+            WireFormat::Json => "json",
+            WireFormat::Flatbuffers => "flatbuffers",
+        }
+    }
+
+    pub fn from_header(value: &[u8]) -> Result<Self, CodecError> {
+        match value {
+            b"json" => Ok(WireFormat::Json),
+            b"flatbuffers" => Ok(WireFormat::Flatbuffers),
+            other => Err(CodecError::UnknownFormat(
+                String::from_utf8_lossy(other).into_owned(),
+            )),
+        }
+    }
+
+    /// Guesses the format of a payload published without a codec header,
+    /// using the FlatBuffers file identifier
+    pub fn sniff(payload: &[u8]) -> Self {
+        if fb::market_event_buffer_has_identifier(payload) {
+            WireFormat::Flatbuffers
+        } else {
+            WireFormat::Json
+        }
+    }
+}
+
+impl fmt::Display for WireFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.header_value())
+    }
+}
+
+#[derive(Debug)]
+pub enum CodecError {
+    Json(serde_json::Error),
+    Flatbuffers(InvalidFlatbuffer),
+    MissingField(&'static str),
+    UnknownEventType(String),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Json(e) => write!(f, "invalid JSON market event: {}", e),
+            CodecError::Flatbuffers(e) => write!(f, "invalid FlatBuffers market event: {}", e),
+            CodecError::MissingField(field) => write!(f, "market event is missing '{}'", field),
+            CodecError::UnknownEventType(kind) => write!(f, "unknown event type '{}'", kind),
+            CodecError::UnknownFormat(format) => write!(f, "unknown codec '{}'", format),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodecError::Json(e) => Some(e),
+            CodecError::Flatbuffers(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for CodecError {
+    fn from(e: serde_json::Error) -> Self {
+        CodecError::Json(e)
+    }
+}
+
+impl From<InvalidFlatbuffer> for CodecError {
+    fn from(e: InvalidFlatbuffer) -> Self {
+        CodecError::Flatbuffers(e)
+    }
+}
+
+/// Serializes `MarketEvent`s for the message bus
+pub trait MarketEventCodec: Send + Sync {
+    fn format(&self) -> WireFormat;
+
+    fn encode(&self, event: &MarketEvent) -> Result<Vec<u8>, CodecError>;
+
+    fn decode(&self, payload: &[u8]) -> Result<MarketEvent, CodecError>;
+}
+
+/// Human-readable codec, the default for every topic
+pub struct JsonCodec;
+
+impl MarketEventCodec for JsonCodec {
+    fn format(&self) -> WireFormat {
+        WireFormat::Json
+    }
+
+    fn encode(&self, event: &MarketEvent) -> Result<Vec<u8>, CodecError> {
+        Ok(serde_json::to_vec(event)?)
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<MarketEvent, CodecError> {
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
+
+/// Binary codec using the `schema.fbs` tables
+pub struct FlatBuffersCodec;
+
+impl FlatBuffersCodec {
+    /// Verifies `payload` and returns a zero-copy view into it, for hot paths
+    /// that only need a few fields and don't want to allocate a `MarketEvent`
+    pub fn view(payload: &[u8]) -> Result<fb::MarketEvent<'_>, CodecError> {
+        Ok(fb::root_as_market_event(payload)?)
+    }
+}
+
+impl MarketEventCodec for FlatBuffersCodec {
+    fn format(&self) -> WireFormat {
+        WireFormat::Flatbuffers
+    }
+
+    fn encode(&self, event: &MarketEvent) -> Result<Vec<u8>, CodecError> {
+        let mut builder = FlatBufferBuilder::with_capacity(128);
+        let symbol = builder.create_string(&event.symbol);
+
+        let (payload_type, payload) = match &event.payload {
+            EventPayload::Quote(q) => {
+                let quote = fb::Quote::create(
+                    &mut builder,
+                    &fb::QuoteArgs {
+                        bid_price: q.bid_price,
+                        ask_price: q.ask_price,
+                        bid_size: q.bid_size,
+                        ask_size: q.ask_size,
+                    },
+                );
+                (fb::Payload::Quote, quote.as_union_value())
+            }
+            EventPayload::Trade(t) => {
+                let trade = fb::Trade::create(
+                    &mut builder,
+                    &fb::TradeArgs {
+                        price: t.price,
+                        quantity: t.quantity,
+                        trade_id: t.trade_id,
+                    },
+                );
+                (fb::Payload::Trade, trade.as_union_value())
+            }
+            EventPayload::Bar(b) => {
+                let bar = fb::Bar::create(
+                    &mut builder,
+                    &fb::BarArgs {
+                        open: b.open,
+                        high: b.high,
+                        low: b.low,
+                        close: b.close,
+                        volume: b.volume,
+                        vwap: b.vwap,
+                        trade_count: b.trade_count,
+                        interval_ns: b.interval_ns,
+                    },
+                );
+                (fb::Payload::Bar, bar.as_union_value())
+            }
+            EventPayload::OrderBook(book) => {
+                let bids: Vec<fb::PriceLevel> = book
+                    .bids
+                    .iter()
+                    .map(|l| fb::PriceLevel::new(l.price, l.quantity))
+                    .collect();
+                let asks: Vec<fb::PriceLevel> = book
+                    .asks
+                    .iter()
+                    .map(|l| fb::PriceLevel::new(l.price, l.quantity))
+                    .collect();
+                let bids = builder.create_vector(&bids);
+                let asks = builder.create_vector(&asks);
+                let update = fb::OrderBookUpdate::create(
+                    &mut builder,
+                    &fb::OrderBookUpdateArgs {
+                        sequence: book.sequence,
+                        is_snapshot: book.is_snapshot,
+                        bids: Some(bids),
+                        asks: Some(asks),
+                    },
+                );
+                (fb::Payload::OrderBookUpdate, update.as_union_value())
+            }
+            EventPayload::OptionQuote(o) => {
+                let underlying = builder.create_string(&o.underlying);
+                let greeks = fb::Greeks::new(
+                    o.greeks.delta,
+                    o.greeks.gamma,
+                    o.greeks.theta,
+                    o.greeks.vega,
+                    o.greeks.rho,
+                );
+                let quote = fb::OptionQuote::create(
+                    &mut builder,
+                    &fb::OptionQuoteArgs {
+                        underlying: Some(underlying),
+                        strike: o.strike,
+                        expiry: o.expiry,
+                        right: match o.right {
+                            OptionRight::Call => fb::OptionRight::Call,
+                            OptionRight::Put => fb::OptionRight::Put,
+                        },
+                        bid_price: o.bid_price,
+                        ask_price: o.ask_price,
+                        last_price: o.last_price,
+                        implied_volatility: o.implied_volatility,
+                        greeks: Some(&greeks),
+                    },
+                );
+                (fb::Payload::OptionQuote, quote.as_union_value())
+            }
+            EventPayload::Gap(g) => {
+                let gap = fb::Gap::create(
+                    &mut builder,
+                    &fb::GapArgs {
+                        since_ns: g.since_ns,
+                    },
+                );
+                (fb::Payload::Gap, gap.as_union_value())
+            }
+            EventPayload::Status(s) => {
+                let reason = builder.create_string(&s.reason);
+                let status = fb::Status::create(
+                    &mut builder,
+                    &fb::StatusArgs {
+                        status: match s.status {
+                            TradingStatus::Trading => fb::TradingStatus::Trading,
+                            TradingStatus::Halted => fb::TradingStatus::Halted,
+                            TradingStatus::Paused => fb::TradingStatus::Paused,
+                            TradingStatus::QuotationOnly => fb::TradingStatus::QuotationOnly,
+                        },
+                        reason: Some(reason),
+                    },
+                );
+                (fb::Payload::Status, status.as_union_value())
+            }
+        };
+
+        let root = fb::MarketEvent::create(
+            &mut builder,
+            &fb::MarketEventArgs {
+                symbol: Some(symbol),
+                exchange_ts_ns: event.exchange_ts_ns,
+                receive_ts_ns: event.receive_ts_ns,
+                payload_type,
+                payload: Some(payload),
+            },
+        );
+        fb::finish_market_event_buffer(&mut builder, root);
+        Ok(builder.finished_data().to_vec())
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<MarketEvent, CodecError> {
+        let event = Self::view(payload)?;
+        let missing = |field| CodecError::MissingField(field);
+
+        let decoded = match event.payload_type() {
+            fb::Payload::Quote => {
+                let q = event.payload_as_quote().ok_or(missing("payload"))?;
+                EventPayload::Quote(Quote {
+                    bid_price: q.bid_price(),
+                    ask_price: q.ask_price(),
+                    bid_size: q.bid_size(),
+                    ask_size: q.ask_size(),
+                })
+            }
+            fb::Payload::Trade => {
+                let t = event.payload_as_trade().ok_or(missing("payload"))?;
+                EventPayload::Trade(Trade {
+                    price: t.price(),
+                    quantity: t.quantity(),
+                    trade_id: t.trade_id(),
+                })
+            }
+            fb::Payload::Bar => {
+                let b = event.payload_as_bar().ok_or(missing("payload"))?;
+                EventPayload::Bar(Bar {
+                    open: b.open(),
+                    high: b.high(),
+                    low: b.low(),
+                    close: b.close(),
+                    volume: b.volume(),
+                    vwap: b.vwap(),
+                    trade_count: b.trade_count(),
+                    interval_ns: b.interval_ns(),
+                })
+            }
+            fb::Payload::OrderBookUpdate => {
+                let u = event
+                    .payload_as_order_book_update()
+                    .ok_or(missing("payload"))?;
+                let levels = |v: Option<flatbuffers::Vector<'_, fb::PriceLevel>>| {
+                    v.map(|v| {
+                        v.iter()
+                            .map(|l| PriceLevel {
+                                price: l.price(),
+                                quantity: l.quantity(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+                };
+                EventPayload::OrderBook(OrderBookUpdate {
+                    sequence: u.sequence(),
+                    is_snapshot: u.is_snapshot(),
+                    bids: levels(u.bids()),
+                    asks: levels(u.asks()),
+                })
+            }
+            fb::Payload::OptionQuote => {
+                let o = event.payload_as_option_quote().ok_or(missing("payload"))?;
+                let greeks = o
+                    .greeks()
+                    .map(|g| Greeks {
+                        delta: g.delta(),
+                        gamma: g.gamma(),
+                        theta: g.theta(),
+                        vega: g.vega(),
+                        rho: g.rho(),
+                    })
+                    .unwrap_or_default();
+                EventPayload::OptionQuote(OptionQuote {
+                    underlying: o.underlying().ok_or(missing("underlying"))?.to_string(),
+                    strike: o.strike(),
+                    expiry: o.expiry(),
+                    right: match o.right() {
+                        fb::OptionRight::Call => OptionRight::Call,
+                        fb::OptionRight::Put => OptionRight::Put,
+                        other => {
+                            return Err(CodecError::UnknownEventType(format!(
+                                "option right {}",
+                                other.0
+                            )))
+                        }
+                    },
+                    bid_price: o.bid_price(),
+                    ask_price: o.ask_price(),
+                    last_price: o.last_price(),
+                    implied_volatility: o.implied_volatility(),
+                    greeks,
+                })
+            }
+            fb::Payload::Gap => {
+                let g = event.payload_as_gap().ok_or(missing("payload"))?;
+                EventPayload::Gap(Gap {
+                    since_ns: g.since_ns(),
+                })
+            }
+            fb::Payload::Status => {
+                let s = event.payload_as_status().ok_or(missing("payload"))?;
+                EventPayload::Status(Status {
+                    status: match s.status() {
+                        fb::TradingStatus::Trading => TradingStatus::Trading,
+                        fb::TradingStatus::Halted => TradingStatus::Halted,
+                        fb::TradingStatus::Paused => TradingStatus::Paused,
+                        fb::TradingStatus::QuotationOnly => TradingStatus::QuotationOnly,
+                        other => {
+                            return Err(CodecError::UnknownEventType(format!(
+                                "trading status {}",
+                                other.0
+                            )))
+                        }
+                    },
+                    reason: s.reason().unwrap_or_default().to_string(),
+                })
+            }
+            fb::Payload::NONE => return Err(missing("payload")),
+            other => return Err(CodecError::UnknownEventType(format!("{:?}", other))),
+        };
+
+        Ok(MarketEvent {
+            symbol: event.symbol().ok_or(missing("symbol"))?.to_string(),
+            exchange_ts_ns: event.exchange_ts_ns(),
+            receive_ts_ns: event.receive_ts_ns(),
+            payload: decoded,
+        })
+    }
+}
+
+static JSON_CODEC: JsonCodec = JsonCodec;
+static FLATBUFFERS_CODEC: FlatBuffersCodec = FlatBuffersCodec;
+
+/// Returns the codec implementing `format`
+pub fn codec_for(format: WireFormat) -> &'static dyn MarketEventCodec {
+    match format {
+        WireFormat::Json => &JSON_CODEC,
+        WireFormat::Flatbuffers => &FLATBUFFERS_CODEC,
+    }
+}
+
+/// Decodes a payload using its `optitrade-codec` header, sniffing the format
+/// for messages published before the header existed
+pub fn decode_market_event(
+    codec_header: Option<&[u8]>,
+    payload: &[u8],
+) -> Result<MarketEvent, CodecError> {
+    let format = match codec_header {
+        Some(value) => WireFormat::from_header(value)?,
+        None => WireFormat::sniff(payload),
+    };
+    codec_for(format).decode(payload)
+}
@@ -1,57 +1,1420 @@
+use crate::shared::calendar::{Market, TradingCalendar};
+use crate::shared::codec::WireFormat;
+use crate::shared::corporate_actions::PriceAdjustment;
+use crate::shared::data_loader::{Adjustment, AlpacaFeed, Timeframe};
+use crate::shared::features::{FeatureKind, FeatureSpec};
+use crate::shared::instrument::Instrument;
+use crate::shared::money::{Price, Quantity};
+use crate::shared::secrets::{
+    EnvSecrets, FileSecrets, Keystore, Secret, SecretError, SecretsProvider,
+    KEYSTORE_PASSPHRASE_ENV,
+};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
 use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use toml::{Table, Value};
+use tracing_subscriber::EnvFilter;
+use url::Url;
 
-#[derive(Debug, Deserialize, Clone)]
+/// Environment variable pointing at the config file
+pub const CONFIG_PATH_ENV: &str = "OPTITRADE_CONFIG";
+/// Environment variable selecting the active profile (dev, paper, live)
+pub const PROFILE_ENV: &str = "OPTITRADE_PROFILE";
+/// Prefix for per-field overrides, e.g. `OPTITRADE_ALPACA__API_KEY`
+pub const ENV_PREFIX: &str = "OPTITRADE_";
+/// Separator between section and field names in override variables
+const ENV_SEPARATOR: &str = "__";
+/// Locations searched when neither an explicit path nor `OPTITRADE_CONFIG` is given
+const DEFAULT_PATHS: [&str; 2] = ["config.toml", "backend/config.toml"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct Config {
+    #[serde(skip)]
+    pub profile: Profile,
     pub data_provider: DataProvider,
     pub backtest: BacktestConfig,
+    pub database: DatabaseConfig,
+    pub cache: CacheConfig,
+    pub corporate_actions: CorporateActionsConfig,
+    pub calendar: CalendarConfig,
+    pub execution: ExecutionConfig,
+    pub instruments: InstrumentsConfig,
+    pub market_data: MarketDataConfig,
     pub alpaca: AlpacaConfig,
     pub ib: IbConfig,
+    pub secrets: SecretsConfig,
+    pub bus: BusConfig,
+    pub topics: BTreeMap<String, TopicConfig>,
+    pub logging: LoggingConfig,
+    pub metrics: MetricsConfig,
+    pub runtime: RuntimeConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    #[default]
+    Dev,
+    Paper,
+    Live,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
 pub struct DataProvider {
-    pub use_provider: String, // "alpaca" or "ib"
+    pub use_provider: Provider,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Live market data provider
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    Alpaca,
+    Ib,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct BacktestConfig {
-    pub data_source: String, // "db" or "alpaca"
+    pub data_source: HistoricalSource,
+    pub timeframe: Timeframe, // Bar size requested from Alpaca, e.g. "1Day", "15Min"
+    pub feed: Option<AlpacaFeed>, // Alpaca feed (sip, iex, otc); the account's default when unset
+    pub adjustment: Adjustment, // Corporate action adjustment applied by Alpaca
+    pub path: String,         // csv/parquet: a file, or a template like "data/{symbol}.csv"
+    pub table: String,        // db: table holding the bars
+    pub features: Vec<FeatureSpec>, // Rolling features computed over the bars, e.g. "sma_50", "rsi_14"
+    pub price_adjustment: PriceAdjustment, // Local split/total-return adjustment from [corporate_actions]
+}
+
+/// Where the backtester loads historical prices from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoricalSource {
+    Db,
+    #[default]
+    Alpaca,
+    Csv,
+    Parquet,
+}
+
+/// Where splits and dividends for price adjustment come from
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorporateActionsConfig {
+    pub source: CorporateActionSource,
+    pub path: String, // csv: symbol,ex_date,action,value rows
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CorporateActionSource {
+    #[default]
+    Alpaca,
+    Csv,
+}
+
+/// Trading hours used by the execution agent, backtester and market data agent
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalendarConfig {
+    pub extended_hours: bool,     // Treat pre-market and after-hours as open
+    pub closures: Vec<NaiveDate>, // Extra full-day closures on top of the exchange holidays
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutionConfig {
+    pub out_of_session: OutOfSession,
+}
+
+/// What the execution agent does with an order that arrives while the market is closed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutOfSession {
+    #[default]
+    Reject,
+    Queue, // Hold it and submit at the next open
+}
+
+/// Instrument reference data (tick size, multiplier, lot size)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstrumentsConfig {
+    pub path: String, // TOML file; instruments.toml or backend/instruments.toml when empty
+}
+
+/// Instruments the market data agent streams from startup. Strategies add
+/// more at runtime on the `market_data_control` topic.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketDataConfig {
+    pub symbols: Vec<String>, // As Alpaca writes them: AAPL, BTC/USD, AAPL250221C00200000
+}
+
+/// Log level filter and output format shared by every agent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub filter: String, // tracing filter directives, e.g. "info,execution_agent=debug"; RUST_LOG wins when set
+    pub format: LogFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json, // One JSON object per line, for log shippers
+}
+
+/// Prometheus endpoint served by market_data, storage_agent and execution_agent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    pub enabled: bool, // Serve GET /metrics alongside the health endpoints
+}
+
+/// HTTP endpoints and shutdown behaviour shared by the long-running agents
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    pub listen: String,             // host:port for /healthz, /readyz and /metrics
+    pub shutdown_timeout_secs: u64, // How long to wait for the bus to flush on shutdown
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Local cache of historical data fetched from remote sources
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub dir: PathBuf,
+}
+
+/// TimescaleDB connection shared by the storage agent and the backtester
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: Secret, // Resolved from `[secrets]` (database/password) when not set here
+    pub dbname: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct AlpacaConfig {
-    pub api_key: String,
-    pub api_secret: String,
+    pub api_key: Secret, // Resolved from `[secrets]` when not set here
+    pub api_secret: Secret,
     pub base_url: String, // Used for REST API calls (trading, historical data)
     pub historic_url: String,
-    pub websocket_url: String, // Used for live market data streaming
+    pub websocket_url: String,        // Used for live market data streaming
+    pub crypto_websocket_url: String, // Crypto pairs stream on their own feed
+    pub options_poll_secs: u64,       // How often each watched option chain is refreshed
+    pub options_requests_per_minute: u32, // REST budget for option chain polling
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct IbConfig {
     pub host: String,
     pub port: u16,
     pub client_id: u32,
 }
 
+/// Message bus backend shared by every agent
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct BusConfig {
+    pub backend: BusBackend,
+    pub brokers: Vec<String>, // Backend default when empty
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BusBackend {
+    #[default]
+    Kafka,
+    Nats,
+    Memory, // In-process only: tests and single-binary deployments
+}
+
+/// Per-topic settings under `[topics.<name>]`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopicConfig {
+    pub format: WireFormat,
+}
+
+/// Where broker credentials are read from when they are not set inline
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecretsConfig {
+    pub provider: SecretsBackend,
+    pub env_prefix: String, // Used by "env": OPTITRADE_SECRET_ALPACA_API_KEY
+    pub dir: PathBuf,       // Used by "file": <dir>/alpaca/api_key
+    pub keystore_path: PathBuf, // Used by "keystore", unlocked with OPTITRADE_KEYSTORE_PASSPHRASE
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsBackend {
+    #[default]
+    Env,
+    File,
+    Keystore,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TradeSignal {
-    pub symbol: String,
-    pub qty: i32,
-    pub side: String,
+    pub symbol: Instrument,
+    pub qty: Quantity,
+    #[serde(alias = "side")]
+    pub action: SignalAction,
+}
+
+/// Order side sent to the broker
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    #[serde(alias = "BUY")]
+    Buy,
+    #[serde(alias = "SELL")]
+    Sell,
+}
+
+/// What a trade signal asks the execution agent to do
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalAction {
+    #[serde(alias = "BUY")]
+    Buy,
+    #[serde(alias = "SELL")]
+    Sell,
+    #[serde(alias = "CANCEL")]
+    Cancel,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MarketData {
-    pub symbol: String,
-    pub price: f64,
+    pub symbol: Instrument,
+    pub price: Price,
     pub moving_average_50: f64,
     pub moving_average_200: f64,
+    #[serde(default)]
+    pub features: BTreeMap<String, f64>, // Every configured feature, keyed like "rsi_14"
+}
+
+impl Default for AlpacaConfig {
+    fn default() -> Self {
+        Self {
+            api_key: Secret::default(),
+            api_secret: Secret::default(),
+            base_url: "https://paper-api.alpaca.markets".to_string(),
+            historic_url: "https://data.alpaca.markets".to_string(),
+            websocket_url: "wss://stream.data.alpaca.markets/v2/iex".to_string(),
+            crypto_websocket_url: "wss://stream.data.alpaca.markets/v1beta3/crypto/us".to_string(),
+            options_poll_secs: 30,
+            options_requests_per_minute: 100,
+        }
+    }
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            data_source: HistoricalSource::default(),
+            timeframe: Timeframe::default(),
+            feed: None,
+            adjustment: Adjustment::default(),
+            path: String::new(),
+            table: "historical_bars".to_string(),
+            features: vec![
+                FeatureSpec::new(FeatureKind::Sma, 50),
+                FeatureSpec::new(FeatureKind::Sma, 200),
+            ],
+            price_adjustment: PriceAdjustment::default(),
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: PathBuf::from("~/.cache/optitrade"),
+        }
+    }
+}
+
+impl Default for MarketDataConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec!["AAPL".to_string(), "TSLA".to_string(), "NVDA".to_string()],
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            filter: "info".to_string(),
+            format: LogFormat::default(),
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            listen: "0.0.0.0:8080".to_string(),
+            shutdown_timeout_secs: 10,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_secs)
+    }
+}
+
+impl CacheConfig {
+    /// `dir` with a leading `~/` expanded
+    pub fn dir(&self) -> PathBuf {
+        expand_home(&self.dir)
+    }
+}
+
+impl CalendarConfig {
+    /// The calendar for `market`, including any configured closures
+    pub fn calendar(&self, market: Market) -> TradingCalendar {
+        TradingCalendar::new(market).with_closures(self.closures.iter().copied())
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 5433,
+            user: "optitrade".to_string(),
+            password: Secret::default(),
+            dbname: "market_data".to_string(),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// libpq-style connection string for `tokio_postgres::connect`
+    pub fn connection_string(&self) -> String {
+        let mut conn = format!(
+            "host={} port={} user={} dbname={}",
+            self.host, self.port, self.user, self.dbname
+        );
+        if !self.password.is_empty() {
+            conn.push_str(&format!(
+                " password='{}'",
+                escape_conn_value(self.password.expose())
+            ));
+        }
+        conn
+    }
+}
+
+impl Default for IbConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 4002,
+            client_id: 100,
+        }
+    }
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            provider: SecretsBackend::Env,
+            env_prefix: "OPTITRADE_SECRET_".to_string(),
+            dir: PathBuf::from("/var/run/secrets/optitrade"),
+            keystore_path: PathBuf::from("~/.config/optitrade/keystore.json"),
+        }
+    }
+}
+
+impl SecretsConfig {
+    /// Builds the configured provider, reading the keystore passphrase from the process environment
+    pub fn build_provider(&self) -> Result<Box<dyn SecretsProvider>, SecretError> {
+        self.build_provider_from(&env::vars().collect::<Vec<_>>())
+    }
+
+    fn build_provider_from(
+        &self,
+        vars: &[(String, String)],
+    ) -> Result<Box<dyn SecretsProvider>, SecretError> {
+        match self.provider {
+            SecretsBackend::Env => Ok(Box::new(EnvSecrets::with_vars(
+                self.env_prefix.clone(),
+                vars.iter().cloned(),
+            ))),
+            SecretsBackend::File => Ok(Box::new(FileSecrets::new(expand_home(&self.dir)))),
+            SecretsBackend::Keystore => {
+                let passphrase = vars
+                    .iter()
+                    .find(|(k, _)| k == KEYSTORE_PASSPHRASE_ENV)
+                    .map(|(_, v)| Secret::new(v.clone()))
+                    .ok_or(SecretError::MissingPassphrase)?;
+                Ok(Box::new(Keystore::open(
+                    expand_home(&self.keystore_path),
+                    &passphrase,
+                )?))
+            }
+        }
+    }
+}
+
+impl BusConfig {
+    /// Configured brokers, or the backend's local default
+    pub fn brokers(&self) -> Vec<String> {
+        if !self.brokers.is_empty() {
+            return self.brokers.clone();
+        }
+        match self.backend {
+            BusBackend::Kafka => vec!["localhost:9093".to_string()],
+            BusBackend::Nats => vec!["nats://localhost:4222".to_string()],
+            BusBackend::Memory => Vec::new(),
+        }
+    }
+}
+
+impl BusBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BusBackend::Kafka => "kafka",
+            BusBackend::Nats => "nats",
+            BusBackend::Memory => "memory",
+        }
+    }
+}
+
+impl fmt::Display for BusBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Provider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::Alpaca => "alpaca",
+            Provider::Ib => "ib",
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl CorporateActionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CorporateActionSource::Alpaca => "alpaca",
+            CorporateActionSource::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for CorporateActionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl OutOfSession {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutOfSession::Reject => "reject",
+            OutOfSession::Queue => "queue",
+        }
+    }
+}
+
+impl fmt::Display for OutOfSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
-/// Reads `config.toml` and loads provider configuration
-pub fn load_config() -> Config {
-    let config_str = fs::read_to_string("backend/config.toml").expect("Failed to read config file");
-    toml::from_str(&config_str).expect("Failed to parse config file")
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl HistoricalSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoricalSource::Db => "db",
+            HistoricalSource::Alpaca => "alpaca",
+            HistoricalSource::Csv => "csv",
+            HistoricalSource::Parquet => "parquet",
+        }
+    }
+}
+
+impl fmt::Display for HistoricalSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SignalAction {
+    /// The order side for buy/sell signals, `None` for cancels
+    pub fn side(&self) -> Option<Side> {
+        match self {
+            SignalAction::Buy => Some(Side::Buy),
+            SignalAction::Sell => Some(Side::Sell),
+            SignalAction::Cancel => None,
+        }
+    }
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Dev => "dev",
+            Profile::Paper => "paper",
+            Profile::Live => "live",
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Profile {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dev" => Ok(Profile::Dev),
+            "paper" => Ok(Profile::Paper),
+            "live" => Ok(Profile::Live),
+            other => Err(ConfigError::UnknownProfile(other.to_string())),
+        }
+    }
+}
+
+/// A single invalid configuration value, addressed by its dotted path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    UnknownProfile(String),
+    Invalid(Vec<FieldError>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(
+                    f,
+                    "failed to read config file {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            ConfigError::Parse { path, source } => {
+                write!(
+                    f,
+                    "failed to parse config file {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            ConfigError::UnknownProfile(profile) => write!(
+                f,
+                "unknown profile '{}' (expected dev, paper or live)",
+                profile
+            ),
+            ConfigError::Invalid(errors) => {
+                write!(f, "invalid configuration ({} errors)", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Builds a `Config` from layered sources, lowest precedence first:
+/// built-in defaults, the config file, the active `[profiles.<name>]` table
+/// in that file, and finally `OPTITRADE_<SECTION>__<FIELD>` environment variables.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    path: Option<PathBuf>,
+    profile: Option<Profile>,
+    env: Option<Vec<(String, String)>>,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the config from `path` instead of `OPTITRADE_CONFIG` or the default locations
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Selects the profile instead of reading `OPTITRADE_PROFILE`
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Uses the given variables instead of the process environment
+    pub fn with_env<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Some(
+            vars.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        );
+        self
+    }
+
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        let vars = self.env_vars();
+        let lookup = |name: &str| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+
+        let profile = match self.profile {
+            Some(profile) => profile,
+            None => match lookup(PROFILE_ENV) {
+                Some(name) => name.parse()?,
+                None => Profile::default(),
+            },
+        };
+
+        let mut merged = defaults_table();
+        let sections: Vec<String> = merged.keys().cloned().collect();
+        let mut errors = Vec::new();
+
+        if let Some(path) = self.resolve_path(lookup(CONFIG_PATH_ENV)) {
+            let mut file_table = read_table(&path)?;
+            let profiles = file_table.remove("profiles");
+            merge(&mut merged, file_table);
+
+            if let Some(Value::Table(mut profiles)) = profiles {
+                if let Some(overlay) = profiles.remove(profile.as_str()) {
+                    match overlay {
+                        Value::Table(overlay) => merge(&mut merged, overlay),
+                        _ => errors.push(FieldError::new(
+                            format!("profiles.{}", profile),
+                            "must be a table",
+                        )),
+                    }
+                }
+            }
+        }
+
+        let overrides = apply_env_overrides(&mut merged, &vars, &mut errors);
+        for name in merged.keys().filter(|name| !sections.contains(name)) {
+            errors.push(FieldError::new(
+                with_origin(name, &overrides),
+                "unknown section",
+            ));
+        }
+
+        let mut config = Config {
+            profile,
+            data_provider: section(&merged, "data_provider", &overrides, &mut errors),
+            backtest: section(&merged, "backtest", &overrides, &mut errors),
+            database: section(&merged, "database", &overrides, &mut errors),
+            cache: section(&merged, "cache", &overrides, &mut errors),
+            corporate_actions: section(&merged, "corporate_actions", &overrides, &mut errors),
+            calendar: section(&merged, "calendar", &overrides, &mut errors),
+            execution: section(&merged, "execution", &overrides, &mut errors),
+            instruments: section(&merged, "instruments", &overrides, &mut errors),
+            market_data: section(&merged, "market_data", &overrides, &mut errors),
+            alpaca: section(&merged, "alpaca", &overrides, &mut errors),
+            ib: section(&merged, "ib", &overrides, &mut errors),
+            secrets: section(&merged, "secrets", &overrides, &mut errors),
+            bus: section(&merged, "bus", &overrides, &mut errors),
+            topics: section(&merged, "topics", &overrides, &mut errors),
+            logging: section(&merged, "logging", &overrides, &mut errors),
+            metrics: section(&merged, "metrics", &overrides, &mut errors),
+            runtime: section(&merged, "runtime", &overrides, &mut errors),
+        };
+
+        resolve_secrets(&mut config, &vars, &mut errors);
+
+        errors.extend(config.validate());
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+
+    fn env_vars(&self) -> Vec<(String, String)> {
+        match &self.env {
+            Some(vars) => vars.clone(),
+            None => env::vars().collect(),
+        }
+    }
+
+    fn resolve_path(&self, from_env: Option<String>) -> Option<PathBuf> {
+        if let Some(path) = &self.path {
+            return Some(path.clone());
+        }
+        if let Some(path) = from_env {
+            return Some(PathBuf::from(path));
+        }
+        DEFAULT_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+    }
+}
+
+impl Config {
+    /// Checks every field and returns all problems found, not just the first
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        let alpaca = &self.alpaca;
+        check_url(
+            &mut errors,
+            "alpaca.base_url",
+            &alpaca.base_url,
+            &["http", "https"],
+        );
+        check_url(
+            &mut errors,
+            "alpaca.historic_url",
+            &alpaca.historic_url,
+            &["http", "https"],
+        );
+        check_url(
+            &mut errors,
+            "alpaca.websocket_url",
+            &alpaca.websocket_url,
+            &["ws", "wss"],
+        );
+        check_url(
+            &mut errors,
+            "alpaca.crypto_websocket_url",
+            &alpaca.crypto_websocket_url,
+            &["ws", "wss"],
+        );
+        if alpaca.options_poll_secs == 0 {
+            errors.push(FieldError::new(
+                "alpaca.options_poll_secs",
+                "must be greater than 0",
+            ));
+        }
+        if alpaca.options_requests_per_minute == 0 {
+            errors.push(FieldError::new(
+                "alpaca.options_requests_per_minute",
+                "must be greater than 0",
+            ));
+        }
+
+        if self.needs_alpaca_credentials() {
+            if alpaca.api_key.is_empty() {
+                errors.push(FieldError::new(
+                    "alpaca.api_key",
+                    "not set inline and not found in the secrets provider",
+                ));
+            }
+            if alpaca.api_secret.is_empty() {
+                errors.push(FieldError::new(
+                    "alpaca.api_secret",
+                    "not set inline and not found in the secrets provider",
+                ));
+            }
+        }
+
+        if self.profile == Profile::Live && alpaca.base_url.contains("paper-api") {
+            errors.push(FieldError::new(
+                "alpaca.base_url",
+                "live profile must not point at the paper trading API",
+            ));
+        }
+
+        if self.ib.host.trim().is_empty() {
+            errors.push(FieldError::new("ib.host", "must not be empty"));
+        }
+        if self.ib.port == 0 {
+            errors.push(FieldError::new("ib.port", "must be a non-zero port"));
+        }
+
+        let backtest = &self.backtest;
+        if matches!(
+            backtest.data_source,
+            HistoricalSource::Csv | HistoricalSource::Parquet
+        ) && backtest.path.trim().is_empty()
+        {
+            errors.push(FieldError::new(
+                "backtest.path",
+                format!("required when data_source is \"{}\"", backtest.data_source),
+            ));
+        }
+        if backtest.table.is_empty()
+            || !backtest
+                .table
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            errors.push(FieldError::new(
+                "backtest.table",
+                "must be a table name (letters, digits, '_' and '.')",
+            ));
+        }
+
+        if backtest.price_adjustment != PriceAdjustment::None {
+            if backtest.data_source == HistoricalSource::Alpaca
+                && backtest.adjustment != Adjustment::Raw
+            {
+                errors.push(FieldError::new(
+                    "backtest.adjustment",
+                    "must be \"raw\" when price_adjustment is set, or prices are adjusted twice",
+                ));
+            }
+            if self.corporate_actions.source == CorporateActionSource::Csv
+                && self.corporate_actions.path.trim().is_empty()
+            {
+                errors.push(FieldError::new(
+                    "corporate_actions.path",
+                    "required when source is \"csv\"",
+                ));
+            }
+        }
+
+        for symbol in &self.market_data.symbols {
+            if let Err(e) = symbol.parse::<Instrument>() {
+                errors.push(FieldError::new("market_data.symbols", e.to_string()));
+            }
+        }
+
+        if self.database.host.trim().is_empty() {
+            errors.push(FieldError::new("database.host", "must not be empty"));
+        }
+        if self.database.port == 0 {
+            errors.push(FieldError::new("database.port", "must be a non-zero port"));
+        }
+
+        if self.bus.brokers.iter().any(|b| b.trim().is_empty()) {
+            errors.push(FieldError::new(
+                "bus.brokers",
+                "must not contain empty entries",
+            ));
+        }
+
+        if let Err(e) = EnvFilter::try_new(&self.logging.filter) {
+            errors.push(FieldError::new("logging.filter", e.to_string()));
+        }
+        if self.runtime.listen.parse::<SocketAddr>().is_err() {
+            errors.push(FieldError::new(
+                "runtime.listen",
+                "must be an address like \"0.0.0.0:8080\"",
+            ));
+        }
+
+        errors
+    }
+
+    /// Wire format configured for `topic`, JSON unless overridden
+    pub fn topic_format(&self, topic: &str) -> WireFormat {
+        self.topics.get(topic).map(|t| t.format).unwrap_or_default()
+    }
+
+    /// Whether any configured component talks to Alpaca
+    pub fn needs_alpaca_credentials(&self) -> bool {
+        self.data_provider.use_provider == Provider::Alpaca
+            || self.backtest.data_source == HistoricalSource::Alpaca
+            || (self.backtest.price_adjustment != PriceAdjustment::None
+                && self.corporate_actions.source == CorporateActionSource::Alpaca)
+    }
+}
+
+/// Loads the config using `OPTITRADE_CONFIG`/`OPTITRADE_PROFILE` and the process environment
+pub fn load_config() -> Result<Config, ConfigError> {
+    ConfigLoader::new().load()
+}
+
+/// Fills empty credentials from the configured secrets provider.
+/// Alpaca keys are required when Alpaca is used; the database password is optional.
+fn resolve_secrets(config: &mut Config, vars: &[(String, String)], errors: &mut Vec<FieldError>) {
+    let wants_alpaca = config.needs_alpaca_credentials()
+        && (config.alpaca.api_key.is_empty() || config.alpaca.api_secret.is_empty());
+    if !wants_alpaca && !config.database.password.is_empty() {
+        return;
+    }
+
+    let provider = match config.secrets.build_provider_from(vars) {
+        Ok(provider) => provider,
+        Err(e) => {
+            // Only an error if something that needs it is missing
+            if wants_alpaca {
+                errors.push(FieldError::new("secrets", e.to_string()));
+            }
+            return;
+        }
+    };
+
+    let mut slots = vec![(
+        "database.password",
+        "database/password",
+        &mut config.database.password,
+    )];
+    if wants_alpaca {
+        let alpaca = &mut config.alpaca;
+        slots.push(("alpaca.api_key", "alpaca/api_key", &mut alpaca.api_key));
+        slots.push((
+            "alpaca.api_secret",
+            "alpaca/api_secret",
+            &mut alpaca.api_secret,
+        ));
+    }
+
+    for (field, key, slot) in slots {
+        if !slot.is_empty() {
+            continue;
+        }
+        match provider.get(key) {
+            Ok(Some(secret)) => *slot = secret,
+            Ok(None) => {}
+            Err(e) => errors.push(FieldError::new(
+                field,
+                format!("{} provider: {}", provider.name(), e),
+            )),
+        }
+    }
+}
+
+/// Quotes a value for a libpq connection string
+fn escape_conn_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Expands a leading `~/` using `$HOME`
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn defaults_table() -> Table {
+    match Value::try_from(Config::default()) {
+        Ok(Value::Table(table)) => table,
+        _ => Table::new(),
+    }
+}
+
+fn read_table(path: &Path) -> Result<Table, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    text.parse::<Table>().map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Recursively overlays `overlay` onto `base`, replacing non-table values
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(overlay)) => merge(existing, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Applies `OPTITRADE_SECTION__FIELD=value` variables, converting each value
+/// to the type already present at that path (so `OPTITRADE_IB__PORT=7497` stays an integer).
+/// Returns the variable each overridden field came from.
+fn apply_env_overrides(
+    table: &mut Table,
+    vars: &[(String, String)],
+    errors: &mut Vec<FieldError>,
+) -> BTreeMap<String, String> {
+    let mut overrides = BTreeMap::new();
+    for (name, raw) in vars {
+        let Some(path) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if !path.contains(ENV_SEPARATOR) {
+            continue;
+        }
+
+        let keys: Vec<String> = path
+            .split(ENV_SEPARATOR)
+            .map(|key| key.to_ascii_lowercase())
+            .collect();
+        let field = keys.join(".");
+
+        match set_path(table, &keys, raw) {
+            Ok(()) => {
+                overrides.insert(field, name.clone());
+            }
+            Err(message) => errors.push(FieldError::new(
+                format!("{} (from {})", field, name),
+                message,
+            )),
+        }
+    }
+    overrides
+}
+
+fn set_path(table: &mut Table, keys: &[String], raw: &str) -> Result<(), String> {
+    let (last, parents) = keys.split_last().ok_or("empty key")?;
+    let mut target = table;
+    for key in parents {
+        let entry = target
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        target = match entry {
+            Value::Table(next) => next,
+            _ => return Err(format!("'{}' is not a section", key)),
+        };
+    }
+    let value = coerce_env_value(target.get(last), raw)?;
+    target.insert(last.clone(), value);
+    Ok(())
+}
+
+fn coerce_env_value(existing: Option<&Value>, raw: &str) -> Result<Value, String> {
+    match existing {
+        Some(Value::Integer(_)) => raw
+            .trim()
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| format!("expected an integer, got '{}'", raw)),
+        Some(Value::Float(_)) => raw
+            .trim()
+            .parse()
+            .map(Value::Float)
+            .map_err(|_| format!("expected a number, got '{}'", raw)),
+        Some(Value::Boolean(_)) => raw
+            .trim()
+            .parse()
+            .map(Value::Boolean)
+            .map_err(|_| format!("expected true or false, got '{}'", raw)),
+        // Lists are comma-separated: OPTITRADE_BUS__BROKERS=kafka-0:9092,kafka-1:9092
+        Some(Value::Array(_)) => Ok(Value::Array(
+            raw.split(',')
+                .map(|item| Value::String(item.trim().to_string()))
+                .filter(|item| item.as_str() != Some(""))
+                .collect(),
+        )),
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Deserializes one top-level section, recording an error for every field
+/// that is invalid or unknown and leaving those fields at their defaults
+fn section<T>(
+    table: &Table,
+    name: &str,
+    overrides: &BTreeMap<String, String>,
+    errors: &mut Vec<FieldError>,
+) -> T
+where
+    T: DeserializeOwned + Default,
+{
+    let fields = match table.get(name) {
+        Some(Value::Table(fields)) => fields,
+        Some(_) => {
+            errors.push(FieldError::new(name, "must be a table"));
+            return T::default();
+        }
+        None => return T::default(),
+    };
+    if let Ok(value) = Value::Table(fields.clone()).try_into() {
+        return value;
+    }
+
+    // serde stops at the first bad field, so try each one on its own
+    let mut valid = Table::new();
+    for (key, value) in fields {
+        let single = Table::from_iter([(key.clone(), value.clone())]);
+        match Value::Table(single).try_into::<T>() {
+            Ok(_) => {
+                valid.insert(key.clone(), value.clone());
+            }
+            Err(e) => errors.push(FieldError::new(
+                with_origin(&format!("{}.{}", name, key), overrides),
+                e.message().trim(),
+            )),
+        }
+    }
+    Value::Table(valid)
+        .try_into()
+        .unwrap_or_else(|e: toml::de::Error| {
+            errors.push(FieldError::new(name, e.message().trim()));
+            T::default()
+        })
+}
+
+/// `field`, naming the environment variable that set it or anything below it
+fn with_origin(field: &str, overrides: &BTreeMap<String, String>) -> String {
+    let nested = format!("{}.", field);
+    match overrides
+        .iter()
+        .find(|(path, _)| *path == field || path.starts_with(&nested))
+    {
+        Some((_, var)) => format!("{} (from {})", field, var),
+        None => field.to_string(),
+    }
+}
+
+fn check_url(errors: &mut Vec<FieldError>, field: &str, value: &str, schemes: &[&str]) {
+    match Url::parse(value) {
+        Ok(url) if schemes.contains(&url.scheme()) => {}
+        Ok(url) => errors.push(FieldError::new(
+            field,
+            format!(
+                "unsupported scheme '{}' (expected {})",
+                url.scheme(),
+                schemes.join(" or ")
+            ),
+        )),
+        Err(e) => errors.push(FieldError::new(
+            field,
+            format!("invalid URL '{}': {}", value, e),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREDENTIALS: [(&str, &str); 2] = [
+        ("OPTITRADE_ALPACA__API_KEY", "key"),
+        ("OPTITRADE_ALPACA__API_SECRET", "secret"),
+    ];
+
+    fn write_config(name: &str, text: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("optitrade_{}_{}.toml", name, std::process::id()));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn fields(err: ConfigError) -> Vec<String> {
+        match err {
+            ConfigError::Invalid(errors) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected validation errors, got {}", other),
+        }
+    }
+
+    #[test]
+    fn shipped_config_loads_for_every_profile() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("config.toml");
+        for profile in [Profile::Dev, Profile::Paper, Profile::Live] {
+            let config = ConfigLoader::new()
+                .with_path(&path)
+                .with_profile(profile)
+                .with_env(CREDENTIALS)
+                .load()
+                .unwrap();
+            assert_eq!(config.profile, profile);
+        }
+    }
+
+    #[test]
+    fn layers_file_profile_and_environment() {
+        let path = write_config(
+            "layers",
+            "[ib]\nport = 4001\nhost = \"gateway\"\n\n[profiles.paper.ib]\nport = 4002\n",
+        );
+        let load = |profile, vars: &[(&str, &str)]| {
+            ConfigLoader::new()
+                .with_path(&path)
+                .with_profile(profile)
+                .with_env(CREDENTIALS.iter().chain(vars).copied())
+                .load()
+                .unwrap()
+        };
+
+        let dev = load(Profile::Dev, &[]);
+        assert_eq!((dev.ib.host.as_str(), dev.ib.port), ("gateway", 4001));
+        assert_eq!(dev.ib.client_id, IbConfig::default().client_id);
+        assert_eq!(load(Profile::Paper, &[]).ib.port, 4002);
+        let overridden = load(
+            Profile::Paper,
+            &[
+                ("OPTITRADE_IB__PORT", "7497"),
+                ("OPTITRADE_BUS__BROKERS", "a:9092, b:9092"),
+            ],
+        );
+        assert_eq!(overridden.ib.port, 7497);
+        assert_eq!(overridden.bus.brokers, ["a:9092", "b:9092"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reports_every_invalid_field() {
+        let path = write_config(
+            "invalid",
+            "[ib]\nhost = 5\nport = \"x\"\n\n[alpaca]\noptions_poll_secs = \"fast\"\nbase_url = \"ftp://example.com\"\n",
+        );
+        let err = ConfigLoader::new()
+            .with_path(&path)
+            .with_env(CREDENTIALS)
+            .load()
+            .unwrap_err();
+        let fields = fields(err);
+        for field in [
+            "ib.host",
+            "ib.port",
+            "alpaca.options_poll_secs",
+            "alpaca.base_url",
+        ] {
+            assert!(
+                fields.iter().any(|f| f == field),
+                "{} missing from {:?}",
+                field,
+                fields
+            );
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_overrides() {
+        let path = write_config("unknown", "[alpaca]\napi_kye = \"x\"\n\n[ibb]\nport = 1\n");
+        let err = ConfigLoader::new()
+            .with_path(&path)
+            .with_env(CREDENTIALS.into_iter().chain([
+                ("OPTITRADE_IB__PROT", "7497"),
+                ("OPTITRADE_ALAPCA__API_KEY", "key"),
+                ("OPTITRADE_PROFILE_NAME", "ignored"), // Not an override: no separator
+            ]))
+            .load()
+            .unwrap_err();
+        let mut fields = fields(err);
+        fields.sort();
+        assert_eq!(
+            fields,
+            [
+                "alapca (from OPTITRADE_ALAPCA__API_KEY)",
+                "alpaca.api_kye",
+                "ib.prot (from OPTITRADE_IB__PROT)",
+                "ibb",
+            ]
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn live_profile_refuses_the_paper_api() {
+        let path = write_config("live", "");
+        let err = ConfigLoader::new()
+            .with_path(&path)
+            .with_profile(Profile::Live)
+            .with_env(CREDENTIALS)
+            .load()
+            .unwrap_err();
+        assert_eq!(fields(err), ["alpaca.base_url"]);
+        fs::remove_file(&path).unwrap();
+    }
 }
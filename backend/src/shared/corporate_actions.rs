@@ -0,0 +1,361 @@
+use crate::shared::config::{Config, CorporateActionSource};
+use crate::shared::data_loader::{AlpacaSource, DataError, HistoricalBar};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+// Bars are stored and fetched raw. Adjustment happens here, backwards from the
+// end of the series: bars after the last action keep their traded prices and
+// everything before an ex-date is scaled, so a 4:1 split no longer shows up as
+// a 75% overnight drop.
+
+/// A split or cash dividend taking effect at the open of `ex_date`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorporateAction {
+    pub symbol: String,
+    pub ex_date: NaiveDate,
+    pub kind: ActionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActionKind {
+    Split { ratio: f64 }, // New shares per old share: 4.0 for 4:1, 0.1 for a 1:10 reverse split
+    CashDividend { amount: f64 }, // Per share, in pre-adjustment terms
+}
+
+/// Which series `adjust_bars` produces
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceAdjustment {
+    #[default]
+    None,
+    Split,       // Prices and volumes continuous across splits
+    TotalReturn, // Split-adjusted, with dividends reinvested into the price
+}
+
+/// Corporate actions by symbol, each list sorted by ex-date
+#[derive(Debug, Clone, Default)]
+pub struct CorporateActions {
+    by_symbol: BTreeMap<String, Vec<CorporateAction>>,
+}
+
+#[derive(Deserialize)]
+struct ActionRecord {
+    symbol: String,
+    ex_date: NaiveDate,
+    action: String,
+    value: f64,
+}
+
+impl CorporateActions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads `symbol,ex_date,action,value` rows, where action is `split`
+    /// (value = new shares per old) or `dividend` (value = cash per share)
+    pub fn from_csv(path: &Path) -> Result<Self, DataError> {
+        let csv_error = |e: csv::Error| match e.into_kind() {
+            csv::ErrorKind::Io(source) => DataError::Io {
+                path: path.to_path_buf(),
+                source,
+            },
+            kind => DataError::Parse(format!("{}: {:?}", path.display(), kind)),
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .map_err(csv_error)?;
+
+        let mut actions = Self::new();
+        for (row, record) in reader.deserialize::<ActionRecord>().enumerate() {
+            let record = record.map_err(csv_error)?;
+            let line = row + 2;
+            let kind = match record.action.to_ascii_lowercase().as_str() {
+                "split" => ActionKind::Split {
+                    ratio: record.value,
+                },
+                "dividend" | "cash_dividend" => ActionKind::CashDividend {
+                    amount: record.value,
+                },
+                other => {
+                    return Err(DataError::Parse(format!(
+                        "{}:{}: unknown action '{}' (expected split or dividend)",
+                        path.display(),
+                        line,
+                        other
+                    )))
+                }
+            };
+            let action = CorporateAction {
+                symbol: record.symbol,
+                ex_date: record.ex_date,
+                kind,
+            };
+            if !action.is_valid() {
+                return Err(DataError::Parse(format!(
+                    "{}:{}: {} must be positive",
+                    path.display(),
+                    line,
+                    action
+                )));
+            }
+            actions.insert(action);
+        }
+        Ok(actions)
+    }
+
+    pub fn insert(&mut self, action: CorporateAction) {
+        let list = self.by_symbol.entry(action.symbol.clone()).or_default();
+        let at = list.partition_point(|a| a.ex_date <= action.ex_date);
+        list.insert(at, action);
+    }
+
+    pub fn extend(&mut self, actions: impl IntoIterator<Item = CorporateAction>) {
+        for action in actions {
+            self.insert(action);
+        }
+    }
+
+    pub fn for_symbol(&self, symbol: &str) -> &[CorporateAction] {
+        self.by_symbol
+            .get(symbol)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_symbol.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CorporateAction {
+    fn is_valid(&self) -> bool {
+        match self.kind {
+            ActionKind::Split { ratio } => ratio.is_finite() && ratio > 0.0,
+            ActionKind::CashDividend { amount } => amount.is_finite() && amount > 0.0,
+        }
+    }
+}
+
+impl fmt::Display for CorporateAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ActionKind::Split { ratio } => {
+                write!(f, "{} split x{} on {}", self.symbol, ratio, self.ex_date)
+            }
+            ActionKind::CashDividend { amount } => {
+                write!(f, "{} dividend {} on {}", self.symbol, amount, self.ex_date)
+            }
+        }
+    }
+}
+
+impl PriceAdjustment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceAdjustment::None => "none",
+            PriceAdjustment::Split => "split",
+            PriceAdjustment::TotalReturn => "total_return",
+        }
+    }
+}
+
+impl fmt::Display for PriceAdjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Adjusts one symbol's bars (oldest first) in place for `actions`.
+///
+/// Splits divide earlier prices by the ratio and multiply earlier volumes by
+/// it. For total return, a dividend also scales earlier prices by
+/// `1 - amount / close`, using the raw close of the last bar before its ex-date.
+pub fn adjust_bars(bars: &mut [HistoricalBar], actions: &[CorporateAction], mode: PriceAdjustment) {
+    let Some(last) = bars.last().map(|bar| bar.timestamp.date_naive()) else {
+        return;
+    };
+    if mode == PriceAdjustment::None {
+        return;
+    }
+
+    // Actions after the series ends would shift every bar; prices stay anchored to the last bar
+    let mut pending = actions
+        .iter()
+        .filter(|a| a.ex_date <= last)
+        .rev()
+        .peekable();
+    let mut price_factor = 1.0;
+    let mut volume_factor = 1.0;
+
+    for bar in bars.iter_mut().rev() {
+        let date = bar.timestamp.date_naive();
+        while let Some(action) = pending.next_if(|a| a.ex_date > date) {
+            match action.kind {
+                ActionKind::Split { ratio } if ratio > 0.0 => {
+                    price_factor /= ratio;
+                    volume_factor *= ratio;
+                }
+                ActionKind::CashDividend { amount }
+                    if mode == PriceAdjustment::TotalReturn && amount < bar.close =>
+                {
+                    price_factor *= 1.0 - amount / bar.close;
+                }
+                _ => {}
+            }
+        }
+
+        if price_factor != 1.0 || volume_factor != 1.0 {
+            bar.open *= price_factor;
+            bar.high *= price_factor;
+            bar.low *= price_factor;
+            bar.close *= price_factor;
+            bar.vwap = bar.vwap.map(|vwap| vwap * price_factor);
+            bar.volume *= volume_factor;
+        }
+    }
+}
+
+/// Loads `symbol`'s actions between `start` and `end` from the configured source
+pub async fn load_corporate_actions(
+    config: &Config,
+    symbol: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<CorporateActions, DataError> {
+    let settings = &config.corporate_actions;
+    let mut actions = CorporateActions::new();
+    match settings.source {
+        CorporateActionSource::Csv => {
+            let all = CorporateActions::from_csv(Path::new(&settings.path))?;
+            actions.extend(all.for_symbol(symbol).iter().cloned());
+        }
+        CorporateActionSource::Alpaca => {
+            let source = AlpacaSource::new(&config.alpaca);
+            actions.extend(
+                source
+                    .load_corporate_actions(&[symbol], start.date_naive(), end.date_naive())
+                    .await?,
+            );
+        }
+    }
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bar(day: u32, close: f64, volume: f64) -> HistoricalBar {
+        HistoricalBar {
+            symbol: "AAPL".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2020, 8, day, 13, 30, 0).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            vwap: Some(close),
+            trade_count: None,
+        }
+    }
+
+    fn action(day: u32, kind: ActionKind) -> CorporateAction {
+        CorporateAction {
+            symbol: "AAPL".to_string(),
+            ex_date: NaiveDate::from_ymd_opt(2020, 8, day).unwrap(),
+            kind,
+        }
+    }
+
+    fn closes(bars: &[HistoricalBar]) -> Vec<f64> {
+        bars.iter().map(|bar| bar.close).collect()
+    }
+
+    #[test]
+    fn splits_scale_bars_before_the_ex_date() {
+        let mut bars = vec![
+            bar(27, 500.0, 100.0),
+            bar(28, 500.0, 100.0),
+            bar(31, 125.0, 400.0),
+        ];
+        let split = [action(31, ActionKind::Split { ratio: 4.0 })];
+        adjust_bars(&mut bars, &split, PriceAdjustment::Split);
+        assert_eq!(closes(&bars), [125.0, 125.0, 125.0]);
+        assert_eq!(bars[0].volume, 400.0);
+        assert_eq!(bars[0].vwap, Some(125.0));
+    }
+
+    #[test]
+    fn dividends_only_adjust_total_return() {
+        let raw = vec![bar(6, 100.0, 1.0), bar(7, 98.0, 1.0)];
+        let dividend = [action(7, ActionKind::CashDividend { amount: 2.0 })];
+
+        let mut split_only = raw.clone();
+        adjust_bars(&mut split_only, &dividend, PriceAdjustment::Split);
+        assert_eq!(closes(&split_only), [100.0, 98.0]);
+
+        let mut total_return = raw.clone();
+        adjust_bars(&mut total_return, &dividend, PriceAdjustment::TotalReturn);
+        assert_eq!(closes(&total_return), [98.0, 98.0]);
+
+        let mut unadjusted = raw.clone();
+        adjust_bars(&mut unadjusted, &dividend, PriceAdjustment::None);
+        assert_eq!(closes(&unadjusted), closes(&raw));
+    }
+
+    #[test]
+    fn actions_after_the_last_bar_are_ignored() {
+        let mut bars = vec![bar(27, 500.0, 100.0), bar(28, 500.0, 100.0)];
+        adjust_bars(
+            &mut bars,
+            &[action(31, ActionKind::Split { ratio: 4.0 })],
+            PriceAdjustment::Split,
+        );
+        assert_eq!(closes(&bars), [500.0, 500.0]);
+    }
+
+    #[test]
+    fn reads_and_sorts_actions_from_csv() {
+        let path =
+            std::env::temp_dir().join(format!("optitrade_actions_{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            "symbol,ex_date,action,value\nAAPL,2020-08-31,split,4\nAAPL,2020-08-07,dividend,0.82\nMSFT,2020-08-19,Dividend,0.51\n",
+        )
+        .unwrap();
+        let actions = CorporateActions::from_csv(&path).unwrap();
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            actions.for_symbol("AAPL"),
+            [
+                action(7, ActionKind::CashDividend { amount: 0.82 }),
+                action(31, ActionKind::Split { ratio: 4.0 }),
+            ]
+        );
+        assert!(actions.for_symbol("TSLA").is_empty());
+
+        std::fs::write(
+            &path,
+            "symbol,ex_date,action,value\nAAPL,2020-08-31,split,0\n",
+        )
+        .unwrap();
+        assert!(CorporateActions::from_csv(&path).is_err());
+        std::fs::write(
+            &path,
+            "symbol,ex_date,action,value\nAAPL,2020-08-31,merger,1\n",
+        )
+        .unwrap();
+        assert!(CorporateActions::from_csv(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
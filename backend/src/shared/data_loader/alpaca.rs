@@ -0,0 +1,594 @@
+use super::{DataError, HistoricalBar, HistoricalDataSource};
+use crate::shared::config::AlpacaConfig;
+use crate::shared::corporate_actions::{ActionKind, CorporateAction};
+use crate::shared::market_event::{EventPayload, MarketEvent, Quote, Trade};
+use crate::shared::secrets::Secret;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Largest page Alpaca's market data API will return
+const PAGE_LIMIT: u32 = 10_000;
+/// Retries after a 429 before giving up
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Bar size accepted by Alpaca: 1-59Min, 1-23Hour, 1Day, 1Week, or 1/2/3/4/6/12Month
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Timeframe {
+    pub amount: u32,
+    pub unit: TimeframeUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeframeUnit {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+/// Alpaca data feed; the account's default when not set
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlpacaFeed {
+    Sip,
+    Iex,
+    Otc,
+}
+
+/// Corporate action adjustment Alpaca applies to bars
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Adjustment {
+    #[default]
+    Raw,
+    Split,
+    Dividend,
+    All,
+}
+
+impl Timeframe {
+    pub const MINUTE: Timeframe = Timeframe::new(1, TimeframeUnit::Minute);
+    pub const HOUR: Timeframe = Timeframe::new(1, TimeframeUnit::Hour);
+    pub const DAY: Timeframe = Timeframe::new(1, TimeframeUnit::Day);
+    pub const WEEK: Timeframe = Timeframe::new(1, TimeframeUnit::Week);
+    pub const MONTH: Timeframe = Timeframe::new(1, TimeframeUnit::Month);
+
+    const fn new(amount: u32, unit: TimeframeUnit) -> Self {
+        Self { amount, unit }
+    }
+
+    /// Nominal bar length; months count as 30 days
+    pub fn duration(&self) -> Duration {
+        let secs = match self.unit {
+            TimeframeUnit::Minute => 60,
+            TimeframeUnit::Hour => 3_600,
+            TimeframeUnit::Day => 86_400,
+            TimeframeUnit::Week => 7 * 86_400,
+            TimeframeUnit::Month => 30 * 86_400,
+        };
+        Duration::from_secs(secs * self.amount as u64)
+    }
+}
+
+impl Default for Timeframe {
+    fn default() -> Self {
+        Timeframe::DAY
+    }
+}
+
+impl TimeframeUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeframeUnit::Minute => "Min",
+            TimeframeUnit::Hour => "Hour",
+            TimeframeUnit::Day => "Day",
+            TimeframeUnit::Week => "Week",
+            TimeframeUnit::Month => "Month",
+        }
+    }
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.unit.as_str())
+    }
+}
+
+impl FromStr for Timeframe {
+    type Err = String;
+
+    /// Accepts Alpaca's spellings: `15Min`/`15T`, `1Hour`/`1H`, `1Day`/`1D`, `1Week`/`1W`, `3Month`/`3M`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (amount, unit) = s.split_at(split);
+        let invalid = || {
+            format!(
+                "invalid timeframe '{}' (expected e.g. 1Min, 1Hour, 1Day)",
+                s
+            )
+        };
+
+        let amount: u32 = amount.parse().map_err(|_| invalid())?;
+        let unit = match unit {
+            "Min" | "T" => TimeframeUnit::Minute,
+            "Hour" | "H" => TimeframeUnit::Hour,
+            "Day" | "D" => TimeframeUnit::Day,
+            "Week" | "W" => TimeframeUnit::Week,
+            "Month" | "M" => TimeframeUnit::Month,
+            _ => return Err(invalid()),
+        };
+
+        let valid = match unit {
+            TimeframeUnit::Minute => (1..=59).contains(&amount),
+            TimeframeUnit::Hour => (1..=23).contains(&amount),
+            TimeframeUnit::Day | TimeframeUnit::Week => amount == 1,
+            TimeframeUnit::Month => [1, 2, 3, 4, 6, 12].contains(&amount),
+        };
+        if !valid {
+            return Err(format!(
+                "timeframe '{}' is not supported by Alpaca (1-59Min, 1-23Hour, 1Day, 1Week, 1/2/3/4/6/12Month)",
+                s
+            ));
+        }
+        Ok(Timeframe::new(amount, unit))
+    }
+}
+
+impl TryFrom<String> for Timeframe {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Timeframe> for String {
+    fn from(timeframe: Timeframe) -> Self {
+        timeframe.to_string()
+    }
+}
+
+impl AlpacaFeed {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlpacaFeed::Sip => "sip",
+            AlpacaFeed::Iex => "iex",
+            AlpacaFeed::Otc => "otc",
+        }
+    }
+}
+
+impl fmt::Display for AlpacaFeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Adjustment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Adjustment::Raw => "raw",
+            Adjustment::Split => "split",
+            Adjustment::Dividend => "dividend",
+            Adjustment::All => "all",
+        }
+    }
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Historical bars, quotes and trades from Alpaca's market data API.
+///
+/// Every call follows `next_page_token` until the range is exhausted, so long
+/// ranges come back complete rather than cut off at the first page.
+pub struct AlpacaSource {
+    client: Client,
+    base_url: String,
+    api_key: Secret,
+    api_secret: Secret,
+    timeframe: Timeframe,
+    feed: Option<AlpacaFeed>,
+    adjustment: Adjustment,
+}
+
+/// One page of any Alpaca historical endpoint
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct Page<T> {
+    #[serde(default, alias = "bars", alias = "quotes", alias = "trades")]
+    items: Option<Vec<T>>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ActionsPage {
+    #[serde(default)]
+    corporate_actions: AlpacaActions,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct AlpacaActions {
+    #[serde(default)]
+    forward_splits: Vec<AlpacaSplit>,
+    #[serde(default)]
+    reverse_splits: Vec<AlpacaSplit>,
+    #[serde(default)]
+    cash_dividends: Vec<AlpacaDividend>,
+}
+
+#[derive(Deserialize)]
+struct AlpacaSplit {
+    symbol: String,
+    ex_date: NaiveDate,
+    new_rate: f64,
+    old_rate: f64,
+}
+
+#[derive(Deserialize)]
+struct AlpacaDividend {
+    symbol: String,
+    ex_date: NaiveDate,
+    rate: f64,
+}
+
+#[derive(Deserialize)]
+struct AlpacaError {
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct AlpacaBar {
+    t: DateTime<Utc>,
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+    v: f64,
+    #[serde(default)]
+    vw: Option<f64>,
+    #[serde(default)]
+    n: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct AlpacaQuote {
+    t: DateTime<Utc>,
+    bp: f64,
+    #[serde(rename = "bs")]
+    bid_size: f64,
+    ap: f64,
+    #[serde(rename = "as")]
+    ask_size: f64,
+}
+
+#[derive(Deserialize)]
+struct AlpacaTrade {
+    t: DateTime<Utc>,
+    p: f64,
+    s: f64,
+    #[serde(default)]
+    i: u64,
+}
+
+impl AlpacaSource {
+    pub fn new(config: &AlpacaConfig) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: config.historic_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.clone(),
+            api_secret: config.api_secret.clone(),
+            timeframe: Timeframe::default(),
+            feed: None,
+            adjustment: Adjustment::default(),
+        }
+    }
+
+    pub fn with_timeframe(mut self, timeframe: Timeframe) -> Self {
+        self.timeframe = timeframe;
+        self
+    }
+
+    pub fn with_feed(mut self, feed: Option<AlpacaFeed>) -> Self {
+        self.feed = feed;
+        self
+    }
+
+    pub fn with_adjustment(mut self, adjustment: Adjustment) -> Self {
+        self.adjustment = adjustment;
+        self
+    }
+
+    /// NBBO quotes for `symbol` with `start <= timestamp < end`, as quote events
+    pub async fn load_quotes(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<MarketEvent>, DataError> {
+        let quotes: Vec<AlpacaQuote> = self
+            .fetch_all(&format!("/v2/stocks/{}/quotes", symbol), start, end, &[])
+            .await?;
+        Ok(quotes
+            .into_iter()
+            .filter(|q| q.t >= start && q.t < end)
+            .map(|q| {
+                MarketEvent::new(
+                    symbol,
+                    timestamp_ns(q.t),
+                    EventPayload::Quote(Quote {
+                        bid_price: q.bp,
+                        ask_price: q.ap,
+                        bid_size: q.bid_size,
+                        ask_size: q.ask_size,
+                    }),
+                )
+            })
+            .collect())
+    }
+
+    /// Trades for `symbol` with `start <= timestamp < end`, as trade events
+    pub async fn load_trades(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<MarketEvent>, DataError> {
+        let trades: Vec<AlpacaTrade> = self
+            .fetch_all(&format!("/v2/stocks/{}/trades", symbol), start, end, &[])
+            .await?;
+        Ok(trades
+            .into_iter()
+            .filter(|t| t.t >= start && t.t < end)
+            .map(|t| {
+                MarketEvent::new(
+                    symbol,
+                    timestamp_ns(t.t),
+                    EventPayload::Trade(Trade {
+                        price: t.p,
+                        quantity: t.s,
+                        trade_id: t.i,
+                    }),
+                )
+            })
+            .collect())
+    }
+
+    /// Splits and cash dividends with an ex-date between `start` and `end` inclusive
+    pub async fn load_corporate_actions(
+        &self,
+        symbols: &[&str],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<CorporateAction>, DataError> {
+        let url = format!("{}/v1/corporate-actions", self.base_url);
+        let params: Vec<(&str, String)> = vec![
+            ("symbols", symbols.join(",")),
+            (
+                "types",
+                "forward_split,reverse_split,cash_dividend".to_string(),
+            ),
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("limit", "1000".to_string()),
+        ];
+
+        let mut actions = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut query = params.clone();
+            if let Some(token) = &page_token {
+                query.push(("page_token", token.clone()));
+            }
+
+            let page: ActionsPage = self.get_json(&url, &query).await?;
+            let found = page.corporate_actions;
+            for split in found.forward_splits.into_iter().chain(found.reverse_splits) {
+                if split.old_rate > 0.0 {
+                    actions.push(CorporateAction {
+                        symbol: split.symbol,
+                        ex_date: split.ex_date,
+                        kind: ActionKind::Split {
+                            ratio: split.new_rate / split.old_rate,
+                        },
+                    });
+                }
+            }
+            for dividend in found.cash_dividends {
+                actions.push(CorporateAction {
+                    symbol: dividend.symbol,
+                    ex_date: dividend.ex_date,
+                    kind: ActionKind::CashDividend {
+                        amount: dividend.rate,
+                    },
+                });
+            }
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        actions.retain(|a| a.ex_date >= start && a.ex_date <= end);
+        info!(
+            count = actions.len(),
+            symbols = %symbols.join(","),
+            "Fetched corporate actions"
+        );
+        Ok(actions)
+    }
+
+    /// Follows `next_page_token` until the endpoint has nothing more to return
+    async fn fetch_all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        extra: &[(&str, String)],
+    ) -> Result<Vec<T>, DataError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut params: Vec<(&str, String)> = vec![
+            ("start", start.to_rfc3339_opts(SecondsFormat::Nanos, true)),
+            ("end", end.to_rfc3339_opts(SecondsFormat::Nanos, true)),
+            ("limit", PAGE_LIMIT.to_string()),
+            ("sort", "asc".to_string()),
+        ];
+        if let Some(feed) = self.feed {
+            params.push(("feed", feed.to_string()));
+        }
+        params.extend(extra.iter().cloned());
+
+        let mut items = Vec::new();
+        let mut page_token: Option<String> = None;
+        let mut pages = 0;
+        loop {
+            let mut query = params.clone();
+            if let Some(token) = &page_token {
+                query.push(("page_token", token.clone()));
+            }
+
+            let page: Page<T> = self.get_json(&url, &query).await?;
+            pages += 1;
+            items.extend(page.items.unwrap_or_default());
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        debug!(count = items.len(), path, pages, "Fetched Alpaca records");
+        Ok(items)
+    }
+
+    /// One request, retried with backoff while Alpaca answers 429
+    async fn get_json<R: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<R, DataError> {
+        let mut attempt = 0;
+        loop {
+            let response = self
+                .client
+                .get(url)
+                .query(query)
+                .header("APCA-API-KEY-ID", self.api_key.expose())
+                .header("APCA-API-SECRET-KEY", self.api_secret.expose())
+                .send()
+                .await?;
+
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RATE_LIMIT_RETRIES {
+                attempt += 1;
+                let wait = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or(Duration::from_secs(1 << attempt));
+                warn!(
+                    ?wait,
+                    attempt,
+                    max_attempts = MAX_RATE_LIMIT_RETRIES,
+                    "Rate limited by Alpaca, retrying"
+                );
+                tokio::time::sleep(wait).await;
+                continue;
+            }
+
+            let body = response.text().await?;
+            if !status.is_success() {
+                // Alpaca reports errors as {"code": ..., "message": ...}
+                let message = serde_json::from_str::<AlpacaError>(&body)
+                    .map(|e| e.message)
+                    .ok()
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or(body);
+                return Err(DataError::Api {
+                    status: status.as_u16(),
+                    message,
+                });
+            }
+
+            return serde_json::from_str(&body)
+                .map_err(|e| DataError::Parse(format!("Alpaca response from {}: {}", url, e)));
+        }
+    }
+}
+
+#[async_trait]
+impl HistoricalDataSource for AlpacaSource {
+    fn name(&self) -> &'static str {
+        "alpaca"
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!(
+            "alpaca {} timeframe={} feed={} adjustment={}",
+            self.base_url,
+            self.timeframe,
+            self.feed.map(|f| f.as_str()).unwrap_or("default"),
+            self.adjustment
+        ))
+    }
+
+    async fn load_bars(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HistoricalBar>, DataError> {
+        let bars: Vec<AlpacaBar> = self
+            .fetch_all(
+                &format!("/v2/stocks/{}/bars", symbol),
+                start,
+                end,
+                &[
+                    ("timeframe", self.timeframe.to_string()),
+                    ("adjustment", self.adjustment.to_string()),
+                ],
+            )
+            .await?;
+
+        // Alpaca's `end` is inclusive; the trait's range is not
+        Ok(bars
+            .into_iter()
+            .filter(|bar| bar.t >= start && bar.t < end)
+            .map(|bar| HistoricalBar {
+                symbol: symbol.to_string(),
+                timestamp: bar.t,
+                open: bar.o,
+                high: bar.h,
+                low: bar.l,
+                close: bar.c,
+                volume: bar.v,
+                vwap: bar.vw,
+                trade_count: bar.n,
+            })
+            .collect())
+    }
+}
+
+fn timestamp_ns(ts: DateTime<Utc>) -> u64 {
+    ts.timestamp_nanos_opt().unwrap_or_default().max(0) as u64
+}
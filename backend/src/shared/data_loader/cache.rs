@@ -0,0 +1,335 @@
+use super::parquet_file::{read_bars, write_bars};
+use super::{finish_bars, DataError, HistoricalBar, HistoricalDataSource};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+// Layout under the cache directory:
+//
+//   bars/<sha256(source key, symbol)>/manifest.json
+//   bars/<sha256(source key, symbol)>/<sha256(contents)>.parquet
+//
+// The manifest lists the time ranges already fetched and names the Parquet
+// blob holding every bar in them. A request fetches only the parts of its
+// range the manifest does not cover, merges them in, writes a new blob and
+// swaps the manifest over with a rename, so a crash never leaves a manifest
+// pointing at a half-written file.
+
+/// Bars newer than this may still change (today's daily bar, late corrections) and are never cached
+const UNSETTLED: Duration = Duration::days(1);
+const MANIFEST: &str = "manifest.json";
+
+/// Wraps a remote source with an on-disk cache, so repeated backtests and
+/// parameter sweeps over the same data are fast and work offline
+pub struct CachedSource {
+    inner: Box<dyn HistoricalDataSource>,
+    dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Manifest {
+    key: String,
+    symbol: String,
+    #[serde(default)]
+    blob: Option<String>, // File name of the Parquet blob, relative to the manifest
+    #[serde(default)]
+    ranges: Vec<CachedRange>, // Sorted, non-overlapping, non-adjacent
+}
+
+/// A half-open `[start, end)` range whose bars are all in the blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedRange {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl CachedSource {
+    /// Caches `inner` under `dir` (usually `~/.cache/optitrade`)
+    pub fn new(inner: Box<dyn HistoricalDataSource>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    fn entry_dir(&self, key: &str, symbol: &str) -> PathBuf {
+        let digest = hex(&Sha256::digest(format!("{}\n{}", key, symbol).as_bytes()));
+        self.dir.join("bars").join(digest)
+    }
+}
+
+#[async_trait]
+impl HistoricalDataSource for CachedSource {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn load_bars(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HistoricalBar>, DataError> {
+        let Some(key) = self.inner.cache_key() else {
+            return self.inner.load_bars(symbol, start, end).await;
+        };
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let entry = self.entry_dir(&key, symbol);
+        let (mut manifest, cached) = {
+            let entry = entry.clone();
+            blocking(move || load_entry(&entry)).await?
+        };
+        if manifest.key.is_empty() {
+            manifest.key = key;
+            manifest.symbol = symbol.to_string();
+        }
+
+        // Only the settled part of the request is cached; anything newer is always fetched
+        let settled = end.min(Utc::now() - UNSETTLED).max(start);
+        let missing = subtract(&manifest.ranges, start, settled);
+
+        let mut fetched = Vec::new();
+        for range in &missing {
+            info!(
+                symbol,
+                start = %range.start,
+                end = %range.end,
+                source = self.inner.name(),
+                "Fetching bars missing from cache"
+            );
+            fetched.extend(self.inner.load_bars(symbol, range.start, range.end).await?);
+        }
+
+        let mut bars = if missing.is_empty() {
+            if start < settled {
+                info!(symbol, %start, end = %settled, "Bars served from cache");
+            }
+            cached
+        } else {
+            let merged = merge_bars(cached, fetched);
+            for range in missing {
+                insert_range(&mut manifest.ranges, range);
+            }
+            let entry = entry.clone();
+            let bars = merged.clone();
+            blocking(move || save_entry(&entry, manifest, &bars)).await?;
+            merged
+        };
+
+        if settled < end {
+            bars.extend(self.inner.load_bars(symbol, settled, end).await?);
+        }
+        Ok(finish_bars(bars, start, end))
+    }
+}
+
+async fn blocking<T, F>(task: F) -> Result<T, DataError>
+where
+    F: FnOnce() -> Result<T, DataError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| DataError::Parse(format!("cache task failed: {}", e)))?
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> DataError + '_ {
+    move |source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// Reads the manifest and its bars; an empty manifest if the entry is new or unreadable
+fn load_entry(entry: &Path) -> Result<(Manifest, Vec<HistoricalBar>), DataError> {
+    let path = entry.join(MANIFEST);
+    let manifest: Manifest = match fs::read_to_string(&path) {
+        Ok(text) => match serde_json::from_str(&text) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring corrupt cache manifest");
+                return Ok((Manifest::default(), Vec::new()));
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok((Manifest::default(), Vec::new()))
+        }
+        Err(e) => return Err(io_error(&path)(e)),
+    };
+
+    let bars = match &manifest.blob {
+        Some(blob) => match read_bars(&entry.join(blob), &manifest.symbol) {
+            Ok(bars) => bars,
+            Err(e) => {
+                warn!(error = %e, "Discarding unreadable cache entry");
+                return Ok((Manifest::default(), Vec::new()));
+            }
+        },
+        None => Vec::new(),
+    };
+    Ok((manifest, bars))
+}
+
+/// Writes the bars as a new content-named blob, then points the manifest at it
+fn save_entry(
+    entry: &Path,
+    mut manifest: Manifest,
+    bars: &[HistoricalBar],
+) -> Result<(), DataError> {
+    fs::create_dir_all(entry).map_err(io_error(entry))?;
+
+    let staging = entry.join(format!("bars.{}.tmp", std::process::id()));
+    write_bars(&staging, bars)?;
+    let digest = hex(&Sha256::digest(
+        fs::read(&staging).map_err(io_error(&staging))?,
+    ));
+    let blob = format!("{}.parquet", digest);
+    let blob_path = entry.join(&blob);
+    fs::rename(&staging, &blob_path).map_err(io_error(&blob_path))?;
+
+    let previous = manifest.blob.replace(blob.clone());
+    let manifest_path = entry.join(MANIFEST);
+    let staging = entry.join(format!("{}.{}.tmp", MANIFEST, std::process::id()));
+    let text = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| DataError::Parse(format!("cache manifest: {}", e)))?;
+    fs::write(&staging, text).map_err(io_error(&staging))?;
+    fs::rename(&staging, &manifest_path).map_err(io_error(&manifest_path))?;
+
+    if let Some(previous) = previous.filter(|previous| *previous != blob) {
+        let _ = fs::remove_file(entry.join(previous));
+    }
+    Ok(())
+}
+
+/// Combines cached and freshly fetched bars, preferring the fresh copy of any duplicate
+fn merge_bars(cached: Vec<HistoricalBar>, fetched: Vec<HistoricalBar>) -> Vec<HistoricalBar> {
+    let mut by_time = BTreeMap::new();
+    for bar in cached.into_iter().chain(fetched) {
+        by_time.insert(bar.timestamp, bar);
+    }
+    by_time.into_values().collect()
+}
+
+/// Parts of `[start, end)` not covered by `ranges`
+fn subtract(ranges: &[CachedRange], start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<CachedRange> {
+    let mut missing = Vec::new();
+    let mut cursor = start;
+    for range in ranges {
+        if range.end <= cursor {
+            continue;
+        }
+        if range.start >= end {
+            break;
+        }
+        if range.start > cursor {
+            missing.push(CachedRange {
+                start: cursor,
+                end: range.start,
+            });
+        }
+        cursor = cursor.max(range.end);
+    }
+    if cursor < end {
+        missing.push(CachedRange { start: cursor, end });
+    }
+    missing
+}
+
+/// Adds `range`, merging it with any range it overlaps or touches
+fn insert_range(ranges: &mut Vec<CachedRange>, mut range: CachedRange) {
+    ranges.retain(|existing| {
+        if existing.end < range.start || existing.start > range.end {
+            return true;
+        }
+        range.start = range.start.min(existing.start);
+        range.end = range.end.max(existing.end);
+        false
+    });
+    let at = ranges.partition_point(|existing| existing.start < range.start);
+    ranges.insert(at, range);
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, d, 0, 0, 0).unwrap()
+    }
+
+    fn range(start: u32, end: u32) -> CachedRange {
+        CachedRange {
+            start: day(start),
+            end: day(end),
+        }
+    }
+
+    fn bar(d: u32, close: f64) -> HistoricalBar {
+        HistoricalBar {
+            symbol: "AAPL".to_string(),
+            timestamp: day(d),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 100.0,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    #[test]
+    fn subtract_returns_the_gaps_between_cached_ranges() {
+        let ranges = vec![range(3, 5), range(8, 10)];
+        assert_eq!(
+            subtract(&ranges, day(1), day(12)),
+            vec![range(1, 3), range(5, 8), range(10, 12)]
+        );
+        assert_eq!(subtract(&ranges, day(4), day(9)), vec![range(5, 8)]);
+        assert!(subtract(&ranges, day(3), day(5)).is_empty());
+        assert_eq!(subtract(&[], day(1), day(2)), vec![range(1, 2)]);
+    }
+
+    #[test]
+    fn insert_range_merges_overlapping_and_touching_ranges() {
+        let mut ranges = vec![range(3, 5), range(8, 10)];
+        insert_range(&mut ranges, range(12, 14));
+        insert_range(&mut ranges, range(1, 2));
+        assert_eq!(
+            ranges,
+            vec![range(1, 2), range(3, 5), range(8, 10), range(12, 14)]
+        );
+
+        insert_range(&mut ranges, range(5, 8));
+        assert_eq!(ranges, vec![range(1, 2), range(3, 10), range(12, 14)]);
+
+        insert_range(&mut ranges, range(2, 13));
+        assert_eq!(ranges, vec![range(1, 14)]);
+    }
+
+    #[test]
+    fn merge_bars_sorts_and_prefers_fetched_duplicates() {
+        let merged = merge_bars(
+            vec![bar(3, 1.0), bar(1, 1.0)],
+            vec![bar(3, 2.0), bar(2, 2.0)],
+        );
+        let closes: Vec<(DateTime<Utc>, f64)> = merged
+            .iter()
+            .map(|bar| (bar.timestamp, bar.close))
+            .collect();
+        assert_eq!(closes, vec![(day(1), 1.0), (day(2), 2.0), (day(3), 2.0)]);
+    }
+}
@@ -0,0 +1,136 @@
+use super::{
+    finish_bars, parse_timestamp, symbol_path, Column, DataError, HistoricalBar,
+    HistoricalDataSource,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// Bars from vendor CSV files with a header row.
+///
+/// Needs timestamp, open, high, low, close and volume columns; vwap and
+/// trade_count are optional. A file holding several symbols must have a
+/// symbol column, otherwise every row is taken to belong to the requested one.
+pub struct CsvSource {
+    path: String,
+}
+
+impl CsvSource {
+    /// `path` is a file or a template such as `data/{symbol}.csv`
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl HistoricalDataSource for CsvSource {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    async fn load_bars(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HistoricalBar>, DataError> {
+        let path = symbol_path(&self.path, symbol);
+        let symbol = symbol.to_string();
+        let bars = tokio::task::spawn_blocking(move || read_bars(&path, &symbol))
+            .await
+            .map_err(|e| DataError::Parse(format!("CSV reader task failed: {}", e)))??;
+        Ok(finish_bars(bars, start, end))
+    }
+}
+
+fn read_bars(path: &Path, symbol: &str) -> Result<Vec<HistoricalBar>, DataError> {
+    let io_error = |source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let csv_error = |e: csv::Error| match e.into_kind() {
+        csv::ErrorKind::Io(source) => io_error(source),
+        kind => DataError::Parse(format!("{}: {:?}", path.display(), kind)),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(csv_error)?;
+
+    let columns: Vec<Option<Column>> = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(Column::from_name)
+        .collect();
+    let index = |column: Column| columns.iter().position(|c| *c == Some(column));
+
+    let required = |column: Column, name: &str| {
+        index(column).ok_or_else(|| {
+            DataError::Parse(format!("{}: missing '{}' column", path.display(), name))
+        })
+    };
+    let timestamp = required(Column::Timestamp, "timestamp")?;
+    let open = required(Column::Open, "open")?;
+    let high = required(Column::High, "high")?;
+    let low = required(Column::Low, "low")?;
+    let close = required(Column::Close, "close")?;
+    let volume = required(Column::Volume, "volume")?;
+    let vwap = index(Column::Vwap);
+    let trade_count = index(Column::TradeCount);
+    let symbol_column = index(Column::Symbol);
+
+    let mut bars = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(csv_error)?;
+        if let Some(column) = symbol_column {
+            if !record
+                .get(column)
+                .unwrap_or("")
+                .eq_ignore_ascii_case(symbol)
+            {
+                continue;
+            }
+        }
+
+        // Header is line 1
+        let line = row + 2;
+        let field = |column: usize| record.get(column).unwrap_or("");
+        let number = |column: usize| {
+            field(column).parse::<f64>().map_err(|_| {
+                DataError::Parse(format!(
+                    "{}:{}: invalid number '{}'",
+                    path.display(),
+                    line,
+                    field(column)
+                ))
+            })
+        };
+        let optional = |column: Option<usize>| column.map(field).filter(|v| !v.is_empty());
+
+        bars.push(HistoricalBar {
+            symbol: symbol.to_string(),
+            timestamp: parse_timestamp(field(timestamp)).ok_or_else(|| {
+                DataError::Parse(format!(
+                    "{}:{}: invalid timestamp '{}'",
+                    path.display(),
+                    line,
+                    field(timestamp)
+                ))
+            })?,
+            open: number(open)?,
+            high: number(high)?,
+            low: number(low)?,
+            close: number(close)?,
+            volume: number(volume)?,
+            vwap: optional(vwap).and_then(|v| v.parse().ok()),
+            trade_count: optional(trade_count)
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(|n| n as u64),
+        });
+    }
+    Ok(bars)
+}
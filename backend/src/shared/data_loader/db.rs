@@ -0,0 +1,71 @@
+use super::{DataError, HistoricalBar, HistoricalDataSource};
+use crate::shared::config::DatabaseConfig;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Client, NoTls};
+use tracing::error;
+
+/// Bars stored in TimescaleDB (see `storage_agent/init_db.sql` for the table layout)
+pub struct DbSource {
+    client: Client,
+    query: String,
+}
+
+impl DbSource {
+    /// `table` must already be validated as a plain identifier; it is spliced into the query
+    pub async fn connect(config: &DatabaseConfig, table: &str) -> Result<Self, DataError> {
+        let (client, connection) =
+            tokio_postgres::connect(&config.connection_string(), NoTls).await?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                error!(error = %e, "Database connection error");
+            }
+        });
+
+        let query = format!(
+            "SELECT symbol, timestamp, open, high, low, close, volume, vwap, trade_count
+            FROM {}
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp < $3
+            ORDER BY timestamp ASC",
+            table
+        );
+        Ok(Self { client, query })
+    }
+}
+
+#[async_trait]
+impl HistoricalDataSource for DbSource {
+    fn name(&self) -> &'static str {
+        "db"
+    }
+
+    async fn load_bars(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HistoricalBar>, DataError> {
+        let rows = self
+            .client
+            .query(&self.query, &[&symbol, &start, &end])
+            .await?;
+
+        let mut bars = Vec::with_capacity(rows.len());
+        for row in rows {
+            let trade_count: Option<i64> = row.try_get(8)?;
+            bars.push(HistoricalBar {
+                symbol: row.try_get(0)?,
+                timestamp: row.try_get(1)?,
+                open: row.try_get(2)?,
+                high: row.try_get(3)?,
+                low: row.try_get(4)?,
+                close: row.try_get(5)?,
+                volume: row.try_get(6)?,
+                vwap: row.try_get(7)?,
+                trade_count: trade_count.map(|n| n.max(0) as u64),
+            });
+        }
+        Ok(bars)
+    }
+}
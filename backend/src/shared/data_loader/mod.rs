@@ -0,0 +1,204 @@
+mod alpaca;
+mod cache;
+mod csv_file;
+mod db;
+mod parquet_file;
+
+pub use alpaca::{Adjustment, AlpacaFeed, AlpacaSource, Timeframe, TimeframeUnit};
+pub use cache::CachedSource;
+pub use csv_file::CsvSource;
+pub use db::DbSource;
+pub use parquet_file::ParquetSource;
+
+use crate::shared::config::{Config, HistoricalSource};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::fmt;
+use std::path::PathBuf;
+
+/// One OHLCV bar, whichever source it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoricalBar {
+    pub symbol: String,
+    pub timestamp: DateTime<Utc>, // Bar open time
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub vwap: Option<f64>,
+    pub trade_count: Option<u64>,
+}
+
+/// Anything the backtester can replay bars from
+#[async_trait]
+pub trait HistoricalDataSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Identifies this source's data (endpoint, timeframe, feed, ...) for the local cache.
+    /// `None` for sources that are already local and not worth caching.
+    fn cache_key(&self) -> Option<String> {
+        None
+    }
+
+    /// Bars for `symbol` with `start <= timestamp < end`, oldest first
+    async fn load_bars(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HistoricalBar>, DataError>;
+}
+
+#[derive(Debug)]
+pub enum DataError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Db(tokio_postgres::Error),
+    Http(reqwest::Error),
+    Api {
+        status: u16,
+        message: String,
+    },
+    Parse(String),
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            DataError::Db(e) => write!(f, "database error: {}", e),
+            DataError::Http(e) => write!(f, "HTTP error: {}", e),
+            DataError::Api { status, message } => {
+                write!(f, "API returned {}: {}", status, message)
+            }
+            DataError::Parse(msg) => write!(f, "parse error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DataError::Io { source, .. } => Some(source),
+            DataError::Db(e) => Some(e),
+            DataError::Http(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<tokio_postgres::Error> for DataError {
+    fn from(e: tokio_postgres::Error) -> Self {
+        DataError::Db(e)
+    }
+}
+
+impl From<reqwest::Error> for DataError {
+    fn from(e: reqwest::Error) -> Self {
+        DataError::Http(e)
+    }
+}
+
+/// Builds the source selected by `[backtest] data_source`, behind the local cache when enabled
+pub async fn historical_source(
+    config: &Config,
+) -> Result<Box<dyn HistoricalDataSource>, DataError> {
+    let backtest = &config.backtest;
+    let source: Box<dyn HistoricalDataSource> = match backtest.data_source {
+        HistoricalSource::Db => {
+            Box::new(DbSource::connect(&config.database, &backtest.table).await?)
+        }
+        HistoricalSource::Alpaca => Box::new(
+            AlpacaSource::new(&config.alpaca)
+                .with_timeframe(backtest.timeframe)
+                .with_feed(backtest.feed)
+                .with_adjustment(backtest.adjustment),
+        ),
+        HistoricalSource::Csv => Box::new(CsvSource::new(&backtest.path)),
+        HistoricalSource::Parquet => Box::new(ParquetSource::new(&backtest.path)),
+    };
+
+    if config.cache.enabled && source.cache_key().is_some() {
+        return Ok(Box::new(CachedSource::new(source, config.cache.dir())));
+    }
+    Ok(source)
+}
+
+/// Expands `{symbol}` in a vendor file path, so one template covers a directory of per-symbol files
+fn symbol_path(template: &str, symbol: &str) -> PathBuf {
+    PathBuf::from(template.replace("{symbol}", symbol))
+}
+
+/// Bar fields vendor files are matched against, by column name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Symbol,
+    Timestamp,
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    Vwap,
+    TradeCount,
+}
+
+impl Column {
+    /// Case-insensitive, accepting the common vendor spellings
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "symbol" | "ticker" => Some(Column::Symbol),
+            "timestamp" | "time" | "date" | "datetime" | "t" => Some(Column::Timestamp),
+            "open" | "o" => Some(Column::Open),
+            "high" | "h" => Some(Column::High),
+            "low" | "l" => Some(Column::Low),
+            "close" | "c" => Some(Column::Close),
+            "volume" | "v" => Some(Column::Volume),
+            "vwap" | "vw" => Some(Column::Vwap),
+            "trade_count" | "trades" | "n" => Some(Column::TradeCount),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps the bars inside `[start, end)`, sorted oldest first
+fn finish_bars(
+    mut bars: Vec<HistoricalBar>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<HistoricalBar> {
+    bars.retain(|bar| bar.timestamp >= start && bar.timestamp < end);
+    bars.sort_by_key(|bar| bar.timestamp);
+    bars
+}
+
+/// Accepts RFC 3339, `YYYY-MM-DD[ HH:MM:SS]` (taken as UTC) or a Unix epoch in s/ms/µs/ns
+fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(text) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(ts) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(ts.and_utc());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|ts| ts.and_utc());
+    }
+    text.parse::<i64>().ok().and_then(from_epoch)
+}
+
+/// Guesses the unit of an integer epoch from its magnitude
+fn from_epoch(value: i64) -> Option<DateTime<Utc>> {
+    let nanos = match value.unsigned_abs() {
+        v if v >= 100_000_000_000_000_000 => value,
+        v if v >= 100_000_000_000_000 => value.checked_mul(1_000)?,
+        v if v >= 100_000_000_000 => value.checked_mul(1_000_000)?,
+        _ => value.checked_mul(1_000_000_000)?,
+    };
+    Some(Utc.timestamp_nanos(nanos))
+}
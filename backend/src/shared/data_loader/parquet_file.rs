@@ -0,0 +1,242 @@
+use super::{
+    finish_bars, from_epoch, parse_timestamp, symbol_path, Column, DataError, HistoricalBar,
+    HistoricalDataSource,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::Field;
+use parquet::schema::parser::parse_message_type;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Layout `write_bars` produces; `read_bars` reads it like any vendor file
+const BARS_SCHEMA: &str = "
+    message bars {
+        REQUIRED INT64 timestamp;
+        REQUIRED DOUBLE open;
+        REQUIRED DOUBLE high;
+        REQUIRED DOUBLE low;
+        REQUIRED DOUBLE close;
+        REQUIRED DOUBLE volume;
+        OPTIONAL DOUBLE vwap;
+        OPTIONAL INT64 trade_count;
+    }
+";
+
+/// Bars from vendor Parquet files, with columns matched by name as for CSV
+pub struct ParquetSource {
+    path: String,
+}
+
+impl ParquetSource {
+    /// `path` is a file or a template such as `data/{symbol}.parquet`
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl HistoricalDataSource for ParquetSource {
+    fn name(&self) -> &'static str {
+        "parquet"
+    }
+
+    async fn load_bars(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<HistoricalBar>, DataError> {
+        let path = symbol_path(&self.path, symbol);
+        let symbol = symbol.to_string();
+        let bars = tokio::task::spawn_blocking(move || read_bars(&path, &symbol))
+            .await
+            .map_err(|e| DataError::Parse(format!("Parquet reader task failed: {}", e)))??;
+        Ok(finish_bars(bars, start, end))
+    }
+}
+
+pub(super) fn read_bars(path: &Path, symbol: &str) -> Result<Vec<HistoricalBar>, DataError> {
+    let parse_error =
+        |e: parquet::errors::ParquetError| DataError::Parse(format!("{}: {}", path.display(), e));
+
+    let file = File::open(path).map_err(|source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let reader = SerializedFileReader::new(file).map_err(parse_error)?;
+    let rows = reader.get_row_iter(None).map_err(parse_error)?;
+
+    let mut bars = Vec::new();
+    for (index, row) in rows.enumerate() {
+        let row = row.map_err(parse_error)?;
+        let mut bar = RowBar::default();
+        for (name, field) in row.get_column_iter() {
+            match Column::from_name(name) {
+                Some(Column::Symbol) => bar.symbol = field_text(field),
+                Some(Column::Timestamp) => bar.timestamp = field_timestamp(field),
+                Some(Column::Open) => bar.open = field_number(field),
+                Some(Column::High) => bar.high = field_number(field),
+                Some(Column::Low) => bar.low = field_number(field),
+                Some(Column::Close) => bar.close = field_number(field),
+                Some(Column::Volume) => bar.volume = field_number(field),
+                Some(Column::Vwap) => bar.vwap = field_number(field),
+                Some(Column::TradeCount) => bar.trade_count = field_number(field),
+                None => {}
+            }
+        }
+
+        if let Some(row_symbol) = &bar.symbol {
+            if !row_symbol.eq_ignore_ascii_case(symbol) {
+                continue;
+            }
+        }
+
+        let missing = |name: &str| {
+            DataError::Parse(format!(
+                "{}: row {} has no usable '{}' column",
+                path.display(),
+                index,
+                name
+            ))
+        };
+        bars.push(HistoricalBar {
+            symbol: symbol.to_string(),
+            timestamp: bar.timestamp.ok_or_else(|| missing("timestamp"))?,
+            open: bar.open.ok_or_else(|| missing("open"))?,
+            high: bar.high.ok_or_else(|| missing("high"))?,
+            low: bar.low.ok_or_else(|| missing("low"))?,
+            close: bar.close.ok_or_else(|| missing("close"))?,
+            volume: bar.volume.ok_or_else(|| missing("volume"))?,
+            vwap: bar.vwap,
+            trade_count: bar.trade_count.map(|n| n.max(0.0) as u64),
+        });
+    }
+    Ok(bars)
+}
+
+/// Writes one symbol's bars with nanosecond timestamps, in the order given
+pub(super) fn write_bars(path: &Path, bars: &[HistoricalBar]) -> Result<(), DataError> {
+    let parse_error =
+        |e: parquet::errors::ParquetError| DataError::Parse(format!("{}: {}", path.display(), e));
+
+    let file = File::create(path).map_err(|source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let schema = Arc::new(parse_message_type(BARS_SCHEMA).map_err(parse_error)?);
+    let mut writer =
+        SerializedFileWriter::new(file, schema, Arc::new(WriterProperties::builder().build()))
+            .map_err(parse_error)?;
+
+    let timestamps: Vec<i64> = bars
+        .iter()
+        .map(|bar| bar.timestamp.timestamp_nanos_opt().unwrap_or_default())
+        .collect();
+    let prices: [Vec<f64>; 5] = [
+        bars.iter().map(|bar| bar.open).collect(),
+        bars.iter().map(|bar| bar.high).collect(),
+        bars.iter().map(|bar| bar.low).collect(),
+        bars.iter().map(|bar| bar.close).collect(),
+        bars.iter().map(|bar| bar.volume).collect(),
+    ];
+    // Optional columns take only the present values, with a definition level per row
+    let vwap: Vec<f64> = bars.iter().filter_map(|bar| bar.vwap).collect();
+    let vwap_levels: Vec<i16> = bars.iter().map(|bar| bar.vwap.is_some() as i16).collect();
+    let trade_counts: Vec<i64> = bars
+        .iter()
+        .filter_map(|bar| bar.trade_count.map(|n| n as i64))
+        .collect();
+    let trade_count_levels: Vec<i16> = bars
+        .iter()
+        .map(|bar| bar.trade_count.is_some() as i16)
+        .collect();
+
+    let mut row_group = writer.next_row_group().map_err(parse_error)?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column().map_err(parse_error)? {
+        match index {
+            0 => column
+                .typed::<Int64Type>()
+                .write_batch(&timestamps, None, None)
+                .map(|_| ()),
+            1..=5 => column
+                .typed::<DoubleType>()
+                .write_batch(&prices[index - 1], None, None)
+                .map(|_| ()),
+            6 => column
+                .typed::<DoubleType>()
+                .write_batch(&vwap, Some(&vwap_levels), None)
+                .map(|_| ()),
+            _ => column
+                .typed::<Int64Type>()
+                .write_batch(&trade_counts, Some(&trade_count_levels), None)
+                .map(|_| ()),
+        }
+        .map_err(parse_error)?;
+        column.close().map_err(parse_error)?;
+        index += 1;
+    }
+    row_group.close().map_err(parse_error)?;
+    writer.close().map_err(parse_error)?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct RowBar {
+    symbol: Option<String>,
+    timestamp: Option<DateTime<Utc>>,
+    open: Option<f64>,
+    high: Option<f64>,
+    low: Option<f64>,
+    close: Option<f64>,
+    volume: Option<f64>,
+    vwap: Option<f64>,
+    trade_count: Option<f64>,
+}
+
+fn field_text(field: &Field) -> Option<String> {
+    match field {
+        Field::Str(text) => Some(text.clone()),
+        _ => None,
+    }
+}
+
+fn field_number(field: &Field) -> Option<f64> {
+    match field {
+        Field::Byte(v) => Some(*v as f64),
+        Field::Short(v) => Some(*v as f64),
+        Field::Int(v) => Some(*v as f64),
+        Field::Long(v) => Some(*v as f64),
+        Field::UByte(v) => Some(*v as f64),
+        Field::UShort(v) => Some(*v as f64),
+        Field::UInt(v) => Some(*v as f64),
+        Field::ULong(v) => Some(*v as f64),
+        Field::Float(v) => Some(*v as f64),
+        Field::Double(v) => Some(*v),
+        Field::Str(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn field_timestamp(field: &Field) -> Option<DateTime<Utc>> {
+    match field {
+        Field::TimestampMillis(ms) => DateTime::from_timestamp_millis(*ms),
+        Field::TimestampMicros(us) => DateTime::from_timestamp_micros(*us),
+        Field::Date(days) => NaiveDate::from_ymd_opt(1970, 1, 1)?
+            .checked_add_signed(chrono::Duration::days(*days as i64))?
+            .and_hms_opt(0, 0, 0)
+            .map(|ts| ts.and_utc()),
+        Field::Int(v) => from_epoch(*v as i64),
+        Field::Long(v) => from_epoch(*v),
+        Field::Str(text) => parse_timestamp(text),
+        _ => None,
+    }
+}
@@ -0,0 +1,395 @@
+use crate::shared::market_event::now_ns;
+use crate::shared::message_bus::{Bus, BusError, Message, MessageBus};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+
+// A dead letter is the original message, untouched, published to
+// `<topic>.dlq` with the failure recorded in extra headers. Keeping the
+// payload byte-for-byte lets `optitrade_dlq` replay it once fixed.
+
+pub const DLQ_SUFFIX: &str = ".dlq";
+
+const SOURCE_TOPIC_HEADER: &str = "optitrade-dlq-topic";
+const SOURCE_PARTITION_HEADER: &str = "optitrade-dlq-partition";
+const SOURCE_OFFSET_HEADER: &str = "optitrade-dlq-offset";
+const CONSUMER_HEADER: &str = "optitrade-dlq-consumer";
+const ERROR_HEADER: &str = "optitrade-dlq-error";
+const FAILED_AT_HEADER: &str = "optitrade-dlq-failed-at-ns";
+/// Number of times a message has been replayed from a dead-letter topic
+pub const REPLAY_COUNT_HEADER: &str = "optitrade-replay-count";
+
+/// Topic that dead letters from `topic` are published to
+pub fn dead_letter_topic(topic: &str) -> String {
+    format!("{}{}", topic, DLQ_SUFFIX)
+}
+
+/// A message a consumer could not process, with where it came from and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    pub source_topic: String,
+    pub partition: Option<i32>,
+    pub offset: Option<i64>,
+    pub consumer: String,
+    pub error: String,
+    pub failed_at_ns: u64,
+    pub key: Option<String>,
+    pub payload: Vec<u8>,
+    pub headers: Vec<(String, Vec<u8>)>, // The original message's headers
+}
+
+impl DeadLetter {
+    pub fn new(message: &Message, consumer: &str, error: impl fmt::Display) -> Self {
+        Self {
+            source_topic: message.topic.clone(),
+            partition: message.partition,
+            offset: message.offset,
+            consumer: consumer.to_string(),
+            error: error.to_string(),
+            failed_at_ns: now_ns(),
+            key: message.key.clone(),
+            payload: message.payload.clone(),
+            headers: message.headers.clone(),
+        }
+    }
+
+    /// The message to publish on the dead-letter topic
+    pub fn to_message(&self) -> Message {
+        let mut message = Message::new(dead_letter_topic(&self.source_topic), self.payload.clone());
+        message.key = self.key.clone();
+        message.headers = self.headers.clone();
+
+        let mut message = message
+            .with_header(SOURCE_TOPIC_HEADER, self.source_topic.as_str())
+            .with_header(CONSUMER_HEADER, self.consumer.as_str())
+            .with_header(ERROR_HEADER, self.error.as_str())
+            .with_header(FAILED_AT_HEADER, self.failed_at_ns.to_string());
+        if let Some(partition) = self.partition {
+            message = message.with_header(SOURCE_PARTITION_HEADER, partition.to_string());
+        }
+        if let Some(offset) = self.offset {
+            message = message.with_header(SOURCE_OFFSET_HEADER, offset.to_string());
+        }
+        message
+    }
+
+    /// Reads a message received from a dead-letter topic
+    pub fn from_message(message: &Message) -> Result<Self, BusError> {
+        let text = |name: &str| {
+            message
+                .header(name)
+                .map(|v| String::from_utf8_lossy(v).into_owned())
+        };
+
+        let source_topic = text(SOURCE_TOPIC_HEADER).ok_or_else(|| {
+            BusError::Decode(format!(
+                "{} has no {} header",
+                message.topic, SOURCE_TOPIC_HEADER
+            ))
+        })?;
+
+        Ok(Self {
+            source_topic,
+            partition: parse_header(message, SOURCE_PARTITION_HEADER),
+            offset: parse_header(message, SOURCE_OFFSET_HEADER),
+            consumer: text(CONSUMER_HEADER).unwrap_or_default(),
+            error: text(ERROR_HEADER).unwrap_or_default(),
+            failed_at_ns: parse_header(message, FAILED_AT_HEADER).unwrap_or_default(),
+            key: message.key.clone(),
+            payload: message.payload.clone(),
+            headers: message
+                .headers
+                .iter()
+                .filter(|(k, _)| !k.starts_with("optitrade-dlq-"))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// The original message, ready to publish back to its source topic
+    pub fn replay_message(&self) -> Message {
+        let replays = self
+            .headers
+            .iter()
+            .find(|(k, _)| k == REPLAY_COUNT_HEADER)
+            .and_then(|(_, v)| String::from_utf8_lossy(v).parse::<u32>().ok())
+            .unwrap_or(0);
+
+        let mut message = Message::new(self.source_topic.as_str(), self.payload.clone());
+        message.key = self.key.clone();
+        message.headers = self
+            .headers
+            .iter()
+            .filter(|(k, _)| k != REPLAY_COUNT_HEADER)
+            .cloned()
+            .collect();
+        message.with_header(REPLAY_COUNT_HEADER, (replays + 1).to_string())
+    }
+
+    /// One JSON line for `optitrade_dlq dump`; text payloads stay editable
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&DeadLetterRecord::from(self))
+    }
+
+    pub fn from_json(line: &str) -> Result<Self, String> {
+        let record: DeadLetterRecord = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        record.try_into()
+    }
+}
+
+impl fmt::Display for DeadLetter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source_topic)?;
+        if let (Some(partition), Some(offset)) = (self.partition, self.offset) {
+            write!(f, "[{}]@{}", partition, offset)?;
+        }
+        write!(f, " in {}: {}", self.consumer, self.error)
+    }
+}
+
+/// Publishes messages a consumer gave up on to their dead-letter topic
+#[derive(Clone)]
+pub struct DeadLetterQueue {
+    transport: Arc<dyn MessageBus>,
+    consumer: String,
+}
+
+impl DeadLetterQueue {
+    pub fn new(bus: &Bus, consumer: &str) -> Self {
+        Self {
+            transport: Arc::clone(bus.transport()),
+            consumer: consumer.to_string(),
+        }
+    }
+
+    pub async fn send(&self, message: &Message, error: impl fmt::Display) -> Result<(), BusError> {
+        let letter = DeadLetter::new(message, &self.consumer, error);
+        warn!(
+            topic = %letter.source_topic,
+            consumer = %letter.consumer,
+            error = %letter.error,
+            correlation_id = message.correlation_id().as_ref().map(|id| id.as_str()),
+            "Dead-lettering message"
+        );
+        self.transport.publish(letter.to_message()).await
+    }
+}
+
+fn parse_header<T: std::str::FromStr>(message: &Message, name: &str) -> Option<T> {
+    message
+        .header(name)
+        .and_then(|v| std::str::from_utf8(v).ok())
+        .and_then(|v| v.parse().ok())
+}
+
+#[derive(Serialize, Deserialize)]
+struct DeadLetterRecord {
+    source_topic: String,
+    #[serde(default)]
+    partition: Option<i32>,
+    #[serde(default)]
+    offset: Option<i64>,
+    #[serde(default)]
+    consumer: String,
+    #[serde(default)]
+    error: String,
+    #[serde(default)]
+    failed_at_ns: u64,
+    #[serde(default)]
+    key: Option<String>,
+    payload: RecordPayload,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RecordPayload {
+    Text(String),
+    Base64(String),
+}
+
+impl From<&DeadLetter> for DeadLetterRecord {
+    fn from(letter: &DeadLetter) -> Self {
+        let payload = match std::str::from_utf8(&letter.payload) {
+            Ok(text) => RecordPayload::Text(text.to_string()),
+            Err(_) => RecordPayload::Base64(BASE64.encode(&letter.payload)),
+        };
+        Self {
+            source_topic: letter.source_topic.clone(),
+            partition: letter.partition,
+            offset: letter.offset,
+            consumer: letter.consumer.clone(),
+            error: letter.error.clone(),
+            failed_at_ns: letter.failed_at_ns,
+            key: letter.key.clone(),
+            payload,
+            headers: letter
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), String::from_utf8_lossy(v).into_owned()))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<DeadLetterRecord> for DeadLetter {
+    type Error = String;
+
+    fn try_from(record: DeadLetterRecord) -> Result<Self, Self::Error> {
+        let payload = match record.payload {
+            RecordPayload::Text(text) => text.into_bytes(),
+            RecordPayload::Base64(data) => BASE64
+                .decode(data)
+                .map_err(|e| format!("invalid base64 payload: {}", e))?,
+        };
+        Ok(Self {
+            source_topic: record.source_topic,
+            partition: record.partition,
+            offset: record.offset,
+            consumer: record.consumer,
+            error: record.error,
+            failed_at_ns: record.failed_at_ns,
+            key: record.key,
+            payload,
+            headers: record
+                .headers
+                .into_iter()
+                .map(|(k, v)| (k, v.into_bytes()))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::message_bus::{MemoryBus, OffsetReset, TRADE_SIGNALS};
+
+    fn failed_message() -> Message {
+        let mut message = Message::new("trade_signals", "{\"symbol\": 42}")
+            .with_key("AAPL")
+            .with_header("optitrade-codec", "json");
+        message.partition = Some(3);
+        message.offset = Some(1042);
+        message
+    }
+
+    fn text_header<'a>(message: &'a Message, name: &str) -> Option<&'a str> {
+        message
+            .header(name)
+            .map(|v| std::str::from_utf8(v).unwrap())
+    }
+
+    #[test]
+    fn dead_letter_message_records_where_and_why_it_failed() {
+        let letter = DeadLetter::new(&failed_message(), "execution_agent", "invalid symbol");
+        let message = letter.to_message();
+
+        assert_eq!(message.topic, "trade_signals.dlq");
+        assert_eq!(message.payload, failed_message().payload);
+        assert_eq!(message.key.as_deref(), Some("AAPL"));
+        assert_eq!(text_header(&message, "optitrade-codec"), Some("json"));
+        assert_eq!(
+            text_header(&message, SOURCE_TOPIC_HEADER),
+            Some("trade_signals")
+        );
+        assert_eq!(text_header(&message, SOURCE_PARTITION_HEADER), Some("3"));
+        assert_eq!(text_header(&message, SOURCE_OFFSET_HEADER), Some("1042"));
+        assert_eq!(
+            text_header(&message, CONSUMER_HEADER),
+            Some("execution_agent")
+        );
+        assert_eq!(text_header(&message, ERROR_HEADER), Some("invalid symbol"));
+        assert_eq!(
+            text_header(&message, FAILED_AT_HEADER),
+            Some(letter.failed_at_ns.to_string().as_str())
+        );
+
+        // Reading it back strips the dead-letter headers and keeps the original ones
+        assert_eq!(DeadLetter::from_message(&message).unwrap(), letter);
+        assert!(DeadLetter::from_message(&failed_message()).is_err());
+    }
+
+    #[test]
+    fn replays_count_how_often_a_message_came_back() {
+        let letter = DeadLetter::new(&failed_message(), "execution_agent", "invalid symbol");
+        let replayed = letter.replay_message();
+        assert_eq!(replayed.topic, "trade_signals");
+        assert_eq!(replayed.payload, failed_message().payload);
+        assert_eq!(replayed.key.as_deref(), Some("AAPL"));
+        assert_eq!(text_header(&replayed, "optitrade-codec"), Some("json"));
+        assert_eq!(text_header(&replayed, REPLAY_COUNT_HEADER), Some("1"));
+        assert!(replayed.header(SOURCE_TOPIC_HEADER).is_none());
+
+        // Failing again and being replayed again bumps the count rather than adding a header
+        let again = DeadLetter::new(&replayed, "execution_agent", "still invalid").replay_message();
+        let counts: Vec<&(String, Vec<u8>)> = again
+            .headers
+            .iter()
+            .filter(|(k, _)| k == REPLAY_COUNT_HEADER)
+            .collect();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(text_header(&again, REPLAY_COUNT_HEADER), Some("2"));
+    }
+
+    #[test]
+    fn json_records_round_trip_text_and_binary_payloads() {
+        let letter = DeadLetter::new(&failed_message(), "execution_agent", "invalid symbol");
+        let line = letter.to_json().unwrap();
+        assert!(line.contains(r#""text":"{\"symbol\": 42}""#));
+        assert_eq!(DeadLetter::from_json(&line).unwrap(), letter);
+
+        let binary = Message::new("market_data", vec![0xff, 0x00, 0x12]);
+        let letter = DeadLetter::new(&binary, "storage_agent", "truncated flatbuffer");
+        let line = letter.to_json().unwrap();
+        assert!(line.contains(r#""base64":"/wAS""#));
+        assert_eq!(DeadLetter::from_json(&line).unwrap(), letter);
+
+        // Edited lines only need the topic and payload
+        let edited =
+            DeadLetter::from_json(r#"{"source_topic":"trade_signals","payload":{"text":"{}"}}"#)
+                .unwrap();
+        assert_eq!(edited.replay_message().payload, b"{}");
+        assert!(DeadLetter::from_json(r#"{"source_topic":"t","payload":{"base64":"!"}}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn decode_failures_land_on_the_dead_letter_topic() {
+        let bus = Bus::new(Arc::new(MemoryBus::new()));
+        let mut dead = bus
+            .transport()
+            .subscribe(
+                &["trade_signals.dlq"],
+                "optitrade_dlq",
+                OffsetReset::Earliest,
+            )
+            .await
+            .unwrap();
+        let mut signals = bus
+            .subscribe(&TRADE_SIGNALS, "execution_agent")
+            .await
+            .unwrap();
+        let queue = DeadLetterQueue::new(&bus, "execution_agent");
+
+        bus.transport()
+            .publish(Message::new("trade_signals", "not json").with_key("AAPL"))
+            .await
+            .unwrap();
+        let delivery = signals.recv().await.unwrap().unwrap();
+        let error = delivery.value.unwrap_err();
+        queue.send(&delivery.message, &error).await.unwrap();
+
+        let letter = DeadLetter::from_message(&dead.recv().await.unwrap().unwrap()).unwrap();
+        assert_eq!(letter.source_topic, "trade_signals");
+        assert_eq!(letter.partition, Some(0));
+        assert_eq!(letter.offset, Some(0));
+        assert_eq!(letter.consumer, "execution_agent");
+        assert_eq!(letter.error, error.to_string());
+        assert_eq!(letter.key.as_deref(), Some("AAPL"));
+        assert_eq!(letter.payload, b"not json");
+    }
+}
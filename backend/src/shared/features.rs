@@ -0,0 +1,482 @@
+use crate::shared::data_loader::{HistoricalBar, Timeframe, TimeframeUnit};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+// Features are computed locally from raw bars, so every data source gives a
+// strategy the same values. Each one is a streaming indicator fed bars in
+// order; it reports nothing until it has seen `lookback()` bars.
+
+/// A rolling feature named `<kind>_<period>` in config and in `MarketData::features`, e.g. `sma_50`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FeatureSpec {
+    pub kind: FeatureKind,
+    pub period: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeatureKind {
+    Sma,        // Simple moving average of the close
+    Ema,        // Exponential moving average of the close, seeded with the SMA
+    Rsi,        // Wilder's relative strength index
+    Atr,        // Wilder's average true range
+    Return,     // Close-to-close return over `period` bars
+    Volatility, // Standard deviation of log returns over `period` bars
+}
+
+impl FeatureSpec {
+    pub const fn new(kind: FeatureKind, period: usize) -> Self {
+        Self { kind, period }
+    }
+
+    /// Bars needed before the first value
+    pub fn lookback(&self) -> usize {
+        match self.kind {
+            FeatureKind::Sma | FeatureKind::Ema => self.period,
+            FeatureKind::Rsi | FeatureKind::Atr | FeatureKind::Return | FeatureKind::Volatility => {
+                self.period + 1
+            }
+        }
+    }
+}
+
+impl FeatureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeatureKind::Sma => "sma",
+            FeatureKind::Ema => "ema",
+            FeatureKind::Rsi => "rsi",
+            FeatureKind::Atr => "atr",
+            FeatureKind::Return => "return",
+            FeatureKind::Volatility => "volatility",
+        }
+    }
+}
+
+impl fmt::Display for FeatureSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.kind.as_str(), self.period)
+    }
+}
+
+impl FromStr for FeatureSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || {
+            format!(
+                "invalid feature '{}' (expected <kind>_<period> with kind sma, ema, rsi, atr, return or volatility)",
+                s
+            )
+        };
+
+        let (kind, period) = s.rsplit_once('_').ok_or_else(invalid)?;
+        let kind = match kind.to_ascii_lowercase().as_str() {
+            "sma" => FeatureKind::Sma,
+            "ema" => FeatureKind::Ema,
+            "rsi" => FeatureKind::Rsi,
+            "atr" => FeatureKind::Atr,
+            "return" => FeatureKind::Return,
+            "volatility" => FeatureKind::Volatility,
+            _ => return Err(invalid()),
+        };
+        let period: usize = period.parse().map_err(|_| invalid())?;
+        if period == 0 {
+            return Err(format!("feature '{}' needs a period of at least 1", s));
+        }
+        Ok(FeatureSpec::new(kind, period))
+    }
+}
+
+impl TryFrom<String> for FeatureSpec {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<FeatureSpec> for String {
+    fn from(spec: FeatureSpec) -> Self {
+        spec.to_string()
+    }
+}
+
+/// A bar with the features that had warmed up by the time it closed
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrichedBar {
+    pub bar: HistoricalBar,
+    pub features: BTreeMap<String, f64>,
+}
+
+/// Computes a set of features over one symbol's bars, oldest first
+pub struct FeatureEngine {
+    features: Vec<(String, Indicator)>,
+}
+
+impl FeatureEngine {
+    pub fn new(specs: &[FeatureSpec]) -> Self {
+        let mut features: Vec<(String, Indicator)> = Vec::new();
+        for spec in specs {
+            let name = spec.to_string();
+            if !features.iter().any(|(n, _)| *n == name) {
+                features.push((name, Indicator::new(*spec)));
+            }
+        }
+        Self { features }
+    }
+
+    /// Bars needed before every feature has a value
+    pub fn lookback(&self) -> usize {
+        self.features
+            .iter()
+            .map(|(_, indicator)| indicator.spec.lookback())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Feeds the next bar and returns it with every feature that has a value
+    pub fn update(&mut self, bar: &HistoricalBar) -> EnrichedBar {
+        let mut features = BTreeMap::new();
+        for (name, indicator) in &mut self.features {
+            if let Some(value) = indicator.update(bar) {
+                features.insert(name.clone(), value);
+            }
+        }
+        EnrichedBar {
+            bar: bar.clone(),
+            features,
+        }
+    }
+
+    /// Whether `bar` carries a value for every configured feature
+    pub fn is_complete(&self, bar: &EnrichedBar) -> bool {
+        bar.features.len() == self.features.len()
+    }
+
+    /// Runs all `bars` through the engine and keeps those at or after `from`.
+    /// Bars before `from` only warm the indicators up.
+    pub fn enrich(&mut self, bars: &[HistoricalBar], from: DateTime<Utc>) -> Vec<EnrichedBar> {
+        bars.iter()
+            .map(|bar| self.update(bar))
+            .filter(|enriched| enriched.bar.timestamp >= from)
+            .collect()
+    }
+}
+
+/// How far before `start` to load bars so `bars` of them precede it.
+/// Intraday bars only cover the regular session, and weekends and holidays add
+/// gaps, so the estimate is padded rather than exact.
+pub fn warmup_start(start: DateTime<Utc>, timeframe: Timeframe, bars: usize) -> DateTime<Utc> {
+    if bars == 0 {
+        return start;
+    }
+    let bars = bars as i64;
+    let amount = timeframe.amount as i64;
+    let calendar_days = match timeframe.unit {
+        TimeframeUnit::Minute | TimeframeUnit::Hour => {
+            let minutes = if timeframe.unit == TimeframeUnit::Hour {
+                amount * 60
+            } else {
+                amount
+            };
+            let per_session = (390 / minutes).max(1); // 6.5 hour regular session
+            let sessions = (bars + per_session - 1) / per_session;
+            sessions * 7 / 5 + 5
+        }
+        TimeframeUnit::Day => bars * 7 / 5 + 10,
+        TimeframeUnit::Week => (bars + 1) * 7,
+        TimeframeUnit::Month => (bars + 1) * amount * 31,
+    };
+    start - Duration::days(calendar_days)
+}
+
+struct Indicator {
+    spec: FeatureSpec,
+    state: IndicatorState,
+}
+
+enum IndicatorState {
+    Sma {
+        window: VecDeque<f64>,
+        sum: f64,
+    },
+    Ema {
+        seed: Vec<f64>,
+        value: Option<f64>,
+    },
+    Rsi {
+        prev_close: Option<f64>,
+        gains: Vec<f64>,
+        losses: Vec<f64>,
+        average: Option<(f64, f64)>,
+    },
+    Atr {
+        prev_close: Option<f64>,
+        ranges: Vec<f64>,
+        value: Option<f64>,
+    },
+    Return {
+        closes: VecDeque<f64>,
+    },
+    Volatility {
+        prev_close: Option<f64>,
+        returns: VecDeque<f64>,
+    },
+}
+
+impl Indicator {
+    fn new(spec: FeatureSpec) -> Self {
+        let state = match spec.kind {
+            FeatureKind::Sma => IndicatorState::Sma {
+                window: VecDeque::with_capacity(spec.period + 1),
+                sum: 0.0,
+            },
+            FeatureKind::Ema => IndicatorState::Ema {
+                seed: Vec::with_capacity(spec.period),
+                value: None,
+            },
+            FeatureKind::Rsi => IndicatorState::Rsi {
+                prev_close: None,
+                gains: Vec::with_capacity(spec.period),
+                losses: Vec::with_capacity(spec.period),
+                average: None,
+            },
+            FeatureKind::Atr => IndicatorState::Atr {
+                prev_close: None,
+                ranges: Vec::with_capacity(spec.period),
+                value: None,
+            },
+            FeatureKind::Return => IndicatorState::Return {
+                closes: VecDeque::with_capacity(spec.period + 1),
+            },
+            FeatureKind::Volatility => IndicatorState::Volatility {
+                prev_close: None,
+                returns: VecDeque::with_capacity(spec.period + 1),
+            },
+        };
+        Self { spec, state }
+    }
+
+    fn update(&mut self, bar: &HistoricalBar) -> Option<f64> {
+        let period = self.spec.period;
+        let close = bar.close;
+        match &mut self.state {
+            IndicatorState::Sma { window, sum } => {
+                window.push_back(close);
+                *sum += close;
+                if window.len() > period {
+                    *sum -= window.pop_front().unwrap_or_default();
+                }
+                (window.len() == period).then(|| *sum / period as f64)
+            }
+            IndicatorState::Ema { seed, value } => {
+                let alpha = 2.0 / (period as f64 + 1.0);
+                match value {
+                    Some(ema) => *ema += alpha * (close - *ema),
+                    None => {
+                        seed.push(close);
+                        if seed.len() == period {
+                            *value = Some(seed.iter().sum::<f64>() / period as f64);
+                        }
+                    }
+                }
+                *value
+            }
+            IndicatorState::Rsi {
+                prev_close,
+                gains,
+                losses,
+                average,
+            } => {
+                let prev = prev_close.replace(close)?;
+                let change = close - prev;
+                let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+                match average {
+                    Some((avg_gain, avg_loss)) => {
+                        let n = period as f64;
+                        *avg_gain = (*avg_gain * (n - 1.0) + gain) / n;
+                        *avg_loss = (*avg_loss * (n - 1.0) + loss) / n;
+                    }
+                    None => {
+                        gains.push(gain);
+                        losses.push(loss);
+                        if gains.len() < period {
+                            return None;
+                        }
+                        *average = Some((
+                            gains.iter().sum::<f64>() / period as f64,
+                            losses.iter().sum::<f64>() / period as f64,
+                        ));
+                    }
+                }
+                average.map(|(avg_gain, avg_loss)| {
+                    if avg_loss == 0.0 {
+                        if avg_gain == 0.0 {
+                            50.0
+                        } else {
+                            100.0
+                        }
+                    } else {
+                        100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+                    }
+                })
+            }
+            IndicatorState::Atr {
+                prev_close,
+                ranges,
+                value,
+            } => {
+                let prev = prev_close.replace(close)?;
+                let true_range = (bar.high - bar.low)
+                    .max((bar.high - prev).abs())
+                    .max((bar.low - prev).abs());
+                match value {
+                    Some(atr) => *atr = (*atr * (period as f64 - 1.0) + true_range) / period as f64,
+                    None => {
+                        ranges.push(true_range);
+                        if ranges.len() == period {
+                            *value = Some(ranges.iter().sum::<f64>() / period as f64);
+                        }
+                    }
+                }
+                *value
+            }
+            IndicatorState::Return { closes } => {
+                closes.push_back(close);
+                if closes.len() > period + 1 {
+                    closes.pop_front();
+                }
+                match closes.front() {
+                    Some(first) if closes.len() == period + 1 && *first != 0.0 => {
+                        Some(close / first - 1.0)
+                    }
+                    _ => None,
+                }
+            }
+            IndicatorState::Volatility {
+                prev_close,
+                returns,
+            } => {
+                let prev = prev_close.replace(close)?;
+                if prev <= 0.0 || close <= 0.0 {
+                    return None;
+                }
+                returns.push_back((close / prev).ln());
+                if returns.len() > period {
+                    returns.pop_front();
+                }
+                if returns.len() < period || period < 2 {
+                    return (returns.len() == period).then_some(0.0);
+                }
+                let mean = returns.iter().sum::<f64>() / period as f64;
+                let variance =
+                    returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (period as f64 - 1.0);
+                Some(variance.sqrt())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bars(closes: &[f64]) -> Vec<HistoricalBar> {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| HistoricalBar {
+                symbol: "AAPL".to_string(),
+                timestamp: start + Duration::days(i as i64),
+                open: close,
+                high: close + 1.0,
+                low: close - 1.0,
+                close,
+                volume: 1000.0,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_and_formats_specs() {
+        let spec: FeatureSpec = "SMA_50".parse().unwrap();
+        assert_eq!(spec, FeatureSpec::new(FeatureKind::Sma, 50));
+        assert_eq!(spec.to_string(), "sma_50");
+        assert!("sma".parse::<FeatureSpec>().is_err());
+        assert!("median_5".parse::<FeatureSpec>().is_err());
+        assert!("sma_0".parse::<FeatureSpec>().is_err());
+    }
+
+    #[test]
+    fn features_appear_once_warmed_up() {
+        let mut engine = FeatureEngine::new(&[
+            FeatureSpec::new(FeatureKind::Sma, 3),
+            FeatureSpec::new(FeatureKind::Return, 3),
+        ]);
+        assert_eq!(engine.lookback(), 4);
+
+        let enriched: Vec<EnrichedBar> = bars(&[10.0, 11.0, 12.0, 13.0, 14.0])
+            .iter()
+            .map(|bar| engine.update(bar))
+            .collect();
+        assert!(enriched[1].features.is_empty());
+        assert_eq!(enriched[2].features.get("sma_3"), Some(&11.0));
+        assert!(!engine.is_complete(&enriched[2]));
+        assert!(engine.is_complete(&enriched[3]));
+        assert_eq!(enriched[3].features.get("sma_3"), Some(&12.0));
+        assert!((enriched[3].features["return_3"] - 0.3).abs() < 1e-12);
+        assert_eq!(enriched[4].features.get("sma_3"), Some(&13.0));
+    }
+
+    #[test]
+    fn duplicate_specs_are_computed_once() {
+        let spec = FeatureSpec::new(FeatureKind::Ema, 2);
+        let mut engine = FeatureEngine::new(&[spec, spec]);
+        let enriched = engine.update(&bars(&[10.0])[0]);
+        assert!(enriched.features.is_empty());
+        let enriched = engine.update(&bars(&[10.0, 12.0])[1]);
+        assert_eq!(enriched.features.len(), 1);
+        assert_eq!(enriched.features.get("ema_2"), Some(&11.0));
+    }
+
+    #[test]
+    fn enrich_uses_warmup_bars_but_only_returns_bars_from_the_start() {
+        let history = bars(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let from = history[2].timestamp;
+        let mut engine = FeatureEngine::new(&[FeatureSpec::new(FeatureKind::Sma, 2)]);
+
+        let enriched = engine.enrich(&history, from);
+        assert_eq!(enriched.len(), 3);
+        assert_eq!(enriched[0].bar.timestamp, from);
+        assert!(enriched.iter().all(|bar| engine.is_complete(bar)));
+        assert_eq!(enriched[0].features.get("sma_2"), Some(&2.5));
+    }
+
+    #[test]
+    fn warmup_start_reaches_back_far_enough() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 2, 13, 30, 0).unwrap();
+        let daily = Timeframe {
+            amount: 1,
+            unit: TimeframeUnit::Day,
+        };
+        let minute = Timeframe {
+            amount: 1,
+            unit: TimeframeUnit::Minute,
+        };
+
+        assert_eq!(warmup_start(start, daily, 0), start);
+        // 50 trading days span 70 calendar days, padded by 10 for holidays
+        assert_eq!(warmup_start(start, daily, 50), start - Duration::days(80));
+        // 400 minute bars need two sessions
+        assert_eq!(warmup_start(start, minute, 400), start - Duration::days(7));
+    }
+}
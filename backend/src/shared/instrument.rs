@@ -0,0 +1,709 @@
+use crate::shared::calendar::Market;
+use crate::shared::config::Config;
+use crate::shared::money::{Money, Price, Quantity};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Canonical symbols, which `Instrument` parses and prints:
+//
+//   equity   AAPL, BRK.B
+//   option   AAPL  250221C00200000   OCC: root padded to 6, YYMMDD, C/P, strike x 1000 in 8 digits
+//   crypto   BTC/USD
+//
+// Alpaca writes options without the padding (AAPL250221C00200000); both forms
+// parse. Trading parameters come from the reference-data file, falling back
+// to per-type defaults for anything not listed.
+
+/// Locations searched for the reference-data file when `[instruments] path` is unset
+const DEFAULT_REFERENCE_PATHS: [&str; 2] = ["instruments.toml", "backend/instruments.toml"];
+/// Width of the root field in an OCC symbol
+const OCC_ROOT_WIDTH: usize = 6;
+/// YYMMDD + C/P + 8-digit strike
+const OCC_SUFFIX_LEN: usize = 15;
+
+/// Something we can trade or subscribe to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Instrument {
+    Equity { symbol: String },
+    Option(OptionContract),
+    Crypto { base: String, quote: String },
+}
+
+/// A listed equity option
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OptionContract {
+    pub underlying: String,
+    pub expiry: NaiveDate,
+    pub right: OptionRight,
+    pub strike: Price,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionRight {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum InstrumentKind {
+    Equity,
+    Option,
+    Crypto,
+}
+
+/// Interactive Brokers contract fields, kept free of the `ibapi` types so any
+/// crate can build one; `market_data` turns it into an `ibapi` `Contract`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IbContract {
+    pub security_type: String, // STK, OPT or CRYPTO
+    pub symbol: String,
+    pub exchange: String,
+    pub currency: String,
+    pub last_trade_date: String, // Options: YYYYMMDD
+    pub strike: f64,
+    pub right: String,      // Options: C or P
+    pub multiplier: String, // Options: usually 100
+}
+
+/// Trading parameters for one instrument
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstrumentSpec {
+    pub instrument: Instrument,
+    pub tick_size: Price,
+    pub multiplier: Decimal,      // Units of the underlying per contract
+    pub lot_size: Quantity,       // Orders must be a multiple of this
+    pub exchange: Option<String>, // IB routing exchange; SMART (or PAXOS for crypto) when unset
+}
+
+/// Reference data for every known instrument, keyed by canonical symbol
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentMaster {
+    specs: BTreeMap<String, InstrumentSpec>,
+}
+
+#[derive(Debug)]
+pub enum InstrumentError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse(String),
+}
+
+#[derive(Deserialize)]
+struct ReferenceFile {
+    #[serde(default)]
+    instruments: Vec<SpecRecord>,
+}
+
+#[derive(Deserialize)]
+struct SpecRecord {
+    symbol: String,
+    tick_size: Option<Price>,
+    multiplier: Option<Decimal>,
+    lot_size: Option<Quantity>,
+    exchange: Option<String>,
+}
+
+impl Instrument {
+    pub fn equity(symbol: &str) -> Result<Self, InstrumentError> {
+        let symbol = symbol.trim().to_ascii_uppercase();
+        let valid = !symbol.is_empty()
+            && symbol
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+        if !valid {
+            return Err(InstrumentError::Parse(format!(
+                "invalid equity symbol '{}'",
+                symbol
+            )));
+        }
+        Ok(Instrument::Equity { symbol })
+    }
+
+    pub fn crypto(base: &str, quote: &str) -> Result<Self, InstrumentError> {
+        let (base, quote) = (
+            base.trim().to_ascii_uppercase(),
+            quote.trim().to_ascii_uppercase(),
+        );
+        let valid = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+        if !valid(&base) || !valid(&quote) {
+            return Err(InstrumentError::Parse(format!(
+                "invalid crypto pair '{}/{}'",
+                base, quote
+            )));
+        }
+        Ok(Instrument::Crypto { base, quote })
+    }
+
+    pub fn kind(&self) -> InstrumentKind {
+        match self {
+            Instrument::Equity { .. } => InstrumentKind::Equity,
+            Instrument::Option(_) => InstrumentKind::Option,
+            Instrument::Crypto { .. } => InstrumentKind::Crypto,
+        }
+    }
+
+    /// The equity an option is written on, the equity itself, or a pair's base asset
+    pub fn underlying(&self) -> &str {
+        match self {
+            Instrument::Equity { symbol } => symbol,
+            Instrument::Option(option) => &option.underlying,
+            Instrument::Crypto { base, .. } => base,
+        }
+    }
+
+    /// The calendar this instrument trades on
+    pub fn market(&self) -> Market {
+        match self {
+            Instrument::Crypto { .. } => Market::Crypto,
+            Instrument::Equity { .. } | Instrument::Option(_) => Market::Nyse,
+        }
+    }
+
+    /// Symbol as Alpaca's market data and trading APIs expect it
+    pub fn alpaca_symbol(&self) -> String {
+        match self {
+            Instrument::Option(option) => option.compact_occ_symbol(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Parses an Alpaca symbol: `BASE/QUOTE` for crypto, compact OCC for options, otherwise equity
+    pub fn from_alpaca(symbol: &str) -> Result<Self, InstrumentError> {
+        symbol.parse()
+    }
+
+    /// IB contract for this instrument, routed through `exchange` when given
+    pub fn ib_contract(&self, exchange: Option<&str>) -> IbContract {
+        match self {
+            Instrument::Equity { symbol } => IbContract {
+                security_type: "STK".to_string(),
+                // IB separates share classes with a space: BRK.B is "BRK B"
+                symbol: symbol.replace('.', " "),
+                exchange: exchange.unwrap_or("SMART").to_string(),
+                currency: "USD".to_string(),
+                ..Default::default()
+            },
+            Instrument::Option(option) => IbContract {
+                security_type: "OPT".to_string(),
+                symbol: option.underlying.replace('.', " "),
+                exchange: exchange.unwrap_or("SMART").to_string(),
+                currency: "USD".to_string(),
+                last_trade_date: option.expiry.format("%Y%m%d").to_string(),
+                strike: option.strike.to_f64(),
+                right: option.right.as_str().to_string(),
+                multiplier: "100".to_string(),
+            },
+            Instrument::Crypto { base, quote } => IbContract {
+                security_type: "CRYPTO".to_string(),
+                symbol: base.clone(),
+                exchange: exchange.unwrap_or("PAXOS").to_string(),
+                currency: quote.clone(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Maps an IB contract back; options need an expiry date, a strike and a right
+    pub fn from_ib(contract: &IbContract) -> Result<Self, InstrumentError> {
+        match contract.security_type.to_ascii_uppercase().as_str() {
+            "STK" => Instrument::equity(&contract.symbol.replace(' ', ".")),
+            "CRYPTO" => Instrument::crypto(&contract.symbol, &contract.currency),
+            "OPT" => {
+                let expiry = NaiveDate::parse_from_str(&contract.last_trade_date, "%Y%m%d")
+                    .map_err(|_| {
+                        InstrumentError::Parse(format!(
+                            "IB option {} has no expiry date (got '{}')",
+                            contract.symbol, contract.last_trade_date
+                        ))
+                    })?;
+                let right = match contract.right.to_ascii_uppercase().as_str() {
+                    "C" | "CALL" => OptionRight::Call,
+                    "P" | "PUT" => OptionRight::Put,
+                    other => {
+                        return Err(InstrumentError::Parse(format!(
+                            "IB option {} has invalid right '{}'",
+                            contract.symbol, other
+                        )))
+                    }
+                };
+                let strike = Price::from_f64(contract.strike)
+                    .filter(|strike| *strike > Price::ZERO)
+                    .ok_or_else(|| {
+                        InstrumentError::Parse(format!(
+                            "IB option {} has invalid strike {}",
+                            contract.symbol, contract.strike
+                        ))
+                    })?;
+                let underlying = contract.symbol.replace(' ', ".");
+                OptionContract::new(&underlying, expiry, right, strike).map(Instrument::Option)
+            }
+            other => Err(InstrumentError::Parse(format!(
+                "unsupported IB security type '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+impl FromStr for Instrument {
+    type Err = InstrumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((base, quote)) = s.split_once('/') {
+            return Instrument::crypto(base, quote);
+        }
+        if looks_like_occ(s) {
+            return OptionContract::from_occ(s).map(Instrument::Option);
+        }
+        Instrument::equity(s)
+    }
+}
+
+impl fmt::Display for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instrument::Equity { symbol } => f.write_str(symbol),
+            Instrument::Option(option) => f.write_str(&option.occ_symbol()),
+            Instrument::Crypto { base, quote } => write!(f, "{}/{}", base, quote),
+        }
+    }
+}
+
+impl TryFrom<String> for Instrument {
+    type Error = InstrumentError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Instrument> for String {
+    fn from(instrument: Instrument) -> Self {
+        instrument.to_string()
+    }
+}
+
+impl OptionContract {
+    pub fn new(
+        underlying: &str,
+        expiry: NaiveDate,
+        right: OptionRight,
+        strike: Price,
+    ) -> Result<Self, InstrumentError> {
+        let underlying = underlying.trim().to_ascii_uppercase();
+        if underlying.is_empty() || underlying.len() > OCC_ROOT_WIDTH {
+            return Err(InstrumentError::Parse(format!(
+                "option root '{}' must be 1 to {} characters",
+                underlying, OCC_ROOT_WIDTH
+            )));
+        }
+        Ok(Self {
+            underlying,
+            expiry,
+            right,
+            strike,
+        })
+    }
+
+    /// Parses an OCC symbol, padded (`AAPL  250221C00200000`) or compact (`AAPL250221C00200000`)
+    pub fn from_occ(symbol: &str) -> Result<Self, InstrumentError> {
+        let symbol = symbol.trim();
+        let invalid = || InstrumentError::Parse(format!("invalid OCC option symbol '{}'", symbol));
+        if !symbol.is_ascii() || symbol.len() <= OCC_SUFFIX_LEN {
+            return Err(invalid());
+        }
+
+        let (root, suffix) = symbol.split_at(symbol.len() - OCC_SUFFIX_LEN);
+        let expiry = NaiveDate::parse_from_str(&suffix[..6], "%y%m%d").map_err(|_| invalid())?;
+        let right = match &suffix[6..7] {
+            "C" | "c" => OptionRight::Call,
+            "P" | "p" => OptionRight::Put,
+            _ => return Err(invalid()),
+        };
+        let digits = &suffix[7..];
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let thousandths: i64 = digits.parse().map_err(|_| invalid())?;
+        let strike = Price::new(Decimal::new(thousandths, 3).normalize());
+
+        OptionContract::new(root.trim_end(), expiry, right, strike)
+    }
+
+    /// The 21-character OCC symbol with the root padded to six characters
+    pub fn occ_symbol(&self) -> String {
+        format!(
+            "{:<width$}{}",
+            self.underlying,
+            self.occ_suffix(),
+            width = OCC_ROOT_WIDTH
+        )
+    }
+
+    /// OCC symbol without the root padding, as Alpaca writes it
+    pub fn compact_occ_symbol(&self) -> String {
+        format!("{}{}", self.underlying, self.occ_suffix())
+    }
+
+    fn occ_suffix(&self) -> String {
+        let thousandths = (self.strike.as_decimal() * Decimal::from(1000)).trunc();
+        format!(
+            "{}{}{:08}",
+            self.expiry.format("%y%m%d"),
+            self.right.as_str(),
+            thousandths
+        )
+    }
+}
+
+impl OptionRight {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptionRight::Call => "C",
+            OptionRight::Put => "P",
+        }
+    }
+}
+
+impl fmt::Display for OptionRight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl InstrumentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstrumentKind::Equity => "equity",
+            InstrumentKind::Option => "option",
+            InstrumentKind::Crypto => "crypto",
+        }
+    }
+}
+
+impl fmt::Display for InstrumentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl IbContract {
+    /// Every listed option on `underlying`, for a contract details request
+    pub fn option_chain(underlying: &str) -> Self {
+        IbContract {
+            security_type: "OPT".to_string(),
+            symbol: underlying.replace('.', " "),
+            exchange: "SMART".to_string(),
+            currency: "USD".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl InstrumentSpec {
+    /// Defaults for instruments missing from the reference data: penny ticks
+    /// (Reg NMS / penny pilot), 100-share option contracts, whole-share lots
+    /// and Alpaca's minimum crypto order increment
+    pub fn default_for(instrument: Instrument) -> Self {
+        let (multiplier, lot_size) = match instrument.kind() {
+            InstrumentKind::Equity => (Decimal::ONE, Quantity::from(1)),
+            InstrumentKind::Option => (Decimal::from(100), Quantity::from(1)),
+            InstrumentKind::Crypto => (Decimal::ONE, Quantity::new(Decimal::new(1, 9))),
+        };
+        Self {
+            instrument,
+            tick_size: Price::CENT,
+            multiplier,
+            lot_size,
+            exchange: None,
+        }
+    }
+
+    /// `price` on this instrument's tick grid; sub-dollar equities use the finer Reg NMS tick
+    pub fn round_price(&self, price: Price) -> Price {
+        let tick = match self.instrument.kind() {
+            InstrumentKind::Equity => self.tick_size.min(price.us_equity_tick()),
+            InstrumentKind::Option | InstrumentKind::Crypto => self.tick_size,
+        };
+        price.round_to_tick(tick)
+    }
+
+    /// Whether `qty` is a whole number of lots
+    pub fn is_valid_quantity(&self, qty: Quantity) -> bool {
+        let lot = self.lot_size.as_decimal();
+        lot <= Decimal::ZERO || (qty.as_decimal() % lot).is_zero()
+    }
+
+    /// Cash value of `qty` units at `price`, including the contract multiplier
+    pub fn notional(&self, price: Price, qty: Quantity) -> Money {
+        Money::new((price * qty).as_decimal() * self.multiplier)
+    }
+
+    pub fn ib_contract(&self) -> IbContract {
+        let mut contract = self.instrument.ib_contract(self.exchange.as_deref());
+        if self.instrument.kind() == InstrumentKind::Option {
+            contract.multiplier = self.multiplier.normalize().to_string();
+        }
+        contract
+    }
+}
+
+impl InstrumentMaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a TOML file of `[[instruments]]` tables with `symbol` and optional
+    /// `tick_size`, `multiplier`, `lot_size` and `exchange`
+    pub fn from_file(path: &Path) -> Result<Self, InstrumentError> {
+        let text = fs::read_to_string(path).map_err(|source| InstrumentError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let file: ReferenceFile = toml::from_str(&text)
+            .map_err(|e| InstrumentError::Parse(format!("{}: {}", path.display(), e)))?;
+
+        let mut master = Self::new();
+        for record in file.instruments {
+            let instrument: Instrument = record
+                .symbol
+                .parse()
+                .map_err(|e| InstrumentError::Parse(format!("{}: {}", path.display(), e)))?;
+            let mut spec = InstrumentSpec::default_for(instrument);
+            spec.tick_size = record.tick_size.unwrap_or(spec.tick_size);
+            spec.multiplier = record.multiplier.unwrap_or(spec.multiplier);
+            spec.lot_size = record.lot_size.unwrap_or(spec.lot_size);
+            spec.exchange = record.exchange;
+
+            if spec.tick_size <= Price::ZERO
+                || spec.multiplier <= Decimal::ZERO
+                || spec.lot_size <= Quantity::ZERO
+            {
+                return Err(InstrumentError::Parse(format!(
+                    "{}: {}: tick_size, multiplier and lot_size must be positive",
+                    path.display(),
+                    record.symbol
+                )));
+            }
+            master.insert(spec);
+        }
+        Ok(master)
+    }
+
+    pub fn insert(&mut self, spec: InstrumentSpec) {
+        self.specs.insert(spec.instrument.to_string(), spec);
+    }
+
+    /// The listed spec for `instrument`, if any
+    pub fn get(&self, instrument: &Instrument) -> Option<&InstrumentSpec> {
+        self.specs.get(&instrument.to_string())
+    }
+
+    /// The listed spec, or the defaults for its type
+    pub fn spec(&self, instrument: &Instrument) -> InstrumentSpec {
+        self.get(instrument)
+            .cloned()
+            .unwrap_or_else(|| InstrumentSpec::default_for(instrument.clone()))
+    }
+
+    /// Parses `symbol` in any supported form and returns its spec
+    pub fn resolve(&self, symbol: &str) -> Result<InstrumentSpec, InstrumentError> {
+        Ok(self.spec(&symbol.parse()?))
+    }
+
+    /// Listed options on `underlying`, ordered by expiry, right and strike
+    pub fn options_on<'a>(
+        &'a self,
+        underlying: &'a str,
+    ) -> impl Iterator<Item = &'a InstrumentSpec> {
+        self.specs.values().filter(move |spec| {
+            matches!(&spec.instrument, Instrument::Option(option) if option.underlying == underlying)
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &InstrumentSpec> {
+        self.specs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.specs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+}
+
+impl fmt::Display for InstrumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstrumentError::Io { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            InstrumentError::Parse(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for InstrumentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InstrumentError::Io { source, .. } => Some(source),
+            InstrumentError::Parse(_) => None,
+        }
+    }
+}
+
+/// Loads the reference data named by `[instruments] path`, or the first of the
+/// default locations that exists; an empty master if none does
+pub fn load_instruments(config: &Config) -> Result<InstrumentMaster, InstrumentError> {
+    let path = &config.instruments.path;
+    if !path.trim().is_empty() {
+        return InstrumentMaster::from_file(Path::new(path));
+    }
+    match DEFAULT_REFERENCE_PATHS
+        .iter()
+        .map(Path::new)
+        .find(|path| path.is_file())
+    {
+        Some(path) => InstrumentMaster::from_file(path),
+        None => Ok(InstrumentMaster::new()),
+    }
+}
+
+/// Ends in YYMMDD, C/P and eight digits after a non-empty root
+fn looks_like_occ(symbol: &str) -> bool {
+    let bytes = symbol.as_bytes();
+    if !symbol.is_ascii() || bytes.len() <= OCC_SUFFIX_LEN {
+        return false;
+    }
+    let suffix = &bytes[bytes.len() - OCC_SUFFIX_LEN..];
+    suffix[..6].iter().all(u8::is_ascii_digit)
+        && matches!(suffix[6], b'C' | b'P' | b'c' | b'p')
+        && suffix[7..].iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(symbol: &str) -> OptionContract {
+        match symbol.parse::<Instrument>().unwrap() {
+            Instrument::Option(option) => option,
+            other => panic!("{} parsed as {:?}", symbol, other),
+        }
+    }
+
+    #[test]
+    fn parses_padded_and_compact_occ_symbols() {
+        let padded = option("AAPL  250221C00200000");
+        assert_eq!(padded, option("AAPL250221C00200000"));
+        assert_eq!(padded.underlying, "AAPL");
+        assert_eq!(padded.expiry, NaiveDate::from_ymd_opt(2025, 2, 21).unwrap());
+        assert_eq!(padded.right, OptionRight::Call);
+        assert_eq!(padded.strike, "200".parse().unwrap());
+        assert_eq!(padded.occ_symbol(), "AAPL  250221C00200000");
+        assert_eq!(padded.compact_occ_symbol(), "AAPL250221C00200000");
+
+        let fractional = option("SPY250321p00512500");
+        assert_eq!(fractional.right, OptionRight::Put);
+        assert_eq!(fractional.strike, "512.5".parse().unwrap());
+        assert_eq!(fractional.occ_symbol(), "SPY   250321P00512500");
+    }
+
+    #[test]
+    fn rejects_malformed_occ_symbols() {
+        for symbol in [
+            "AAPL251341C00200000",    // No such date
+            "AAPL250221X00200000",    // Neither call nor put
+            "AAPL250221C0020000A",    // Strike is not all digits
+            "250221C00200000",        // No root
+            "ABCDEFG250221C00200000", // Root longer than six characters
+        ] {
+            assert!(
+                OptionContract::from_occ(symbol).is_err(),
+                "{} parsed",
+                symbol
+            );
+        }
+    }
+
+    #[test]
+    fn parses_every_symbol_form() {
+        assert_eq!(
+            "brk.b".parse::<Instrument>().unwrap(),
+            Instrument::Equity {
+                symbol: "BRK.B".into()
+            }
+        );
+        let btc = "BTC/USD".parse::<Instrument>().unwrap();
+        assert_eq!(btc.kind(), InstrumentKind::Crypto);
+        assert_eq!(btc.underlying(), "BTC");
+        assert_eq!(btc.market(), Market::Crypto);
+        assert!("AA PL".parse::<Instrument>().is_err());
+
+        let call = Instrument::Option(option("AAPL250221C00200000"));
+        assert_eq!(call.underlying(), "AAPL");
+        assert_eq!(call.to_string(), "AAPL  250221C00200000");
+        assert_eq!(call.alpaca_symbol(), "AAPL250221C00200000");
+        assert_eq!(
+            serde_json::to_string(&call).unwrap(),
+            "\"AAPL  250221C00200000\""
+        );
+    }
+
+    #[test]
+    fn round_trips_through_ib_contracts() {
+        for symbol in ["BRK.B", "BTC/USD", "BRK.B250221C00350000"] {
+            let instrument: Instrument = symbol.parse().unwrap();
+            let contract = instrument.ib_contract(None);
+            assert_eq!(Instrument::from_ib(&contract).unwrap(), instrument);
+        }
+        assert_eq!(
+            Instrument::equity("BRK.B")
+                .unwrap()
+                .ib_contract(None)
+                .symbol,
+            "BRK B"
+        );
+    }
+
+    #[test]
+    fn specs_apply_ticks_lots_and_multipliers() {
+        let equity = InstrumentSpec::default_for(Instrument::equity("AAPL").unwrap());
+        assert_eq!(
+            equity.round_price("187.315".parse().unwrap()),
+            "187.32".parse().unwrap()
+        );
+        assert_eq!(
+            equity.round_price("0.12345".parse().unwrap()),
+            "0.1235".parse().unwrap()
+        );
+        assert!(equity.is_valid_quantity(Quantity::from(3)));
+        assert!(!equity.is_valid_quantity("1.5".parse().unwrap()));
+
+        let call = InstrumentSpec::default_for(Instrument::Option(option("AAPL250221C00200000")));
+        let notional = call.notional("2.35".parse().unwrap(), Quantity::from(2));
+        assert_eq!(notional, "470".parse().unwrap());
+
+        let mut master = InstrumentMaster::new();
+        master.insert(InstrumentSpec {
+            lot_size: Quantity::from(10),
+            ..equity.clone()
+        });
+        assert_eq!(master.resolve("aapl").unwrap().lot_size, Quantity::from(10));
+        assert_eq!(master.resolve("MSFT").unwrap().lot_size, Quantity::from(1));
+    }
+}
@@ -18,299 +18,1723 @@ pub mod market_data {
   extern crate flatbuffers;
   use self::flatbuffers::{EndianScalar, Follow};
 
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MIN_OPTION_RIGHT: i8 = 0;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MAX_OPTION_RIGHT: i8 = 1;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+#[allow(non_camel_case_types)]
+pub const ENUM_VALUES_OPTION_RIGHT: [OptionRight; 2] = [
+  OptionRight::Call,
+  OptionRight::Put,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct OptionRight(pub i8);
+#[allow(non_upper_case_globals)]
+impl OptionRight {
+  pub const Call: Self = Self(0);
+  pub const Put: Self = Self(1);
+
+  pub const ENUM_MIN: i8 = 0;
+  pub const ENUM_MAX: i8 = 1;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::Call,
+    Self::Put,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::Call => Some("Call"),
+      Self::Put => Some("Put"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for OptionRight {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for OptionRight {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = flatbuffers::read_scalar_at::<i8>(buf, loc);
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for OptionRight {
+    type Output = OptionRight;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        flatbuffers::emplace_scalar::<i8>(dst, self.0);
+    }
+}
+
+impl flatbuffers::EndianScalar for OptionRight {
+  type Scalar = i8;
+  #[inline]
+  fn to_little_endian(self) -> i8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: i8) -> Self {
+    let b = i8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for OptionRight {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    i8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for OptionRight {}
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MIN_TRADING_STATUS: i8 = 0;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MAX_TRADING_STATUS: i8 = 3;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+#[allow(non_camel_case_types)]
+pub const ENUM_VALUES_TRADING_STATUS: [TradingStatus; 4] = [
+  TradingStatus::Trading,
+  TradingStatus::Halted,
+  TradingStatus::Paused,
+  TradingStatus::QuotationOnly,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct TradingStatus(pub i8);
+#[allow(non_upper_case_globals)]
+impl TradingStatus {
+  pub const Trading: Self = Self(0);
+  pub const Halted: Self = Self(1);
+  pub const Paused: Self = Self(2);
+  pub const QuotationOnly: Self = Self(3);
+
+  pub const ENUM_MIN: i8 = 0;
+  pub const ENUM_MAX: i8 = 3;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::Trading,
+    Self::Halted,
+    Self::Paused,
+    Self::QuotationOnly,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::Trading => Some("Trading"),
+      Self::Halted => Some("Halted"),
+      Self::Paused => Some("Paused"),
+      Self::QuotationOnly => Some("QuotationOnly"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for TradingStatus {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for TradingStatus {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = flatbuffers::read_scalar_at::<i8>(buf, loc);
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for TradingStatus {
+    type Output = TradingStatus;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        flatbuffers::emplace_scalar::<i8>(dst, self.0);
+    }
+}
+
+impl flatbuffers::EndianScalar for TradingStatus {
+  type Scalar = i8;
+  #[inline]
+  fn to_little_endian(self) -> i8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: i8) -> Self {
+    let b = i8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for TradingStatus {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    i8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for TradingStatus {}
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MIN_PAYLOAD: u8 = 0;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MAX_PAYLOAD: u8 = 7;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+#[allow(non_camel_case_types)]
+pub const ENUM_VALUES_PAYLOAD: [Payload; 8] = [
+  Payload::NONE,
+  Payload::Quote,
+  Payload::Trade,
+  Payload::Bar,
+  Payload::OrderBookUpdate,
+  Payload::OptionQuote,
+  Payload::Gap,
+  Payload::Status,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct Payload(pub u8);
+#[allow(non_upper_case_globals)]
+impl Payload {
+  pub const NONE: Self = Self(0);
+  pub const Quote: Self = Self(1);
+  pub const Trade: Self = Self(2);
+  pub const Bar: Self = Self(3);
+  pub const OrderBookUpdate: Self = Self(4);
+  pub const OptionQuote: Self = Self(5);
+  pub const Gap: Self = Self(6);
+  pub const Status: Self = Self(7);
+
+  pub const ENUM_MIN: u8 = 0;
+  pub const ENUM_MAX: u8 = 7;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::NONE,
+    Self::Quote,
+    Self::Trade,
+    Self::Bar,
+    Self::OrderBookUpdate,
+    Self::OptionQuote,
+    Self::Gap,
+    Self::Status,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::NONE => Some("NONE"),
+      Self::Quote => Some("Quote"),
+      Self::Trade => Some("Trade"),
+      Self::Bar => Some("Bar"),
+      Self::OrderBookUpdate => Some("OrderBookUpdate"),
+      Self::OptionQuote => Some("OptionQuote"),
+      Self::Gap => Some("Gap"),
+      Self::Status => Some("Status"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for Payload {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for Payload {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = flatbuffers::read_scalar_at::<u8>(buf, loc);
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for Payload {
+    type Output = Payload;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        flatbuffers::emplace_scalar::<u8>(dst, self.0);
+    }
+}
+
+impl flatbuffers::EndianScalar for Payload {
+  type Scalar = u8;
+  #[inline]
+  fn to_little_endian(self) -> u8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: u8) -> Self {
+    let b = u8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for Payload {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    u8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Payload {}
+pub struct PayloadUnionTableOffset {}
+
+// struct PriceLevel, aligned to 8
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct PriceLevel(pub [u8; 16]);
+impl Default for PriceLevel { 
+  fn default() -> Self { 
+    Self([0; 16])
+  }
+}
+impl core::fmt::Debug for PriceLevel {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_struct("PriceLevel")
+      .field("price", &self.price())
+      .field("quantity", &self.quantity())
+      .finish()
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for PriceLevel {}
+impl<'a> flatbuffers::Follow<'a> for PriceLevel {
+  type Inner = &'a PriceLevel;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    <&'a PriceLevel>::follow(buf, loc)
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for &'a PriceLevel {
+  type Inner = &'a PriceLevel;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    flatbuffers::follow_cast_ref::<PriceLevel>(buf, loc)
+  }
+}
+impl<'b> flatbuffers::Push for PriceLevel {
+    type Output = PriceLevel;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        let src = ::core::slice::from_raw_parts(self as *const PriceLevel as *const u8, Self::size());
+        dst.copy_from_slice(src);
+    }
+}
+
+impl<'a> flatbuffers::Verifiable for PriceLevel {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.in_buffer::<Self>(pos)
+  }
+}
+
+impl<'a> PriceLevel {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    price: f64,
+    quantity: f64,
+  ) -> Self {
+    let mut s = Self([0; 16]);
+    s.set_price(price);
+    s.set_quantity(quantity);
+    s
+  }
+
+  pub fn price(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[0..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_price(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[0..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn quantity(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[8..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_quantity(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[8..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+}
+
+// struct Greeks, aligned to 8
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Greeks(pub [u8; 40]);
+impl Default for Greeks { 
+  fn default() -> Self { 
+    Self([0; 40])
+  }
+}
+impl core::fmt::Debug for Greeks {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    f.debug_struct("Greeks")
+      .field("delta", &self.delta())
+      .field("gamma", &self.gamma())
+      .field("theta", &self.theta())
+      .field("vega", &self.vega())
+      .field("rho", &self.rho())
+      .finish()
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Greeks {}
+impl<'a> flatbuffers::Follow<'a> for Greeks {
+  type Inner = &'a Greeks;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    <&'a Greeks>::follow(buf, loc)
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for &'a Greeks {
+  type Inner = &'a Greeks;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    flatbuffers::follow_cast_ref::<Greeks>(buf, loc)
+  }
+}
+impl<'b> flatbuffers::Push for Greeks {
+    type Output = Greeks;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        let src = ::core::slice::from_raw_parts(self as *const Greeks as *const u8, Self::size());
+        dst.copy_from_slice(src);
+    }
+}
+
+impl<'a> flatbuffers::Verifiable for Greeks {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.in_buffer::<Self>(pos)
+  }
+}
+
+impl<'a> Greeks {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    delta: f64,
+    gamma: f64,
+    theta: f64,
+    vega: f64,
+    rho: f64,
+  ) -> Self {
+    let mut s = Self([0; 40]);
+    s.set_delta(delta);
+    s.set_gamma(gamma);
+    s.set_theta(theta);
+    s.set_vega(vega);
+    s.set_rho(rho);
+    s
+  }
+
+  pub fn delta(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[0..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_delta(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[0..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn gamma(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[8..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_gamma(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[8..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn theta(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[16..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_theta(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[16..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn vega(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[24..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_vega(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[24..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+  pub fn rho(&self) -> f64 {
+    let mut mem = core::mem::MaybeUninit::<<f64 as EndianScalar>::Scalar>::uninit();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    EndianScalar::from_little_endian(unsafe {
+      core::ptr::copy_nonoverlapping(
+        self.0[32..].as_ptr(),
+        mem.as_mut_ptr() as *mut u8,
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+      mem.assume_init()
+    })
+  }
+
+  pub fn set_rho(&mut self, x: f64) {
+    let x_le = x.to_little_endian();
+    // Safety:
+    // Created from a valid Table for this object
+    // Which contains a valid value in this slot
+    unsafe {
+      core::ptr::copy_nonoverlapping(
+        &x_le as *const _ as *const u8,
+        self.0[32..].as_mut_ptr(),
+        core::mem::size_of::<<f64 as EndianScalar>::Scalar>(),
+      );
+    }
+  }
+
+}
+
 pub enum QuoteOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct Quote<'a> {
+pub struct Quote<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Quote<'a> {
+  type Inner = Quote<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Quote<'a> {
+  pub const VT_BID_PRICE: flatbuffers::VOffsetT = 4;
+  pub const VT_ASK_PRICE: flatbuffers::VOffsetT = 6;
+  pub const VT_BID_SIZE: flatbuffers::VOffsetT = 8;
+  pub const VT_ASK_SIZE: flatbuffers::VOffsetT = 10;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Quote { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args QuoteArgs
+  ) -> flatbuffers::WIPOffset<Quote<'bldr>> {
+    let mut builder = QuoteBuilder::new(_fbb);
+    builder.add_ask_size(args.ask_size);
+    builder.add_bid_size(args.bid_size);
+    builder.add_ask_price(args.ask_price);
+    builder.add_bid_price(args.bid_price);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn bid_price(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Quote::VT_BID_PRICE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn ask_price(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Quote::VT_ASK_PRICE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn bid_size(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Quote::VT_BID_SIZE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn ask_size(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Quote::VT_ASK_SIZE, Some(0.0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Quote<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<f64>("bid_price", Self::VT_BID_PRICE, false)?
+     .visit_field::<f64>("ask_price", Self::VT_ASK_PRICE, false)?
+     .visit_field::<f64>("bid_size", Self::VT_BID_SIZE, false)?
+     .visit_field::<f64>("ask_size", Self::VT_ASK_SIZE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct QuoteArgs {
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub bid_size: f64,
+    pub ask_size: f64,
+}
+impl<'a> Default for QuoteArgs {
+  #[inline]
+  fn default() -> Self {
+    QuoteArgs {
+      bid_price: 0.0,
+      ask_price: 0.0,
+      bid_size: 0.0,
+      ask_size: 0.0,
+    }
+  }
+}
+
+pub struct QuoteBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> QuoteBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_bid_price(&mut self, bid_price: f64) {
+    self.fbb_.push_slot::<f64>(Quote::VT_BID_PRICE, bid_price, 0.0);
+  }
+  #[inline]
+  pub fn add_ask_price(&mut self, ask_price: f64) {
+    self.fbb_.push_slot::<f64>(Quote::VT_ASK_PRICE, ask_price, 0.0);
+  }
+  #[inline]
+  pub fn add_bid_size(&mut self, bid_size: f64) {
+    self.fbb_.push_slot::<f64>(Quote::VT_BID_SIZE, bid_size, 0.0);
+  }
+  #[inline]
+  pub fn add_ask_size(&mut self, ask_size: f64) {
+    self.fbb_.push_slot::<f64>(Quote::VT_ASK_SIZE, ask_size, 0.0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> QuoteBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    QuoteBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Quote<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Quote<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Quote");
+      ds.field("bid_price", &self.bid_price());
+      ds.field("ask_price", &self.ask_price());
+      ds.field("bid_size", &self.bid_size());
+      ds.field("ask_size", &self.ask_size());
+      ds.finish()
+  }
+}
+pub enum TradeOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Trade<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Trade<'a> {
+  type Inner = Trade<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Trade<'a> {
+  pub const VT_PRICE: flatbuffers::VOffsetT = 4;
+  pub const VT_QUANTITY: flatbuffers::VOffsetT = 6;
+  pub const VT_TRADE_ID: flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Trade { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args TradeArgs
+  ) -> flatbuffers::WIPOffset<Trade<'bldr>> {
+    let mut builder = TradeBuilder::new(_fbb);
+    builder.add_trade_id(args.trade_id);
+    builder.add_quantity(args.quantity);
+    builder.add_price(args.price);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn price(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Trade::VT_PRICE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn quantity(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Trade::VT_QUANTITY, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn trade_id(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(Trade::VT_TRADE_ID, Some(0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Trade<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<f64>("price", Self::VT_PRICE, false)?
+     .visit_field::<f64>("quantity", Self::VT_QUANTITY, false)?
+     .visit_field::<u64>("trade_id", Self::VT_TRADE_ID, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct TradeArgs {
+    pub price: f64,
+    pub quantity: f64,
+    pub trade_id: u64,
+}
+impl<'a> Default for TradeArgs {
+  #[inline]
+  fn default() -> Self {
+    TradeArgs {
+      price: 0.0,
+      quantity: 0.0,
+      trade_id: 0,
+    }
+  }
+}
+
+pub struct TradeBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> TradeBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_price(&mut self, price: f64) {
+    self.fbb_.push_slot::<f64>(Trade::VT_PRICE, price, 0.0);
+  }
+  #[inline]
+  pub fn add_quantity(&mut self, quantity: f64) {
+    self.fbb_.push_slot::<f64>(Trade::VT_QUANTITY, quantity, 0.0);
+  }
+  #[inline]
+  pub fn add_trade_id(&mut self, trade_id: u64) {
+    self.fbb_.push_slot::<u64>(Trade::VT_TRADE_ID, trade_id, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> TradeBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    TradeBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Trade<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Trade<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Trade");
+      ds.field("price", &self.price());
+      ds.field("quantity", &self.quantity());
+      ds.field("trade_id", &self.trade_id());
+      ds.finish()
+  }
+}
+pub enum BarOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Bar<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Bar<'a> {
+  type Inner = Bar<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Bar<'a> {
+  pub const VT_OPEN: flatbuffers::VOffsetT = 4;
+  pub const VT_HIGH: flatbuffers::VOffsetT = 6;
+  pub const VT_LOW: flatbuffers::VOffsetT = 8;
+  pub const VT_CLOSE: flatbuffers::VOffsetT = 10;
+  pub const VT_VOLUME: flatbuffers::VOffsetT = 12;
+  pub const VT_VWAP: flatbuffers::VOffsetT = 14;
+  pub const VT_TRADE_COUNT: flatbuffers::VOffsetT = 16;
+  pub const VT_INTERVAL_NS: flatbuffers::VOffsetT = 18;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Bar { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args BarArgs
+  ) -> flatbuffers::WIPOffset<Bar<'bldr>> {
+    let mut builder = BarBuilder::new(_fbb);
+    builder.add_interval_ns(args.interval_ns);
+    builder.add_trade_count(args.trade_count);
+    builder.add_vwap(args.vwap);
+    builder.add_volume(args.volume);
+    builder.add_close(args.close);
+    builder.add_low(args.low);
+    builder.add_high(args.high);
+    builder.add_open(args.open);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn open(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Bar::VT_OPEN, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn high(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Bar::VT_HIGH, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn low(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Bar::VT_LOW, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn close(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Bar::VT_CLOSE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn volume(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Bar::VT_VOLUME, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn vwap(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(Bar::VT_VWAP, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn trade_count(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(Bar::VT_TRADE_COUNT, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn interval_ns(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(Bar::VT_INTERVAL_NS, Some(0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Bar<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<f64>("open", Self::VT_OPEN, false)?
+     .visit_field::<f64>("high", Self::VT_HIGH, false)?
+     .visit_field::<f64>("low", Self::VT_LOW, false)?
+     .visit_field::<f64>("close", Self::VT_CLOSE, false)?
+     .visit_field::<f64>("volume", Self::VT_VOLUME, false)?
+     .visit_field::<f64>("vwap", Self::VT_VWAP, false)?
+     .visit_field::<u64>("trade_count", Self::VT_TRADE_COUNT, false)?
+     .visit_field::<u64>("interval_ns", Self::VT_INTERVAL_NS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct BarArgs {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub vwap: f64,
+    pub trade_count: u64,
+    pub interval_ns: u64,
+}
+impl<'a> Default for BarArgs {
+  #[inline]
+  fn default() -> Self {
+    BarArgs {
+      open: 0.0,
+      high: 0.0,
+      low: 0.0,
+      close: 0.0,
+      volume: 0.0,
+      vwap: 0.0,
+      trade_count: 0,
+      interval_ns: 0,
+    }
+  }
+}
+
+pub struct BarBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> BarBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_open(&mut self, open: f64) {
+    self.fbb_.push_slot::<f64>(Bar::VT_OPEN, open, 0.0);
+  }
+  #[inline]
+  pub fn add_high(&mut self, high: f64) {
+    self.fbb_.push_slot::<f64>(Bar::VT_HIGH, high, 0.0);
+  }
+  #[inline]
+  pub fn add_low(&mut self, low: f64) {
+    self.fbb_.push_slot::<f64>(Bar::VT_LOW, low, 0.0);
+  }
+  #[inline]
+  pub fn add_close(&mut self, close: f64) {
+    self.fbb_.push_slot::<f64>(Bar::VT_CLOSE, close, 0.0);
+  }
+  #[inline]
+  pub fn add_volume(&mut self, volume: f64) {
+    self.fbb_.push_slot::<f64>(Bar::VT_VOLUME, volume, 0.0);
+  }
+  #[inline]
+  pub fn add_vwap(&mut self, vwap: f64) {
+    self.fbb_.push_slot::<f64>(Bar::VT_VWAP, vwap, 0.0);
+  }
+  #[inline]
+  pub fn add_trade_count(&mut self, trade_count: u64) {
+    self.fbb_.push_slot::<u64>(Bar::VT_TRADE_COUNT, trade_count, 0);
+  }
+  #[inline]
+  pub fn add_interval_ns(&mut self, interval_ns: u64) {
+    self.fbb_.push_slot::<u64>(Bar::VT_INTERVAL_NS, interval_ns, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> BarBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    BarBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Bar<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Bar<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Bar");
+      ds.field("open", &self.open());
+      ds.field("high", &self.high());
+      ds.field("low", &self.low());
+      ds.field("close", &self.close());
+      ds.field("volume", &self.volume());
+      ds.field("vwap", &self.vwap());
+      ds.field("trade_count", &self.trade_count());
+      ds.field("interval_ns", &self.interval_ns());
+      ds.finish()
+  }
+}
+pub enum OrderBookUpdateOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct OrderBookUpdate<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for OrderBookUpdate<'a> {
+  type Inner = OrderBookUpdate<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> OrderBookUpdate<'a> {
+  pub const VT_SEQUENCE: flatbuffers::VOffsetT = 4;
+  pub const VT_IS_SNAPSHOT: flatbuffers::VOffsetT = 6;
+  pub const VT_BIDS: flatbuffers::VOffsetT = 8;
+  pub const VT_ASKS: flatbuffers::VOffsetT = 10;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    OrderBookUpdate { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args OrderBookUpdateArgs<'args>
+  ) -> flatbuffers::WIPOffset<OrderBookUpdate<'bldr>> {
+    let mut builder = OrderBookUpdateBuilder::new(_fbb);
+    builder.add_sequence(args.sequence);
+    if let Some(x) = args.asks { builder.add_asks(x); }
+    if let Some(x) = args.bids { builder.add_bids(x); }
+    builder.add_is_snapshot(args.is_snapshot);
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn sequence(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(OrderBookUpdate::VT_SEQUENCE, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn is_snapshot(&self) -> bool {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<bool>(OrderBookUpdate::VT_IS_SNAPSHOT, Some(false)).unwrap()}
+  }
+  #[inline]
+  pub fn bids(&self) -> Option<flatbuffers::Vector<'a, PriceLevel>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, PriceLevel>>>(OrderBookUpdate::VT_BIDS, None)}
+  }
+  #[inline]
+  pub fn asks(&self) -> Option<flatbuffers::Vector<'a, PriceLevel>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, PriceLevel>>>(OrderBookUpdate::VT_ASKS, None)}
+  }
+}
+
+impl flatbuffers::Verifiable for OrderBookUpdate<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u64>("sequence", Self::VT_SEQUENCE, false)?
+     .visit_field::<bool>("is_snapshot", Self::VT_IS_SNAPSHOT, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, PriceLevel>>>("bids", Self::VT_BIDS, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, PriceLevel>>>("asks", Self::VT_ASKS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct OrderBookUpdateArgs<'a> {
+    pub sequence: u64,
+    pub is_snapshot: bool,
+    pub bids: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, PriceLevel>>>,
+    pub asks: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, PriceLevel>>>,
+}
+impl<'a> Default for OrderBookUpdateArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    OrderBookUpdateArgs {
+      sequence: 0,
+      is_snapshot: false,
+      bids: None,
+      asks: None,
+    }
+  }
+}
+
+pub struct OrderBookUpdateBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> OrderBookUpdateBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_sequence(&mut self, sequence: u64) {
+    self.fbb_.push_slot::<u64>(OrderBookUpdate::VT_SEQUENCE, sequence, 0);
+  }
+  #[inline]
+  pub fn add_is_snapshot(&mut self, is_snapshot: bool) {
+    self.fbb_.push_slot::<bool>(OrderBookUpdate::VT_IS_SNAPSHOT, is_snapshot, false);
+  }
+  #[inline]
+  pub fn add_bids(&mut self, bids: flatbuffers::WIPOffset<flatbuffers::Vector<'b , PriceLevel>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(OrderBookUpdate::VT_BIDS, bids);
+  }
+  #[inline]
+  pub fn add_asks(&mut self, asks: flatbuffers::WIPOffset<flatbuffers::Vector<'b , PriceLevel>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(OrderBookUpdate::VT_ASKS, asks);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> OrderBookUpdateBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    OrderBookUpdateBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<OrderBookUpdate<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for OrderBookUpdate<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("OrderBookUpdate");
+      ds.field("sequence", &self.sequence());
+      ds.field("is_snapshot", &self.is_snapshot());
+      ds.field("bids", &self.bids());
+      ds.field("asks", &self.asks());
+      ds.finish()
+  }
+}
+pub enum OptionQuoteOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct OptionQuote<'a> {
   pub _tab: flatbuffers::Table<'a>,
 }
 
-impl<'a> flatbuffers::Follow<'a> for Quote<'a> {
-  type Inner = Quote<'a>;
+impl<'a> flatbuffers::Follow<'a> for OptionQuote<'a> {
+  type Inner = OptionQuote<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: flatbuffers::Table::new(buf, loc) }
   }
 }
 
-impl<'a> Quote<'a> {
-  pub const VT_SYMBOL: flatbuffers::VOffsetT = 4;
-  pub const VT_BID_PRICE: flatbuffers::VOffsetT = 6;
-  pub const VT_ASK_PRICE: flatbuffers::VOffsetT = 8;
-  pub const VT_TIMESTAMP: flatbuffers::VOffsetT = 10;
+impl<'a> OptionQuote<'a> {
+  pub const VT_UNDERLYING: flatbuffers::VOffsetT = 4;
+  pub const VT_STRIKE: flatbuffers::VOffsetT = 6;
+  pub const VT_EXPIRY: flatbuffers::VOffsetT = 8;
+  pub const VT_RIGHT: flatbuffers::VOffsetT = 10;
+  pub const VT_BID_PRICE: flatbuffers::VOffsetT = 12;
+  pub const VT_ASK_PRICE: flatbuffers::VOffsetT = 14;
+  pub const VT_LAST_PRICE: flatbuffers::VOffsetT = 16;
+  pub const VT_IMPLIED_VOLATILITY: flatbuffers::VOffsetT = 18;
+  pub const VT_GREEKS: flatbuffers::VOffsetT = 20;
 
   #[inline]
   pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
-    Quote { _tab: table }
+    OptionQuote { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
-    args: &'args QuoteArgs<'args>
-  ) -> flatbuffers::WIPOffset<Quote<'bldr>> {
-    let mut builder = QuoteBuilder::new(_fbb);
-    builder.add_timestamp(args.timestamp);
+    args: &'args OptionQuoteArgs<'args>
+  ) -> flatbuffers::WIPOffset<OptionQuote<'bldr>> {
+    let mut builder = OptionQuoteBuilder::new(_fbb);
+    builder.add_implied_volatility(args.implied_volatility);
+    builder.add_last_price(args.last_price);
     builder.add_ask_price(args.ask_price);
     builder.add_bid_price(args.bid_price);
-    if let Some(x) = args.symbol { builder.add_symbol(x); }
+    builder.add_strike(args.strike);
+    if let Some(x) = args.greeks { builder.add_greeks(x); }
+    builder.add_expiry(args.expiry);
+    if let Some(x) = args.underlying { builder.add_underlying(x); }
+    builder.add_right(args.right);
     builder.finish()
   }
 
 
   #[inline]
-  pub fn symbol(&self) -> Option<&'a str> {
+  pub fn underlying(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(OptionQuote::VT_UNDERLYING, None)}
+  }
+  #[inline]
+  pub fn strike(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(OptionQuote::VT_STRIKE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn expiry(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(OptionQuote::VT_EXPIRY, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn right(&self) -> OptionRight {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(Quote::VT_SYMBOL, None)}
+    unsafe { self._tab.get::<OptionRight>(OptionQuote::VT_RIGHT, Some(OptionRight::Call)).unwrap()}
   }
   #[inline]
   pub fn bid_price(&self) -> f64 {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<f64>(Quote::VT_BID_PRICE, Some(0.0)).unwrap()}
+    unsafe { self._tab.get::<f64>(OptionQuote::VT_BID_PRICE, Some(0.0)).unwrap()}
   }
   #[inline]
   pub fn ask_price(&self) -> f64 {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<f64>(Quote::VT_ASK_PRICE, Some(0.0)).unwrap()}
+    unsafe { self._tab.get::<f64>(OptionQuote::VT_ASK_PRICE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn last_price(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(OptionQuote::VT_LAST_PRICE, Some(0.0)).unwrap()}
+  }
+  #[inline]
+  pub fn implied_volatility(&self) -> f64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<f64>(OptionQuote::VT_IMPLIED_VOLATILITY, Some(0.0)).unwrap()}
   }
   #[inline]
-  pub fn timestamp(&self) -> u64 {
+  pub fn greeks(&self) -> Option<&'a Greeks> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<u64>(Quote::VT_TIMESTAMP, Some(0)).unwrap()}
+    unsafe { self._tab.get::<Greeks>(OptionQuote::VT_GREEKS, None)}
   }
 }
 
-impl flatbuffers::Verifiable for Quote<'_> {
+impl flatbuffers::Verifiable for OptionQuote<'_> {
   #[inline]
   fn run_verifier(
     v: &mut flatbuffers::Verifier, pos: usize
   ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
     use self::flatbuffers::Verifiable;
     v.visit_table(pos)?
-     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("symbol", Self::VT_SYMBOL, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("underlying", Self::VT_UNDERLYING, false)?
+     .visit_field::<f64>("strike", Self::VT_STRIKE, false)?
+     .visit_field::<u32>("expiry", Self::VT_EXPIRY, false)?
+     .visit_field::<OptionRight>("right", Self::VT_RIGHT, false)?
      .visit_field::<f64>("bid_price", Self::VT_BID_PRICE, false)?
      .visit_field::<f64>("ask_price", Self::VT_ASK_PRICE, false)?
-     .visit_field::<u64>("timestamp", Self::VT_TIMESTAMP, false)?
+     .visit_field::<f64>("last_price", Self::VT_LAST_PRICE, false)?
+     .visit_field::<f64>("implied_volatility", Self::VT_IMPLIED_VOLATILITY, false)?
+     .visit_field::<Greeks>("greeks", Self::VT_GREEKS, false)?
      .finish();
     Ok(())
   }
 }
-pub struct QuoteArgs<'a> {
-    pub symbol: Option<flatbuffers::WIPOffset<&'a str>>,
+pub struct OptionQuoteArgs<'a> {
+    pub underlying: Option<flatbuffers::WIPOffset<&'a str>>,
+    pub strike: f64,
+    pub expiry: u32,
+    pub right: OptionRight,
     pub bid_price: f64,
     pub ask_price: f64,
-    pub timestamp: u64,
+    pub last_price: f64,
+    pub implied_volatility: f64,
+    pub greeks: Option<&'a Greeks>,
 }
-impl<'a> Default for QuoteArgs<'a> {
+impl<'a> Default for OptionQuoteArgs<'a> {
   #[inline]
   fn default() -> Self {
-    QuoteArgs {
-      symbol: None,
+    OptionQuoteArgs {
+      underlying: None,
+      strike: 0.0,
+      expiry: 0,
+      right: OptionRight::Call,
       bid_price: 0.0,
       ask_price: 0.0,
-      timestamp: 0,
+      last_price: 0.0,
+      implied_volatility: 0.0,
+      greeks: None,
     }
   }
 }
 
-pub struct QuoteBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+pub struct OptionQuoteBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
   fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
   start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
 }
-impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> QuoteBuilder<'a, 'b, A> {
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> OptionQuoteBuilder<'a, 'b, A> {
   #[inline]
-  pub fn add_symbol(&mut self, symbol: flatbuffers::WIPOffset<&'b  str>) {
-    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Quote::VT_SYMBOL, symbol);
+  pub fn add_underlying(&mut self, underlying: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(OptionQuote::VT_UNDERLYING, underlying);
+  }
+  #[inline]
+  pub fn add_strike(&mut self, strike: f64) {
+    self.fbb_.push_slot::<f64>(OptionQuote::VT_STRIKE, strike, 0.0);
+  }
+  #[inline]
+  pub fn add_expiry(&mut self, expiry: u32) {
+    self.fbb_.push_slot::<u32>(OptionQuote::VT_EXPIRY, expiry, 0);
+  }
+  #[inline]
+  pub fn add_right(&mut self, right: OptionRight) {
+    self.fbb_.push_slot::<OptionRight>(OptionQuote::VT_RIGHT, right, OptionRight::Call);
   }
   #[inline]
   pub fn add_bid_price(&mut self, bid_price: f64) {
-    self.fbb_.push_slot::<f64>(Quote::VT_BID_PRICE, bid_price, 0.0);
+    self.fbb_.push_slot::<f64>(OptionQuote::VT_BID_PRICE, bid_price, 0.0);
   }
   #[inline]
   pub fn add_ask_price(&mut self, ask_price: f64) {
-    self.fbb_.push_slot::<f64>(Quote::VT_ASK_PRICE, ask_price, 0.0);
+    self.fbb_.push_slot::<f64>(OptionQuote::VT_ASK_PRICE, ask_price, 0.0);
   }
   #[inline]
-  pub fn add_timestamp(&mut self, timestamp: u64) {
-    self.fbb_.push_slot::<u64>(Quote::VT_TIMESTAMP, timestamp, 0);
+  pub fn add_last_price(&mut self, last_price: f64) {
+    self.fbb_.push_slot::<f64>(OptionQuote::VT_LAST_PRICE, last_price, 0.0);
   }
   #[inline]
-  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> QuoteBuilder<'a, 'b, A> {
+  pub fn add_implied_volatility(&mut self, implied_volatility: f64) {
+    self.fbb_.push_slot::<f64>(OptionQuote::VT_IMPLIED_VOLATILITY, implied_volatility, 0.0);
+  }
+  #[inline]
+  pub fn add_greeks(&mut self, greeks: &Greeks) {
+    self.fbb_.push_slot_always::<&Greeks>(OptionQuote::VT_GREEKS, greeks);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> OptionQuoteBuilder<'a, 'b, A> {
     let start = _fbb.start_table();
-    QuoteBuilder {
+    OptionQuoteBuilder {
       fbb_: _fbb,
       start_: start,
     }
   }
   #[inline]
-  pub fn finish(self) -> flatbuffers::WIPOffset<Quote<'a>> {
+  pub fn finish(self) -> flatbuffers::WIPOffset<OptionQuote<'a>> {
     let o = self.fbb_.end_table(self.start_);
     flatbuffers::WIPOffset::new(o.value())
   }
 }
 
-impl core::fmt::Debug for Quote<'_> {
+impl core::fmt::Debug for OptionQuote<'_> {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-    let mut ds = f.debug_struct("Quote");
-      ds.field("symbol", &self.symbol());
+    let mut ds = f.debug_struct("OptionQuote");
+      ds.field("underlying", &self.underlying());
+      ds.field("strike", &self.strike());
+      ds.field("expiry", &self.expiry());
+      ds.field("right", &self.right());
       ds.field("bid_price", &self.bid_price());
       ds.field("ask_price", &self.ask_price());
-      ds.field("timestamp", &self.timestamp());
+      ds.field("last_price", &self.last_price());
+      ds.field("implied_volatility", &self.implied_volatility());
+      ds.field("greeks", &self.greeks());
       ds.finish()
   }
 }
-pub enum TradeOffset {}
+pub enum GapOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
-pub struct Trade<'a> {
+pub struct Gap<'a> {
   pub _tab: flatbuffers::Table<'a>,
 }
 
-impl<'a> flatbuffers::Follow<'a> for Trade<'a> {
-  type Inner = Trade<'a>;
+impl<'a> flatbuffers::Follow<'a> for Gap<'a> {
+  type Inner = Gap<'a>;
   #[inline]
   unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
     Self { _tab: flatbuffers::Table::new(buf, loc) }
   }
 }
 
-impl<'a> Trade<'a> {
-  pub const VT_SYMBOL: flatbuffers::VOffsetT = 4;
-  pub const VT_PRICE: flatbuffers::VOffsetT = 6;
-  pub const VT_VOLUME: flatbuffers::VOffsetT = 8;
-  pub const VT_TIMESTAMP: flatbuffers::VOffsetT = 10;
+impl<'a> Gap<'a> {
+  pub const VT_SINCE_NS: flatbuffers::VOffsetT = 4;
 
   #[inline]
   pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
-    Trade { _tab: table }
+    Gap { _tab: table }
   }
   #[allow(unused_mut)]
   pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
     _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
-    args: &'args TradeArgs<'args>
-  ) -> flatbuffers::WIPOffset<Trade<'bldr>> {
-    let mut builder = TradeBuilder::new(_fbb);
-    builder.add_timestamp(args.timestamp);
-    builder.add_volume(args.volume);
-    builder.add_price(args.price);
-    if let Some(x) = args.symbol { builder.add_symbol(x); }
+    args: &'args GapArgs
+  ) -> flatbuffers::WIPOffset<Gap<'bldr>> {
+    let mut builder = GapBuilder::new(_fbb);
+    builder.add_since_ns(args.since_ns);
     builder.finish()
   }
 
 
   #[inline]
-  pub fn symbol(&self) -> Option<&'a str> {
+  pub fn since_ns(&self) -> u64 {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(Trade::VT_SYMBOL, None)}
+    unsafe { self._tab.get::<u64>(Gap::VT_SINCE_NS, Some(0)).unwrap()}
   }
+}
+
+impl flatbuffers::Verifiable for Gap<'_> {
   #[inline]
-  pub fn price(&self) -> f32 {
-    // Safety:
-    // Created from valid Table for this object
-    // which contains a valid value in this slot
-    unsafe { self._tab.get::<f32>(Trade::VT_PRICE, Some(0.0)).unwrap()}
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u64>("since_ns", Self::VT_SINCE_NS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct GapArgs {
+    pub since_ns: u64,
+}
+impl<'a> Default for GapArgs {
+  #[inline]
+  fn default() -> Self {
+    GapArgs {
+      since_ns: 0,
+    }
+  }
+}
+
+pub struct GapBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> GapBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_since_ns(&mut self, since_ns: u64) {
+    self.fbb_.push_slot::<u64>(Gap::VT_SINCE_NS, since_ns, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> GapBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    GapBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Gap<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Gap<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Gap");
+      ds.field("since_ns", &self.since_ns());
+      ds.finish()
+  }
+}
+pub enum StatusOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Status<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Status<'a> {
+  type Inner = Status<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Status<'a> {
+  pub const VT_STATUS: flatbuffers::VOffsetT = 4;
+  pub const VT_REASON: flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Status { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args StatusArgs<'args>
+  ) -> flatbuffers::WIPOffset<Status<'bldr>> {
+    let mut builder = StatusBuilder::new(_fbb);
+    if let Some(x) = args.reason { builder.add_reason(x); }
+    builder.add_status(args.status);
+    builder.finish()
   }
+
+
   #[inline]
-  pub fn volume(&self) -> i32 {
+  pub fn status(&self) -> TradingStatus {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<i32>(Trade::VT_VOLUME, Some(0)).unwrap()}
+    unsafe { self._tab.get::<TradingStatus>(Status::VT_STATUS, Some(TradingStatus::Trading)).unwrap()}
   }
   #[inline]
-  pub fn timestamp(&self) -> u64 {
+  pub fn reason(&self) -> Option<&'a str> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<u64>(Trade::VT_TIMESTAMP, Some(0)).unwrap()}
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(Status::VT_REASON, None)}
   }
 }
 
-impl flatbuffers::Verifiable for Trade<'_> {
+impl flatbuffers::Verifiable for Status<'_> {
   #[inline]
   fn run_verifier(
     v: &mut flatbuffers::Verifier, pos: usize
   ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
     use self::flatbuffers::Verifiable;
     v.visit_table(pos)?
-     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("symbol", Self::VT_SYMBOL, false)?
-     .visit_field::<f32>("price", Self::VT_PRICE, false)?
-     .visit_field::<i32>("volume", Self::VT_VOLUME, false)?
-     .visit_field::<u64>("timestamp", Self::VT_TIMESTAMP, false)?
+     .visit_field::<TradingStatus>("status", Self::VT_STATUS, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("reason", Self::VT_REASON, false)?
      .finish();
     Ok(())
   }
 }
-pub struct TradeArgs<'a> {
-    pub symbol: Option<flatbuffers::WIPOffset<&'a str>>,
-    pub price: f32,
-    pub volume: i32,
-    pub timestamp: u64,
+pub struct StatusArgs<'a> {
+    pub status: TradingStatus,
+    pub reason: Option<flatbuffers::WIPOffset<&'a str>>,
 }
-impl<'a> Default for TradeArgs<'a> {
+impl<'a> Default for StatusArgs<'a> {
   #[inline]
   fn default() -> Self {
-    TradeArgs {
-      symbol: None,
-      price: 0.0,
-      volume: 0,
-      timestamp: 0,
+    StatusArgs {
+      status: TradingStatus::Trading,
+      reason: None,
     }
   }
 }
 
-pub struct TradeBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+pub struct StatusBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
   fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
   start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
 }
-impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> TradeBuilder<'a, 'b, A> {
-  #[inline]
-  pub fn add_symbol(&mut self, symbol: flatbuffers::WIPOffset<&'b  str>) {
-    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Trade::VT_SYMBOL, symbol);
-  }
-  #[inline]
-  pub fn add_price(&mut self, price: f32) {
-    self.fbb_.push_slot::<f32>(Trade::VT_PRICE, price, 0.0);
-  }
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> StatusBuilder<'a, 'b, A> {
   #[inline]
-  pub fn add_volume(&mut self, volume: i32) {
-    self.fbb_.push_slot::<i32>(Trade::VT_VOLUME, volume, 0);
+  pub fn add_status(&mut self, status: TradingStatus) {
+    self.fbb_.push_slot::<TradingStatus>(Status::VT_STATUS, status, TradingStatus::Trading);
   }
   #[inline]
-  pub fn add_timestamp(&mut self, timestamp: u64) {
-    self.fbb_.push_slot::<u64>(Trade::VT_TIMESTAMP, timestamp, 0);
+  pub fn add_reason(&mut self, reason: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Status::VT_REASON, reason);
   }
   #[inline]
-  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> TradeBuilder<'a, 'b, A> {
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> StatusBuilder<'a, 'b, A> {
     let start = _fbb.start_table();
-    TradeBuilder {
+    StatusBuilder {
       fbb_: _fbb,
       start_: start,
     }
   }
   #[inline]
-  pub fn finish(self) -> flatbuffers::WIPOffset<Trade<'a>> {
+  pub fn finish(self) -> flatbuffers::WIPOffset<Status<'a>> {
     let o = self.fbb_.end_table(self.start_);
     flatbuffers::WIPOffset::new(o.value())
   }
 }
 
-impl core::fmt::Debug for Trade<'_> {
+impl core::fmt::Debug for Status<'_> {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-    let mut ds = f.debug_struct("Trade");
-      ds.field("symbol", &self.symbol());
-      ds.field("price", &self.price());
-      ds.field("volume", &self.volume());
-      ds.field("timestamp", &self.timestamp());
+    let mut ds = f.debug_struct("Status");
+      ds.field("status", &self.status());
+      ds.field("reason", &self.reason());
       ds.finish()
   }
 }
@@ -330,9 +1754,11 @@ impl<'a> flatbuffers::Follow<'a> for MarketEvent<'a> {
 }
 
 impl<'a> MarketEvent<'a> {
-  pub const VT_EVENT_TYPE: flatbuffers::VOffsetT = 4;
-  pub const VT_QUOTE: flatbuffers::VOffsetT = 6;
-  pub const VT_TRADE: flatbuffers::VOffsetT = 8;
+  pub const VT_SYMBOL: flatbuffers::VOffsetT = 4;
+  pub const VT_EXCHANGE_TS_NS: flatbuffers::VOffsetT = 6;
+  pub const VT_RECEIVE_TS_NS: flatbuffers::VOffsetT = 8;
+  pub const VT_PAYLOAD_TYPE: flatbuffers::VOffsetT = 10;
+  pub const VT_PAYLOAD: flatbuffers::VOffsetT = 12;
 
   #[inline]
   pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
@@ -344,34 +1770,155 @@ impl<'a> MarketEvent<'a> {
     args: &'args MarketEventArgs<'args>
   ) -> flatbuffers::WIPOffset<MarketEvent<'bldr>> {
     let mut builder = MarketEventBuilder::new(_fbb);
-    if let Some(x) = args.trade { builder.add_trade(x); }
-    if let Some(x) = args.quote { builder.add_quote(x); }
-    if let Some(x) = args.event_type { builder.add_event_type(x); }
+    builder.add_receive_ts_ns(args.receive_ts_ns);
+    builder.add_exchange_ts_ns(args.exchange_ts_ns);
+    if let Some(x) = args.payload { builder.add_payload(x); }
+    if let Some(x) = args.symbol { builder.add_symbol(x); }
+    builder.add_payload_type(args.payload_type);
     builder.finish()
   }
 
 
   #[inline]
-  pub fn event_type(&self) -> Option<&'a str> {
+  pub fn symbol(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(MarketEvent::VT_SYMBOL, None)}
+  }
+  #[inline]
+  pub fn exchange_ts_ns(&self) -> u64 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u64>(MarketEvent::VT_EXCHANGE_TS_NS, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn receive_ts_ns(&self) -> u64 {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(MarketEvent::VT_EVENT_TYPE, None)}
+    unsafe { self._tab.get::<u64>(MarketEvent::VT_RECEIVE_TS_NS, Some(0)).unwrap()}
   }
   #[inline]
-  pub fn quote(&self) -> Option<Quote<'a>> {
+  pub fn payload_type(&self) -> Payload {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<Quote>>(MarketEvent::VT_QUOTE, None)}
+    unsafe { self._tab.get::<Payload>(MarketEvent::VT_PAYLOAD_TYPE, Some(Payload::NONE)).unwrap()}
   }
   #[inline]
-  pub fn trade(&self) -> Option<Trade<'a>> {
+  pub fn payload(&self) -> Option<flatbuffers::Table<'a>> {
     // Safety:
     // Created from valid Table for this object
     // which contains a valid value in this slot
-    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<Trade>>(MarketEvent::VT_TRADE, None)}
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Table<'a>>>(MarketEvent::VT_PAYLOAD, None)}
+  }
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_quote(&self) -> Option<Quote<'a>> {
+    if self.payload_type() == Payload::Quote {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Quote::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_trade(&self) -> Option<Trade<'a>> {
+    if self.payload_type() == Payload::Trade {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Trade::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_bar(&self) -> Option<Bar<'a>> {
+    if self.payload_type() == Payload::Bar {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Bar::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_order_book_update(&self) -> Option<OrderBookUpdate<'a>> {
+    if self.payload_type() == Payload::OrderBookUpdate {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { OrderBookUpdate::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_option_quote(&self) -> Option<OptionQuote<'a>> {
+    if self.payload_type() == Payload::OptionQuote {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { OptionQuote::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_gap(&self) -> Option<Gap<'a>> {
+    if self.payload_type() == Payload::Gap {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Gap::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_status(&self) -> Option<Status<'a>> {
+    if self.payload_type() == Payload::Status {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Status::init_from_table(t) }
+     })
+    } else {
+      None
+    }
   }
+
 }
 
 impl flatbuffers::Verifiable for MarketEvent<'_> {
@@ -381,25 +1928,41 @@ impl flatbuffers::Verifiable for MarketEvent<'_> {
   ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
     use self::flatbuffers::Verifiable;
     v.visit_table(pos)?
-     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("event_type", Self::VT_EVENT_TYPE, false)?
-     .visit_field::<flatbuffers::ForwardsUOffset<Quote>>("quote", Self::VT_QUOTE, false)?
-     .visit_field::<flatbuffers::ForwardsUOffset<Trade>>("trade", Self::VT_TRADE, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("symbol", Self::VT_SYMBOL, false)?
+     .visit_field::<u64>("exchange_ts_ns", Self::VT_EXCHANGE_TS_NS, false)?
+     .visit_field::<u64>("receive_ts_ns", Self::VT_RECEIVE_TS_NS, false)?
+     .visit_union::<Payload, _>("payload_type", Self::VT_PAYLOAD_TYPE, "payload", Self::VT_PAYLOAD, false, |key, v, pos| {
+        match key {
+          Payload::Quote => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Quote>>("Payload::Quote", pos),
+          Payload::Trade => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Trade>>("Payload::Trade", pos),
+          Payload::Bar => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Bar>>("Payload::Bar", pos),
+          Payload::OrderBookUpdate => v.verify_union_variant::<flatbuffers::ForwardsUOffset<OrderBookUpdate>>("Payload::OrderBookUpdate", pos),
+          Payload::OptionQuote => v.verify_union_variant::<flatbuffers::ForwardsUOffset<OptionQuote>>("Payload::OptionQuote", pos),
+          Payload::Gap => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Gap>>("Payload::Gap", pos),
+          Payload::Status => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Status>>("Payload::Status", pos),
+          _ => Ok(()),
+        }
+     })?
      .finish();
     Ok(())
   }
 }
 pub struct MarketEventArgs<'a> {
-    pub event_type: Option<flatbuffers::WIPOffset<&'a str>>,
-    pub quote: Option<flatbuffers::WIPOffset<Quote<'a>>>,
-    pub trade: Option<flatbuffers::WIPOffset<Trade<'a>>>,
+    pub symbol: Option<flatbuffers::WIPOffset<&'a str>>,
+    pub exchange_ts_ns: u64,
+    pub receive_ts_ns: u64,
+    pub payload_type: Payload,
+    pub payload: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
 }
 impl<'a> Default for MarketEventArgs<'a> {
   #[inline]
   fn default() -> Self {
     MarketEventArgs {
-      event_type: None,
-      quote: None,
-      trade: None,
+      symbol: None,
+      exchange_ts_ns: 0,
+      receive_ts_ns: 0,
+      payload_type: Payload::NONE,
+      payload: None,
     }
   }
 }
@@ -410,16 +1973,24 @@ pub struct MarketEventBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
 }
 impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> MarketEventBuilder<'a, 'b, A> {
   #[inline]
-  pub fn add_event_type(&mut self, event_type: flatbuffers::WIPOffset<&'b  str>) {
-    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(MarketEvent::VT_EVENT_TYPE, event_type);
+  pub fn add_symbol(&mut self, symbol: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(MarketEvent::VT_SYMBOL, symbol);
+  }
+  #[inline]
+  pub fn add_exchange_ts_ns(&mut self, exchange_ts_ns: u64) {
+    self.fbb_.push_slot::<u64>(MarketEvent::VT_EXCHANGE_TS_NS, exchange_ts_ns, 0);
+  }
+  #[inline]
+  pub fn add_receive_ts_ns(&mut self, receive_ts_ns: u64) {
+    self.fbb_.push_slot::<u64>(MarketEvent::VT_RECEIVE_TS_NS, receive_ts_ns, 0);
   }
   #[inline]
-  pub fn add_quote(&mut self, quote: flatbuffers::WIPOffset<Quote<'b >>) {
-    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<Quote>>(MarketEvent::VT_QUOTE, quote);
+  pub fn add_payload_type(&mut self, payload_type: Payload) {
+    self.fbb_.push_slot::<Payload>(MarketEvent::VT_PAYLOAD_TYPE, payload_type, Payload::NONE);
   }
   #[inline]
-  pub fn add_trade(&mut self, trade: flatbuffers::WIPOffset<Trade<'b >>) {
-    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<Trade>>(MarketEvent::VT_TRADE, trade);
+  pub fn add_payload(&mut self, payload: flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(MarketEvent::VT_PAYLOAD, payload);
   }
   #[inline]
   pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> MarketEventBuilder<'a, 'b, A> {
@@ -439,9 +2010,65 @@ impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> MarketEventBuilder<'a, 'b, A> {
 impl core::fmt::Debug for MarketEvent<'_> {
   fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     let mut ds = f.debug_struct("MarketEvent");
-      ds.field("event_type", &self.event_type());
-      ds.field("quote", &self.quote());
-      ds.field("trade", &self.trade());
+      ds.field("symbol", &self.symbol());
+      ds.field("exchange_ts_ns", &self.exchange_ts_ns());
+      ds.field("receive_ts_ns", &self.receive_ts_ns());
+      ds.field("payload_type", &self.payload_type());
+      match self.payload_type() {
+        Payload::Quote => {
+          if let Some(x) = self.payload_as_quote() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Trade => {
+          if let Some(x) = self.payload_as_trade() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Bar => {
+          if let Some(x) = self.payload_as_bar() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::OrderBookUpdate => {
+          if let Some(x) = self.payload_as_order_book_update() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::OptionQuote => {
+          if let Some(x) = self.payload_as_option_quote() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Gap => {
+          if let Some(x) = self.payload_as_gap() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Status => {
+          if let Some(x) = self.payload_as_status() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        _ => {
+          let x: Option<()> = None;
+          ds.field("payload", &x)
+        },
+      };
       ds.finish()
   }
 }
@@ -505,16 +2132,28 @@ pub unsafe fn root_as_market_event_unchecked(buf: &[u8]) -> MarketEvent {
 pub unsafe fn size_prefixed_root_as_market_event_unchecked(buf: &[u8]) -> MarketEvent {
   flatbuffers::size_prefixed_root_unchecked::<MarketEvent>(buf)
 }
+pub const MARKET_EVENT_IDENTIFIER: &str = "OTME";
+
+#[inline]
+pub fn market_event_buffer_has_identifier(buf: &[u8]) -> bool {
+  flatbuffers::buffer_has_identifier(buf, MARKET_EVENT_IDENTIFIER, false)
+}
+
+#[inline]
+pub fn market_event_size_prefixed_buffer_has_identifier(buf: &[u8]) -> bool {
+  flatbuffers::buffer_has_identifier(buf, MARKET_EVENT_IDENTIFIER, true)
+}
+
 #[inline]
 pub fn finish_market_event_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(
     fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
     root: flatbuffers::WIPOffset<MarketEvent<'a>>) {
-  fbb.finish(root, None);
+  fbb.finish(root, Some(MARKET_EVENT_IDENTIFIER));
 }
 
 #[inline]
 pub fn finish_size_prefixed_market_event_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>, root: flatbuffers::WIPOffset<MarketEvent<'a>>) {
-  fbb.finish_size_prefixed(root, None);
+  fbb.finish_size_prefixed(root, Some(MARKET_EVENT_IDENTIFIER));
 }
 }  // pub mod MarketData
 
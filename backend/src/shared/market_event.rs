@@ -0,0 +1,180 @@
+use crate::shared::instrument::{self, OptionContract};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A market data event as carried on the `market_data` topic, mirroring the
+/// `MarketEvent` table in `schema.fbs`. Timestamps are Unix nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketEvent {
+    pub symbol: String,
+    pub exchange_ts_ns: u64, // Stamped by the venue
+    pub receive_ts_ns: u64,  // Stamped by our feed handler
+    pub payload: EventPayload,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventPayload {
+    Quote(Quote),
+    Trade(Trade),
+    Bar(Bar),
+    OrderBook(OrderBookUpdate),
+    OptionQuote(OptionQuote),
+    Gap(Gap),
+    Status(Status),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub bid_size: f64,
+    pub ask_size: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub price: f64,
+    pub quantity: f64,
+    pub trade_id: u64,
+}
+
+/// OHLCV bar covering `interval_ns` from the event's exchange timestamp
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bar {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub vwap: f64,
+    pub trade_count: u64,
+    pub interval_ns: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price: f64,
+    pub quantity: f64, // 0 removes the level
+}
+
+/// Level changes, or the full book when `is_snapshot` is set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookUpdate {
+    pub sequence: u64,
+    pub is_snapshot: bool,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionRight {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+}
+
+/// Quote for a single option contract; the event symbol is the OCC symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionQuote {
+    pub underlying: String,
+    pub strike: f64,
+    pub expiry: u32, // YYYYMMDD
+    pub right: OptionRight,
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub last_price: f64,
+    pub implied_volatility: f64,
+    pub greeks: Greeks,
+}
+
+impl OptionQuote {
+    /// A quote for `contract` with prices, volatility and greeks still zero
+    pub fn for_contract(contract: &OptionContract) -> Self {
+        let expiry = &contract.expiry;
+        Self {
+            underlying: contract.underlying.clone(),
+            strike: contract.strike.to_f64(),
+            expiry: expiry.year() as u32 * 10_000 + expiry.month() * 100 + expiry.day(),
+            right: match contract.right {
+                instrument::OptionRight::Call => OptionRight::Call,
+                instrument::OptionRight::Put => OptionRight::Put,
+            },
+            bid_price: 0.0,
+            ask_price: 0.0,
+            last_price: 0.0,
+            implied_volatility: 0.0,
+            greeks: Greeks::default(),
+        }
+    }
+}
+
+/// The feed was interrupted: events for the symbol between `since_ns` and
+/// the event's exchange timestamp may be missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Gap {
+    pub since_ns: u64, // The last message received before the interruption
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradingStatus {
+    Trading,
+    Halted,
+    Paused,        // Volatility pause (LULD)
+    QuotationOnly, // Quotes but no trades, e.g. before a halted symbol reopens
+}
+
+/// The venue changed the symbol's trading status, e.g. a halt or its lifting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub status: TradingStatus,
+    pub reason: String, // The venue's explanation; empty if none was given
+}
+
+impl MarketEvent {
+    /// Builds an event stamped as received now
+    pub fn new(symbol: impl Into<String>, exchange_ts_ns: u64, payload: EventPayload) -> Self {
+        Self {
+            symbol: symbol.into(),
+            exchange_ts_ns,
+            receive_ts_ns: now_ns(),
+            payload,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Short name of the payload variant
+    pub fn event_type(&self) -> &'static str {
+        match self.payload {
+            EventPayload::Quote(_) => "quote",
+            EventPayload::Trade(_) => "trade",
+            EventPayload::Bar(_) => "bar",
+            EventPayload::OrderBook(_) => "order_book",
+            EventPayload::OptionQuote(_) => "option_quote",
+            EventPayload::Gap(_) => "gap",
+            EventPayload::Status(_) => "status",
+        }
+    }
+}
+
+/// Current wall-clock time in Unix nanoseconds
+pub fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,926 @@
+use crate::shared::codec::{codec_for, decode_market_event, WireFormat, CODEC_HEADER};
+use crate::shared::config::{BusBackend, BusConfig, Config, TradeSignal};
+use crate::shared::market_event::MarketEvent;
+use crate::shared::metrics::{
+    symbol_label, BUS_PUBLISH_SECONDS, MESSAGES_PUBLISHED, MESSAGES_RECEIVED, PARSE_FAILURES,
+};
+use crate::shared::subscription::SubscriptionRequest;
+use crate::shared::telemetry::CorrelationId;
+use async_trait::async_trait;
+use futures_util::stream::{select_all, SelectAll, StreamExt};
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::{Header, Headers, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::info;
+
+/// Carries the message key on backends without native keys (NATS)
+pub const KEY_HEADER: &str = "optitrade-key";
+/// Carries the `CorrelationId` of the market event a message descends from
+pub const CORRELATION_HEADER: &str = "optitrade-correlation-id";
+
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(3);
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Market data events published by `market_data`
+pub const MARKET_DATA: Topic<MarketEvent> = Topic::new("market_data");
+/// Orders requested by strategies and the backtester
+pub const TRADE_SIGNALS: Topic<TradeSignal> = Topic::new("trade_signals");
+/// Subscribe and unsubscribe requests for `market_data`, see `subscription.rs`
+pub const MARKET_DATA_CONTROL: Topic<SubscriptionRequest> = Topic::new("market_data_control");
+
+/// A message as it travels over any backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub topic: String,
+    pub key: Option<String>,
+    pub payload: Vec<u8>,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub partition: Option<i32>, // Set by backends that track positions
+    pub offset: Option<i64>,
+}
+
+impl Message {
+    pub fn new(topic: impl Into<String>, payload: impl Into<Vec<u8>>) -> Self {
+        Self {
+            topic: topic.into(),
+            key: None,
+            payload: payload.into(),
+            headers: Vec::new(),
+            partition: None,
+            offset: None,
+        }
+    }
+
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// First value of the header named `key`
+    pub fn header(&self, key: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_slice())
+    }
+
+    pub fn with_correlation_id(self, id: &CorrelationId) -> Self {
+        self.with_header(CORRELATION_HEADER, id.as_str())
+    }
+
+    pub fn correlation_id(&self) -> Option<CorrelationId> {
+        self.header(CORRELATION_HEADER)
+            .map(|v| CorrelationId::from(String::from_utf8_lossy(v).into_owned()))
+    }
+}
+
+#[derive(Debug)]
+pub enum BusError {
+    Connect(String),
+    Publish(String),
+    Subscribe(String),
+    Receive(String),
+    Commit(String),
+    Encode(String),
+    Decode(String),
+}
+
+impl fmt::Display for BusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusError::Connect(e) => write!(f, "failed to connect to message bus: {}", e),
+            BusError::Publish(e) => write!(f, "failed to publish: {}", e),
+            BusError::Subscribe(e) => write!(f, "failed to subscribe: {}", e),
+            BusError::Receive(e) => write!(f, "failed to receive: {}", e),
+            BusError::Commit(e) => write!(f, "failed to commit: {}", e),
+            BusError::Encode(e) => write!(f, "failed to encode message: {}", e),
+            BusError::Decode(e) => write!(f, "failed to decode message: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BusError {}
+
+/// Where a consumer group without committed offsets starts reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetReset {
+    /// Only messages published from now on
+    Latest,
+    /// Everything the topic still retains; backends without retention
+    /// behave as `Latest`
+    Earliest,
+}
+
+impl OffsetReset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OffsetReset::Latest => "latest",
+            OffsetReset::Earliest => "earliest",
+        }
+    }
+}
+
+/// Publish/subscribe transport for raw messages
+#[async_trait]
+pub trait MessageBus: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn publish(&self, message: Message) -> Result<(), BusError>;
+
+    /// Joins consumer `group`; each message goes to one member of each group.
+    /// A group new to the topics starts reading at `reset`.
+    async fn subscribe(
+        &self,
+        topics: &[&str],
+        group: &str,
+        reset: OffsetReset,
+    ) -> Result<Box<dyn Subscription>, BusError>;
+
+    /// Waits for published messages to leave the process
+    async fn flush(&self, timeout: Duration) -> Result<(), BusError>;
+
+    /// Whether the backend is reachable right now
+    async fn health_check(&self) -> Result<(), BusError>;
+}
+
+#[async_trait]
+pub trait Subscription: Send {
+    /// Next message; `None` once the bus has shut down
+    async fn recv(&mut self) -> Option<Result<Message, BusError>>;
+
+    /// Marks `message` as processed so it is not redelivered to the group
+    async fn commit(&mut self, message: &Message) -> Result<(), BusError>;
+
+    /// Commits everything marked processed right away and leaves the group
+    async fn close(self: Box<Self>) -> Result<(), BusError> {
+        Ok(())
+    }
+}
+
+/// Values carried on a typed topic
+pub trait BusPayload: Sized {
+    /// Partition key, so related messages stay ordered
+    fn key(&self) -> Option<&str>;
+
+    fn encode(&self, format: WireFormat) -> Result<Vec<u8>, BusError>;
+
+    fn decode(message: &Message) -> Result<Self, BusError>;
+}
+
+impl BusPayload for MarketEvent {
+    fn key(&self) -> Option<&str> {
+        Some(self.symbol())
+    }
+
+    fn encode(&self, format: WireFormat) -> Result<Vec<u8>, BusError> {
+        codec_for(format)
+            .encode(self)
+            .map_err(|e| BusError::Encode(e.to_string()))
+    }
+
+    fn decode(message: &Message) -> Result<Self, BusError> {
+        decode_market_event(message.header(CODEC_HEADER), &message.payload)
+            .map_err(|e| BusError::Decode(e.to_string()))
+    }
+}
+
+impl BusPayload for TradeSignal {
+    // By underlying, so signals for a stock and its options stay in order
+    fn key(&self) -> Option<&str> {
+        Some(self.symbol.underlying())
+    }
+
+    fn encode(&self, format: WireFormat) -> Result<Vec<u8>, BusError> {
+        match format {
+            WireFormat::Json => {
+                serde_json::to_vec(self).map_err(|e| BusError::Encode(e.to_string()))
+            }
+            other => Err(BusError::Encode(format!(
+                "trade signals cannot be encoded as {}",
+                other
+            ))),
+        }
+    }
+
+    fn decode(message: &Message) -> Result<Self, BusError> {
+        serde_json::from_slice(&message.payload).map_err(|e| BusError::Decode(e.to_string()))
+    }
+}
+
+impl BusPayload for SubscriptionRequest {
+    // By requester, so one strategy's subscribe and unsubscribe stay in order
+    fn key(&self) -> Option<&str> {
+        Some(&self.requester)
+    }
+
+    fn encode(&self, format: WireFormat) -> Result<Vec<u8>, BusError> {
+        match format {
+            WireFormat::Json => {
+                serde_json::to_vec(self).map_err(|e| BusError::Encode(e.to_string()))
+            }
+            other => Err(BusError::Encode(format!(
+                "subscription requests cannot be encoded as {}",
+                other
+            ))),
+        }
+    }
+
+    fn decode(message: &Message) -> Result<Self, BusError> {
+        serde_json::from_slice(&message.payload).map_err(|e| BusError::Decode(e.to_string()))
+    }
+}
+
+/// A topic name bound to the type published on it
+pub struct Topic<T> {
+    pub name: &'static str,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<T> Topic<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _payload: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Topic<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Topic<T> {}
+
+/// A message together with its decoded value
+pub struct Delivery<T> {
+    pub message: Message,
+    pub value: Result<T, BusError>,
+}
+
+/// Subscription that decodes every message as `T`
+pub struct TypedSubscription<T> {
+    inner: Box<dyn Subscription>,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<T: BusPayload> TypedSubscription<T> {
+    pub async fn recv(&mut self) -> Option<Result<Delivery<T>, BusError>> {
+        let message = match self.inner.recv().await? {
+            Ok(message) => message,
+            Err(e) => return Some(Err(e)),
+        };
+        let value = T::decode(&message);
+        if value.is_ok() {
+            let symbol = symbol_label(message.key.as_deref().unwrap_or(""));
+            MESSAGES_RECEIVED
+                .with_label_values(&[message.topic.as_str(), &symbol])
+                .inc();
+        } else {
+            PARSE_FAILURES
+                .with_label_values(&[message.topic.as_str()])
+                .inc();
+        }
+        Some(Ok(Delivery { message, value }))
+    }
+
+    pub async fn commit(&mut self, message: &Message) -> Result<(), BusError> {
+        self.inner.commit(message).await
+    }
+
+    /// Commits and leaves the group; call on shutdown once the last message is committed
+    pub async fn close(self) -> Result<(), BusError> {
+        self.inner.close().await
+    }
+}
+
+/// Typed front end over the configured backend, encoding each topic in its
+/// configured wire format
+#[derive(Clone)]
+pub struct Bus {
+    transport: Arc<dyn MessageBus>,
+    formats: HashMap<String, WireFormat>,
+}
+
+impl Bus {
+    /// Connects to the backend in `[bus]` and picks up `[topics]` formats
+    pub async fn connect(config: &Config) -> Result<Self, BusError> {
+        let transport = connect_transport(&config.bus).await?;
+        let mut bus = Bus::new(transport);
+        for (topic, topic_config) in &config.topics {
+            bus = bus.with_format(topic, topic_config.format);
+        }
+        Ok(bus)
+    }
+
+    /// Wraps an existing transport; every topic uses JSON until overridden
+    pub fn new(transport: Arc<dyn MessageBus>) -> Self {
+        Self {
+            transport,
+            formats: HashMap::new(),
+        }
+    }
+
+    pub fn with_format(mut self, topic: &str, format: WireFormat) -> Self {
+        self.formats.insert(topic.to_string(), format);
+        self
+    }
+
+    pub fn transport(&self) -> &Arc<dyn MessageBus> {
+        &self.transport
+    }
+
+    pub fn format<T>(&self, topic: &Topic<T>) -> WireFormat {
+        self.formats.get(topic.name).copied().unwrap_or_default()
+    }
+
+    pub async fn publish<T: BusPayload>(
+        &self,
+        topic: &Topic<T>,
+        value: &T,
+    ) -> Result<(), BusError> {
+        let message = self.encode(topic, value)?;
+        self.send(message).await
+    }
+
+    /// Publishes `value` tagged with the correlation ID of the event that caused it
+    pub async fn publish_correlated<T: BusPayload>(
+        &self,
+        topic: &Topic<T>,
+        value: &T,
+        correlation_id: &CorrelationId,
+    ) -> Result<(), BusError> {
+        let message = self
+            .encode(topic, value)?
+            .with_correlation_id(correlation_id);
+        self.send(message).await
+    }
+
+    pub async fn subscribe<T: BusPayload>(
+        &self,
+        topic: &Topic<T>,
+        group: &str,
+    ) -> Result<TypedSubscription<T>, BusError> {
+        Ok(TypedSubscription {
+            inner: self
+                .transport
+                .subscribe(&[topic.name], group, OffsetReset::Latest)
+                .await?,
+            _payload: PhantomData,
+        })
+    }
+
+    pub async fn flush(&self, timeout: Duration) -> Result<(), BusError> {
+        self.transport.flush(timeout).await
+    }
+
+    pub async fn health_check(&self) -> Result<(), BusError> {
+        self.transport.health_check().await
+    }
+
+    fn encode<T: BusPayload>(&self, topic: &Topic<T>, value: &T) -> Result<Message, BusError> {
+        let format = self.format(topic);
+        let mut message = Message::new(topic.name, value.encode(format)?)
+            .with_header(CODEC_HEADER, format.header_value());
+        message.key = value.key().map(str::to_string);
+        Ok(message)
+    }
+
+    async fn send(&self, message: Message) -> Result<(), BusError> {
+        let labels = [
+            message.topic.clone(),
+            symbol_label(message.key.as_deref().unwrap_or("")).into_owned(),
+        ];
+        let timer = BUS_PUBLISH_SECONDS
+            .with_label_values(&labels[..1])
+            .start_timer();
+        let result = self.transport.publish(message).await;
+        timer.observe_duration(); // Failed sends count too; timeouts show up here
+        result?;
+        MESSAGES_PUBLISHED.with_label_values(&labels).inc();
+        Ok(())
+    }
+}
+
+/// Builds the raw transport selected by `[bus]`
+pub async fn connect_transport(config: &BusConfig) -> Result<Arc<dyn MessageBus>, BusError> {
+    let brokers = config.brokers();
+    let transport: Arc<dyn MessageBus> = match config.backend {
+        BusBackend::Kafka => Arc::new(KafkaBus::new(&brokers)?),
+        BusBackend::Nats => Arc::new(NatsBus::connect(&brokers).await?),
+        BusBackend::Memory => Arc::new(MemoryBus::new()),
+    };
+    info!(
+        backend = transport.name(),
+        brokers = %if brokers.is_empty() {
+            "in-process".to_string()
+        } else {
+            brokers.join(",")
+        },
+        "Connected to message bus"
+    );
+    Ok(transport)
+}
+
+pub struct KafkaBus {
+    brokers: String,
+    producer: FutureProducer,
+}
+
+impl KafkaBus {
+    pub fn new(brokers: &[String]) -> Result<Self, BusError> {
+        let brokers = brokers.join(",");
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .set("message.timeout.ms", "5000")
+            .set("acks", "all") // Ensure messages are confirmed by the broker
+            .set("queue.buffering.max.ms", "1") // Reduce buffering delay
+            .create()
+            .map_err(|e| BusError::Connect(e.to_string()))?;
+        Ok(Self { brokers, producer })
+    }
+}
+
+#[async_trait]
+impl MessageBus for KafkaBus {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn publish(&self, message: Message) -> Result<(), BusError> {
+        let mut headers = OwnedHeaders::new();
+        for (key, value) in &message.headers {
+            headers = headers.insert(Header {
+                key,
+                value: Some(value),
+            });
+        }
+
+        let mut record = FutureRecord::<str, Vec<u8>>::to(&message.topic)
+            .payload(&message.payload)
+            .headers(headers);
+        if let Some(key) = &message.key {
+            record = record.key(key);
+        }
+
+        self.producer
+            .send(record, PUBLISH_TIMEOUT)
+            .await
+            .map(|_| ())
+            .map_err(|(e, _)| BusError::Publish(e.to_string()))
+    }
+
+    async fn subscribe(
+        &self,
+        topics: &[&str],
+        group: &str,
+        reset: OffsetReset,
+    ) -> Result<Box<dyn Subscription>, BusError> {
+        // Offsets are stored on commit and flushed by auto-commit, so only
+        // processed messages count as consumed
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("group.id", group)
+            .set("bootstrap.servers", &self.brokers)
+            .set("enable.auto.commit", "true")
+            .set("enable.auto.offset.store", "false")
+            .set("auto.offset.reset", reset.as_str())
+            .create()
+            .map_err(|e| BusError::Subscribe(e.to_string()))?;
+        consumer
+            .subscribe(topics)
+            .map_err(|e| BusError::Subscribe(e.to_string()))?;
+        Ok(Box::new(KafkaSubscription { consumer }))
+    }
+
+    async fn flush(&self, timeout: Duration) -> Result<(), BusError> {
+        self.producer
+            .flush(timeout)
+            .map_err(|e| BusError::Publish(e.to_string()))
+    }
+
+    async fn health_check(&self) -> Result<(), BusError> {
+        // Metadata requests block on the network
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || {
+            producer
+                .client()
+                .fetch_metadata(None, HEALTH_CHECK_TIMEOUT)
+                .map(|_| ())
+        })
+        .await
+        .map_err(|e| BusError::Connect(e.to_string()))?
+        .map_err(|e| BusError::Connect(e.to_string()))
+    }
+}
+
+struct KafkaSubscription {
+    consumer: StreamConsumer,
+}
+
+#[async_trait]
+impl Subscription for KafkaSubscription {
+    async fn recv(&mut self) -> Option<Result<Message, BusError>> {
+        use rdkafka::Message as _;
+
+        let borrowed = match self.consumer.recv().await {
+            Ok(message) => message,
+            Err(e) => return Some(Err(BusError::Receive(e.to_string()))),
+        };
+        let headers = borrowed
+            .headers()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .map(|h| (h.key.to_string(), h.value.unwrap_or_default().to_vec()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Ok(Message {
+            topic: borrowed.topic().to_string(),
+            key: borrowed
+                .key()
+                .map(|k| String::from_utf8_lossy(k).into_owned()),
+            payload: borrowed.payload().unwrap_or_default().to_vec(),
+            headers,
+            partition: Some(borrowed.partition()),
+            offset: Some(borrowed.offset()),
+        }))
+    }
+
+    async fn commit(&mut self, message: &Message) -> Result<(), BusError> {
+        let (Some(partition), Some(offset)) = (message.partition, message.offset) else {
+            return Err(BusError::Commit("message has no partition offset".into()));
+        };
+        self.consumer
+            .store_offset(&message.topic, partition, offset)
+            .map_err(|e| BusError::Commit(e.to_string()))
+    }
+
+    async fn close(self: Box<Self>) -> Result<(), BusError> {
+        // Dropping the consumer afterwards leaves the group, so partitions move on promptly
+        tokio::task::spawn_blocking(move || {
+            match self.consumer.commit_consumer_state(CommitMode::Sync) {
+                Ok(()) | Err(KafkaError::ConsumerCommit(RDKafkaErrorCode::NoOffset)) => Ok(()),
+                Err(e) => Err(BusError::Commit(e.to_string())),
+            }
+        })
+        .await
+        .map_err(|e| BusError::Commit(e.to_string()))?
+    }
+}
+
+pub struct NatsBus {
+    client: async_nats::Client,
+}
+
+impl NatsBus {
+    pub async fn connect(servers: &[String]) -> Result<Self, BusError> {
+        let client = async_nats::connect(servers.join(","))
+            .await
+            .map_err(|e| BusError::Connect(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl MessageBus for NatsBus {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn publish(&self, message: Message) -> Result<(), BusError> {
+        // NATS header values are text; every header we set is
+        let mut headers = async_nats::HeaderMap::new();
+        for (key, value) in &message.headers {
+            headers.insert(key.as_str(), String::from_utf8_lossy(value).as_ref());
+        }
+        if let Some(key) = &message.key {
+            headers.insert(KEY_HEADER, key.as_str());
+        }
+
+        self.client
+            .publish_with_headers(message.topic, headers, message.payload.into())
+            .await
+            .map_err(|e| BusError::Publish(e.to_string()))
+    }
+
+    async fn subscribe(
+        &self,
+        topics: &[&str],
+        group: &str,
+        _reset: OffsetReset,
+    ) -> Result<Box<dyn Subscription>, BusError> {
+        let mut subscribers = Vec::with_capacity(topics.len());
+        for topic in topics {
+            let subscriber = self
+                .client
+                .queue_subscribe(topic.to_string(), group.to_string())
+                .await
+                .map_err(|e| BusError::Subscribe(e.to_string()))?;
+            subscribers.push(subscriber);
+        }
+        Ok(Box::new(NatsSubscription {
+            subscribers: select_all(subscribers),
+        }))
+    }
+
+    async fn flush(&self, timeout: Duration) -> Result<(), BusError> {
+        tokio::time::timeout(timeout, self.client.flush())
+            .await
+            .map_err(|_| BusError::Publish("flush timed out".into()))?
+            .map_err(|e| BusError::Publish(e.to_string()))
+    }
+
+    async fn health_check(&self) -> Result<(), BusError> {
+        match self.client.connection_state() {
+            async_nats::connection::State::Connected => Ok(()),
+            state => Err(BusError::Connect(format!("connection {}", state))),
+        }
+    }
+}
+
+struct NatsSubscription {
+    subscribers: SelectAll<async_nats::Subscriber>,
+}
+
+#[async_trait]
+impl Subscription for NatsSubscription {
+    async fn recv(&mut self) -> Option<Result<Message, BusError>> {
+        let message = self.subscribers.next().await?;
+
+        let mut key = None;
+        let mut headers = Vec::new();
+        if let Some(map) = &message.headers {
+            for (name, values) in map.iter() {
+                let name = name.to_string();
+                for value in values {
+                    if name == KEY_HEADER {
+                        key = Some(value.to_string());
+                    } else {
+                        headers.push((name.clone(), value.as_str().as_bytes().to_vec()));
+                    }
+                }
+            }
+        }
+
+        Some(Ok(Message {
+            topic: message.subject.to_string(),
+            key,
+            payload: message.payload.to_vec(),
+            headers,
+            partition: None,
+            offset: None,
+        }))
+    }
+
+    async fn commit(&mut self, _message: &Message) -> Result<(), BusError> {
+        Ok(()) // Core NATS is at-most-once; there is nothing to acknowledge
+    }
+}
+
+/// In-process bus. Messages published before a group subscribes are dropped,
+/// as with NATS, and queues are unbounded so a slow consumer never blocks
+/// the publisher.
+#[derive(Default)]
+pub struct MemoryBus {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    offsets: HashMap<String, i64>,
+    routes: HashMap<String, Vec<String>>, // topic -> subscribed groups
+    groups: HashMap<String, MemoryGroup>,
+}
+
+struct MemoryGroup {
+    tx: mpsc::UnboundedSender<Message>,
+    rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>>,
+}
+
+impl MemoryBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MessageBus for MemoryBus {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn publish(&self, mut message: Message) -> Result<(), BusError> {
+        let mut state = self.state.lock().expect("memory bus lock poisoned");
+        let offset = state.offsets.entry(message.topic.clone()).or_insert(0);
+        message.partition = Some(0);
+        message.offset = Some(*offset);
+        *offset += 1;
+
+        let Some(groups) = state.routes.get(&message.topic) else {
+            return Ok(());
+        };
+        for group in groups {
+            if let Some(group) = state.groups.get(group) {
+                let _ = group.tx.send(message.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn subscribe(
+        &self,
+        topics: &[&str],
+        group: &str,
+        _reset: OffsetReset,
+    ) -> Result<Box<dyn Subscription>, BusError> {
+        let mut state = self.state.lock().expect("memory bus lock poisoned");
+        let rx = Arc::clone(
+            &state
+                .groups
+                .entry(group.to_string())
+                .or_insert_with(|| {
+                    let (tx, rx) = mpsc::unbounded_channel();
+                    MemoryGroup {
+                        tx,
+                        rx: Arc::new(tokio::sync::Mutex::new(rx)),
+                    }
+                })
+                .rx,
+        );
+        for topic in topics {
+            let groups = state.routes.entry(topic.to_string()).or_default();
+            if !groups.iter().any(|g| g == group) {
+                groups.push(group.to_string());
+            }
+        }
+        Ok(Box::new(MemorySubscription { rx }))
+    }
+
+    async fn flush(&self, _timeout: Duration) -> Result<(), BusError> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), BusError> {
+        Ok(())
+    }
+}
+
+/// Members of a group share one queue, so each message reaches one of them
+struct MemorySubscription {
+    rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>>,
+}
+
+#[async_trait]
+impl Subscription for MemorySubscription {
+    async fn recv(&mut self) -> Option<Result<Message, BusError>> {
+        self.rx.lock().await.recv().await.map(Ok)
+    }
+
+    async fn commit(&mut self, _message: &Message) -> Result<(), BusError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::config::SignalAction;
+    use crate::shared::market_event::{EventPayload, Trade};
+    use crate::shared::money::Quantity;
+
+    fn signal(symbol: &str) -> TradeSignal {
+        TradeSignal {
+            symbol: symbol.parse().unwrap(),
+            qty: Quantity::from(10),
+            action: SignalAction::Buy,
+        }
+    }
+
+    #[tokio::test]
+    async fn memory_bus_delivers_published_messages() {
+        let bus = MemoryBus::new();
+        // Nobody is listening yet, so this one is dropped
+        bus.publish(Message::new("prices", "early")).await.unwrap();
+
+        let mut subscription = bus
+            .subscribe(&["prices"], "reader", OffsetReset::Latest)
+            .await
+            .unwrap();
+        bus.publish(
+            Message::new("prices", "first")
+                .with_key("AAPL")
+                .with_header("h", "v"),
+        )
+        .await
+        .unwrap();
+        bus.publish(Message::new("other", "elsewhere"))
+            .await
+            .unwrap();
+        bus.publish(Message::new("prices", "second")).await.unwrap();
+
+        let first = subscription.recv().await.unwrap().unwrap();
+        assert_eq!(first.payload, b"first");
+        assert_eq!(first.key.as_deref(), Some("AAPL"));
+        assert_eq!(first.header("h"), Some(&b"v"[..]));
+        assert_eq!((first.partition, first.offset), (Some(0), Some(1)));
+        subscription.commit(&first).await.unwrap();
+
+        let second = subscription.recv().await.unwrap().unwrap();
+        assert_eq!(second.payload, b"second");
+        assert_eq!(second.offset, Some(2));
+    }
+
+    #[tokio::test]
+    async fn every_group_gets_every_message_and_members_share_them() {
+        let bus = MemoryBus::new();
+        let mut storage = bus
+            .subscribe(&["t"], "storage", OffsetReset::Latest)
+            .await
+            .unwrap();
+        let mut strategy = bus
+            .subscribe(&["t"], "strategy", OffsetReset::Latest)
+            .await
+            .unwrap();
+        let mut strategy_peer = bus
+            .subscribe(&["t"], "strategy", OffsetReset::Latest)
+            .await
+            .unwrap();
+
+        for payload in ["a", "b"] {
+            bus.publish(Message::new("t", payload)).await.unwrap();
+        }
+
+        for group in [&mut storage, &mut strategy] {
+            assert_eq!(group.recv().await.unwrap().unwrap().payload, b"a");
+        }
+        assert_eq!(storage.recv().await.unwrap().unwrap().payload, b"b");
+        // The second member of "strategy" takes the message its peer has not read
+        assert_eq!(strategy_peer.recv().await.unwrap().unwrap().payload, b"b");
+    }
+
+    #[tokio::test]
+    async fn typed_topics_round_trip_with_their_correlation_id() {
+        let bus = Bus::new(Arc::new(MemoryBus::new()))
+            .with_format(MARKET_DATA.name, WireFormat::Flatbuffers);
+        let mut signals = bus.subscribe(&TRADE_SIGNALS, "execution").await.unwrap();
+        let mut events = bus.subscribe(&MARKET_DATA, "storage").await.unwrap();
+
+        let id = CorrelationId::new();
+        bus.publish_correlated(&TRADE_SIGNALS, &signal("AAPL250221C00200000"), &id)
+            .await
+            .unwrap();
+        let delivery = signals.recv().await.unwrap().unwrap();
+        let received = delivery.value.unwrap();
+        assert_eq!(received.symbol, signal("AAPL250221C00200000").symbol);
+        assert_eq!(received.qty, Quantity::from(10));
+        assert_eq!(delivery.message.correlation_id(), Some(id));
+        // Keyed by underlying so a stock and its options stay in order
+        assert_eq!(delivery.message.key.as_deref(), Some("AAPL"));
+        assert_eq!(
+            delivery.message.header(CODEC_HEADER),
+            Some(WireFormat::Json.header_value().as_bytes())
+        );
+
+        let event = MarketEvent::new(
+            "AAPL",
+            1_700_000_000_000_000_000,
+            EventPayload::Trade(Trade {
+                price: 187.5,
+                quantity: 100.0,
+                trade_id: 7,
+            }),
+        );
+        bus.publish(&MARKET_DATA, &event).await.unwrap();
+        let delivery = events.recv().await.unwrap().unwrap();
+        assert_eq!(
+            delivery.message.header(CODEC_HEADER),
+            Some(WireFormat::Flatbuffers.header_value().as_bytes())
+        );
+        assert!(delivery.message.correlation_id().is_none());
+        assert_eq!(delivery.value.unwrap(), event);
+
+        // Whatever cannot be decoded is still delivered, with the error
+        bus.transport()
+            .publish(Message::new(TRADE_SIGNALS.name, "not json"))
+            .await
+            .unwrap();
+        let delivery = signals.recv().await.unwrap().unwrap();
+        assert!(matches!(delivery.value, Err(BusError::Decode(_))));
+    }
+}
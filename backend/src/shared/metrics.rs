@@ -0,0 +1,139 @@
+use crate::shared::instrument::Instrument;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use prometheus::{
+    exponential_buckets, histogram_opts, register_histogram_vec, register_int_counter_vec,
+    HistogramVec, IntCounterVec, TextEncoder,
+};
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+// Process-wide metrics, exported in the Prometheus text format on
+// `GET /metrics` (see `runtime.rs`). Each agent is its own process and scrape
+// job, so series carry no agent label; an agent only exports the series it
+// touches.
+
+/// Messages taken in, by where they came from (a bus topic, or "feed" for the
+/// provider stream) and symbol as given by `symbol_label`
+pub static MESSAGES_RECEIVED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "optitrade_messages_received_total",
+        "Messages received, by source and symbol (options under their underlying)",
+        &["source", "symbol"]
+    )
+    .expect("metric registered once")
+});
+
+pub static MESSAGES_PUBLISHED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "optitrade_messages_published_total",
+        "Messages published to the bus, by topic and symbol (options under their underlying)",
+        &["topic", "symbol"]
+    )
+    .expect("metric registered once")
+});
+
+/// Messages that could not be parsed or decoded, by source as in `MESSAGES_RECEIVED`
+pub static PARSE_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "optitrade_parse_failures_total",
+        "Messages that failed to parse, by source",
+        &["source"]
+    )
+    .expect("metric registered once")
+});
+
+/// Until the broker acknowledges the message (acks=all on Kafka)
+pub static BUS_PUBLISH_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        histogram_opts!(
+            "optitrade_bus_publish_seconds",
+            "Time to publish a message to the bus, by topic",
+            latency_buckets()
+        ),
+        &["topic"]
+    )
+    .expect("metric registered once")
+});
+
+pub static DB_INSERT_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        histogram_opts!(
+            "optitrade_db_insert_seconds",
+            "Time to insert one batch of rows, by table",
+            latency_buckets()
+        ),
+        &["table"]
+    )
+    .expect("metric registered once")
+});
+
+pub static DB_INSERT_BATCH_SIZE: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        histogram_opts!(
+            "optitrade_db_insert_batch_size",
+            "Rows per insert, by table",
+            exponential_buckets(1.0, 2.0, 11).unwrap_or_default()
+        ),
+        &["table"]
+    )
+    .expect("metric registered once")
+});
+
+/// Orders by outcome: "placed" (accepted by the broker), "rejected" (stopped
+/// before the broker by the session gate or risk checks) or "failed" (refused
+/// by the broker, or the request failed)
+pub static ORDERS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "optitrade_orders_total",
+        "Orders by outcome: placed, rejected or failed",
+        &["outcome"]
+    )
+    .expect("metric registered once")
+});
+
+pub static RISK_REJECTIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "optitrade_risk_rejections_total",
+        "Orders rejected by risk checks, by rule",
+        &["rule"]
+    )
+    .expect("metric registered once")
+});
+
+/// Value of a `symbol` label. Option contracts are counted under their
+/// underlying: a polled chain adds thousands of contracts per underlying,
+/// which would be one series each.
+pub fn symbol_label(symbol: &str) -> Cow<'_, str> {
+    match symbol.parse::<Instrument>() {
+        Ok(Instrument::Option(option)) => Cow::Owned(option.underlying),
+        _ => Cow::Borrowed(symbol),
+    }
+}
+
+/// 0.5ms to about 4s
+fn latency_buckets() -> Vec<f64> {
+    exponential_buckets(0.0005, 2.0, 14).unwrap_or_default()
+}
+
+/// `GET /metrics` in the Prometheus text format, served by `AgentRuntime`
+pub async fn render() -> Response {
+    match TextEncoder::new().encode_to_string(&prometheus::gather()) {
+        Ok(body) => ([(CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_contracts_are_labelled_by_underlying() {
+        assert_eq!(symbol_label("AAPL250221C00200000"), "AAPL");
+        assert_eq!(symbol_label("AAPL"), "AAPL");
+        assert_eq!(symbol_label("BTC/USD"), "BTC/USD");
+        assert_eq!(symbol_label(""), "");
+    }
+}
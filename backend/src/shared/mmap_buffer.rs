@@ -1,47 +1,1042 @@
-use memmap2::{MmapMut, MmapOptions};
-use std::fs::OpenOptions;
-use std::sync::Mutex;
-use lazy_static::lazy_static;
+use crate::shared::market_event::{EventPayload, MarketEvent, Quote, Trade};
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::mem::size_of;
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
 
-const BUFFER_SIZE: usize = 10_000_000; // 10MB buffer for real-time market data
+// Single-producer / multi-consumer ring buffer in a memory-mapped file.
+//
+// Layout:
+//   [0..192)   header: magic, version, capacity, then the writer's counters
+//              each on its own cache line
+//   [192..)    data region of `capacity` bytes holding 16-byte aligned records
+//
+// Record: [len: u32][kind: u32][seq: u64][payload][padding to 16 bytes]
+//
+// Positions are logical byte offsets that only ever grow; the physical offset
+// is `pos % capacity`. A record never straddles the end of the data region:
+// the writer fills the remainder with a padding record and starts again at
+// offset 0.
+//
+// Before touching the data region the writer announces the end of what it is
+// about to write in `tail_intent`, and publishes `tail` once the record is
+// complete. Readers copy a record out and then re-check `tail_intent`: if the
+// writer has started writing more than `capacity` bytes past the record, it
+// may have been overwritten and the reader was lapped.
+//
+// A writer that dies mid-record leaves `tail_intent` ahead of `tail`. The next
+// writer overwrites that half-written span with skip records before
+// publishing past it, so readers never see the partial record.
+
+pub const DEFAULT_PATH: &str = "/tmp/market_data_buffer";
+pub const DEFAULT_CAPACITY: u64 = 10_000_000; // 10MB buffer for real-time market data
+
+const MAGIC: u64 = u64::from_le_bytes(*b"OTRING01");
+const VERSION: u32 = 2;
+
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 8;
+const CAPACITY_OFFSET: usize = 16;
+const TAIL_INTENT_OFFSET: usize = 64;
+const TAIL_OFFSET: usize = 128;
+const NEXT_SEQ_OFFSET: usize = 136;
+const HEADER_SIZE: usize = 192;
+
+const RECORD_HEADER_SIZE: u64 = 16;
+const RECORD_ALIGN: u64 = 16;
+const KIND_DATA: u32 = 1;
+const KIND_PADDING: u32 = 2;
+const KIND_SKIP: u32 = 3; // Covers `len` bytes abandoned by a crashed writer
+
+#[derive(Debug)]
+pub enum RingError {
+    Io(std::io::Error),
+    InvalidHeader(String),
+    TooLarge { len: usize, max: usize },
+    Lapped { expected_seq: u64 },
+    Corrupt { pos: u64 },
+}
+
+impl fmt::Display for RingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RingError::Io(e) => write!(f, "ring buffer I/O error: {}", e),
+            RingError::InvalidHeader(msg) => write!(f, "invalid ring buffer header: {}", msg),
+            RingError::TooLarge { len, max } => {
+                write!(
+                    f,
+                    "record of {} bytes exceeds the {} byte maximum",
+                    len, max
+                )
+            }
+            RingError::Lapped { expected_seq } => write!(
+                f,
+                "reader was lapped by the writer; records from seq {} were overwritten",
+                expected_seq
+            ),
+            RingError::Corrupt { pos } => write!(f, "corrupt record at position {}", pos),
+        }
+    }
+}
+
+impl std::error::Error for RingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RingError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RingError {
+    fn from(e: std::io::Error) -> Self {
+        RingError::Io(e)
+    }
+}
+
+/// A record copied out of the ring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record<'a> {
+    pub seq: u64,
+    pub payload: &'a [u8],
+}
+
+/// The only writer of a ring buffer file
+pub struct RingWriter {
+    mmap: MmapMut,
+    capacity: u64,
+    tail: u64,
+    next_seq: u64,
+}
+
+impl RingWriter {
+    /// Opens the ring at `path`, creating or re-initialising it if it does not
+    /// hold a ring of the same capacity. An existing ring resumes its sequence.
+    pub fn create(path: impl AsRef<Path>, capacity: u64) -> Result<Self, RingError> {
+        if !capacity.is_multiple_of(RECORD_ALIGN)
+            || capacity < 2 * RECORD_HEADER_SIZE
+            || capacity > u32::MAX as u64
+        {
+            return Err(RingError::InvalidHeader(format!(
+                "capacity {} must be a multiple of {} between {} and {}",
+                capacity,
+                RECORD_ALIGN,
+                2 * RECORD_HEADER_SIZE,
+                u32::MAX
+            )));
+        }
 
-lazy_static! {
-    static ref MMAP: Mutex<MmapMut> = {
         let file = OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
-            .open("/tmp/market_data_buffer")
-            .expect("Failed to open mmap buffer file");
+            .truncate(false)
+            .open(path)?;
+        let file_len = HEADER_SIZE as u64 + capacity;
+        let existing = file.metadata()?.len() == file_len;
+        if !existing {
+            file.set_len(file_len)?;
+        }
 
-        file.set_len(BUFFER_SIZE as u64).expect("Failed to set mmap file size");
+        let mmap = unsafe { MmapOptions::new().map_mut(&file)? };
+        let mut writer = RingWriter {
+            mmap,
+            capacity,
+            tail: 0,
+            next_seq: 0,
+        };
 
-        let mmap = unsafe { MmapOptions::new().map_mut(&file).expect("Failed to create mmap") };
-        Mutex::new(mmap)
-    };
+        let tail = writer.counter(TAIL_OFFSET).load(Ordering::Acquire);
+        let intent = writer.counter(TAIL_INTENT_OFFSET).load(Ordering::Acquire);
+        let resumable = existing
+            && writer.header_matches()
+            && intent >= tail
+            && intent - tail <= capacity
+            && (intent - tail).is_multiple_of(RECORD_ALIGN);
+        if resumable {
+            writer.tail = tail;
+            writer.next_seq = writer.counter(NEXT_SEQ_OFFSET).load(Ordering::Acquire);
+            if intent > tail {
+                writer.skip_to(intent);
+            }
+        } else {
+            writer.initialise();
+        }
+
+        Ok(writer)
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Sequence number the next record will be written with
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Largest payload a single record can hold
+    pub fn max_payload(&self) -> usize {
+        (self.capacity - RECORD_HEADER_SIZE) as usize
+    }
+
+    /// Appends a record and returns its sequence number
+    pub fn write(&mut self, payload: &[u8]) -> Result<u64, RingError> {
+        if payload.len() > self.max_payload() {
+            return Err(RingError::TooLarge {
+                len: payload.len(),
+                max: self.max_payload(),
+            });
+        }
+
+        let record_len = align(RECORD_HEADER_SIZE + payload.len() as u64);
+        let offset = self.tail % self.capacity;
+        let remaining = self.capacity - offset;
+        let padding = if record_len > remaining { remaining } else { 0 };
+        let new_tail = self.tail + padding + record_len;
+        let seq = self.next_seq;
+
+        // Announce the overwrite before any byte of the data region changes
+        self.counter(TAIL_INTENT_OFFSET)
+            .store(new_tail, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        if padding > 0 {
+            self.write_record_header(offset, padding as u32, KIND_PADDING, 0);
+        }
+        let offset = (self.tail + padding) % self.capacity;
+        self.write_record_header(offset, payload.len() as u32, KIND_DATA, seq);
+        unsafe {
+            ptr::copy_nonoverlapping(
+                payload.as_ptr(),
+                self.data_ptr().add((offset + RECORD_HEADER_SIZE) as usize),
+                payload.len(),
+            );
+        }
+
+        self.tail = new_tail;
+        self.next_seq = seq + 1;
+        self.counter(NEXT_SEQ_OFFSET)
+            .store(self.next_seq, Ordering::Release);
+        self.counter(TAIL_OFFSET).store(new_tail, Ordering::Release);
+
+        Ok(seq)
+    }
+
+    /// Replaces whatever a crashed writer left in `[tail, intent)` with skip
+    /// records and publishes `intent` as the new tail
+    fn skip_to(&mut self, intent: u64) {
+        while self.tail < intent {
+            let offset = self.tail % self.capacity;
+            let len = (intent - self.tail).min(self.capacity - offset);
+            self.write_record_header(offset, len as u32, KIND_SKIP, 0);
+            self.tail += len;
+        }
+        self.counter(TAIL_OFFSET).store(intent, Ordering::Release);
+    }
+
+    fn header_matches(&self) -> bool {
+        read_u64(&self.mmap, MAGIC_OFFSET) == MAGIC
+            && read_u32(&self.mmap, VERSION_OFFSET) == VERSION
+            && read_u64(&self.mmap, CAPACITY_OFFSET) == self.capacity
+    }
+
+    fn initialise(&mut self) {
+        // Invalidate first so readers never accept a half-written header
+        self.counter(MAGIC_OFFSET).store(0, Ordering::Release);
+        self.mmap[VERSION_OFFSET..VERSION_OFFSET + 4].copy_from_slice(&VERSION.to_le_bytes());
+        self.mmap[CAPACITY_OFFSET..CAPACITY_OFFSET + 8]
+            .copy_from_slice(&self.capacity.to_le_bytes());
+        self.counter(TAIL_INTENT_OFFSET).store(0, Ordering::Relaxed);
+        self.counter(TAIL_OFFSET).store(0, Ordering::Relaxed);
+        self.counter(NEXT_SEQ_OFFSET).store(0, Ordering::Relaxed);
+        self.counter(MAGIC_OFFSET).store(MAGIC, Ordering::Release);
+    }
+
+    fn write_record_header(&mut self, offset: u64, len: u32, kind: u32, seq: u64) {
+        let mut header = [0u8; RECORD_HEADER_SIZE as usize];
+        header[0..4].copy_from_slice(&len.to_le_bytes());
+        header[4..8].copy_from_slice(&kind.to_le_bytes());
+        header[8..16].copy_from_slice(&seq.to_le_bytes());
+        unsafe {
+            ptr::copy_nonoverlapping(
+                header.as_ptr(),
+                self.data_ptr().add(offset as usize),
+                header.len(),
+            );
+        }
+    }
+
+    fn data_ptr(&mut self) -> *mut u8 {
+        unsafe { self.mmap.as_mut_ptr().add(HEADER_SIZE) }
+    }
+
+    fn counter(&self, offset: usize) -> &AtomicU64 {
+        counter(self.mmap.as_ptr(), offset)
+    }
 }
 
-/// Synchronous function to write data to mmap buffer
-pub fn write_to_mmap(data: &str) {
-    let mut mmap = MMAP.lock().expect("Failed to acquire mmap lock");
-    
-    let bytes = data.as_bytes();
-    let len = bytes.len().min(mmap.len());
+/// Follows a ring buffer written by another thread or process
+pub struct RingReader {
+    mmap: Mmap,
+    capacity: u64,
+    pos: u64,
+    next_seq: u64,
+    buf: Vec<u8>,
+}
 
-    mmap[..len].copy_from_slice(&bytes[..len]);
-    mmap.flush().expect("Failed to flush mmap buffer");
+impl RingReader {
+    /// Opens the ring at `path`, positioned at the writer's current tail
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RingError> {
+        let file = File::open(path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        if mmap.len() < HEADER_SIZE {
+            return Err(RingError::InvalidHeader(
+                "file is shorter than the header".into(),
+            ));
+        }
+        if counter(mmap.as_ptr(), MAGIC_OFFSET).load(Ordering::Acquire) != MAGIC {
+            return Err(RingError::InvalidHeader("bad magic".into()));
+        }
+        let version = read_u32(&mmap, VERSION_OFFSET);
+        if version != VERSION {
+            return Err(RingError::InvalidHeader(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+        let capacity = read_u64(&mmap, CAPACITY_OFFSET);
+        if capacity == 0 || (mmap.len() as u64) < HEADER_SIZE as u64 + capacity {
+            return Err(RingError::InvalidHeader(format!(
+                "capacity {} does not match file length {}",
+                capacity,
+                mmap.len()
+            )));
+        }
 
-    println!("[MarketData] ✅ Data written to mmap.");
+        let mut reader = RingReader {
+            mmap,
+            capacity,
+            pos: 0,
+            next_seq: 0,
+            buf: Vec::new(),
+        };
+        reader.seek_to_tail();
+        Ok(reader)
+    }
+
+    /// Sequence number the reader expects next
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Records written but not yet read; more than `capacity` means lapped
+    pub fn lag_bytes(&self) -> u64 {
+        self.counter(TAIL_OFFSET)
+            .load(Ordering::Acquire)
+            .saturating_sub(self.pos)
+    }
+
+    /// Returns the next record, or `None` when caught up with the writer.
+    ///
+    /// On `Lapped` or `Corrupt` the reader has already skipped to the writer's
+    /// tail, so callers can log the gap and keep reading.
+    pub fn read_next(&mut self) -> Result<Option<Record<'_>>, RingError> {
+        loop {
+            let tail = self.counter(TAIL_OFFSET).load(Ordering::Acquire);
+            if self.pos == tail {
+                return Ok(None);
+            }
+            if tail.wrapping_sub(self.pos) > self.capacity {
+                return Err(self.lapped());
+            }
+
+            let offset = self.pos % self.capacity;
+            let mut header = [0u8; RECORD_HEADER_SIZE as usize];
+            self.copy_out(offset, &mut header);
+            if !self.intact() {
+                return Err(self.lapped());
+            }
+
+            let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as u64;
+            let kind = u32::from_le_bytes(header[4..8].try_into().unwrap());
+            let seq = u64::from_le_bytes(header[8..16].try_into().unwrap());
+
+            match kind {
+                KIND_PADDING if len == self.capacity - offset => {
+                    self.pos += len;
+                    continue;
+                }
+                KIND_SKIP
+                    if len >= RECORD_HEADER_SIZE
+                        && len.is_multiple_of(RECORD_ALIGN)
+                        && len <= self.capacity - offset =>
+                {
+                    self.pos += len;
+                    continue;
+                }
+                KIND_DATA if align(RECORD_HEADER_SIZE + len) <= self.capacity - offset => {}
+                _ => {
+                    // The length can't be trusted, so resync at the writer's tail
+                    let pos = self.pos;
+                    self.seek_to_tail();
+                    return Err(RingError::Corrupt { pos });
+                }
+            }
+
+            let mut buf = std::mem::take(&mut self.buf);
+            buf.resize(len as usize, 0);
+            self.copy_out(offset + RECORD_HEADER_SIZE, &mut buf);
+            self.buf = buf;
+            if !self.intact() {
+                return Err(self.lapped());
+            }
+
+            self.pos += align(RECORD_HEADER_SIZE + len);
+            self.next_seq = seq + 1;
+            return Ok(Some(Record {
+                seq,
+                payload: &self.buf,
+            }));
+        }
+    }
+
+    /// Whether the bytes copied since `pos` can still be trusted
+    fn intact(&self) -> bool {
+        fence(Ordering::Acquire);
+        let intent = self.counter(TAIL_INTENT_OFFSET).load(Ordering::Relaxed);
+        // A re-initialised ring moves intent backwards, which also fails here
+        intent.wrapping_sub(self.pos) <= self.capacity
+    }
+
+    fn lapped(&mut self) -> RingError {
+        let expected_seq = self.next_seq;
+        self.seek_to_tail();
+        RingError::Lapped { expected_seq }
+    }
+
+    fn seek_to_tail(&mut self) {
+        // Only a hint if the writer races us; the next record read corrects it
+        self.next_seq = self.counter(NEXT_SEQ_OFFSET).load(Ordering::Acquire);
+        self.pos = self.counter(TAIL_OFFSET).load(Ordering::Acquire);
+    }
+
+    fn copy_out(&self, offset: u64, dst: &mut [u8]) {
+        // The writer may be overwriting these bytes concurrently; the copy is
+        // only trusted after `intact` confirms it was not lapped
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.mmap.as_ptr().add(HEADER_SIZE + offset as usize),
+                dst.as_mut_ptr(),
+                dst.len(),
+            );
+        }
+    }
+
+    fn counter(&self, offset: usize) -> &AtomicU64 {
+        counter(self.mmap.as_ptr(), offset)
+    }
 }
 
-/// Reads latest market data from mmap buffer
-pub fn read_from_mmap() -> String {
-    let mmap = MMAP.lock().expect("Failed to acquire mmap lock");
-    
-    let content = String::from_utf8_lossy(&mmap[..]);
-    let trimmed = content.trim_matches(char::from(0)).to_string(); // Remove null bytes
+// Latest quote and trade per symbol, for processes that only need "the
+// current NBBO for AAPL" rather than the stream.
+//
+// Layout:
+//   [0..128)   header: magic, version, slot count (a power of two)
+//   [128..)    slots of 128 bytes, one cache line pair each
+//
+// Symbols are placed by open addressing on their FNV-1a hash and never move
+// once claimed. Every slot field is an atomic word guarded by the slot's
+// seqlock: the writer makes `seq` odd, updates the fields and makes it even
+// again; readers retry until they see the same even `seq` on both sides of
+// their copy, giving up after `MAX_READ_RETRIES` attempts. A writer that dies
+// mid-update leaves `seq` odd; the next writer to open the table bumps it back
+// to even.
+
+pub const DEFAULT_SNAPSHOT_PATH: &str = "/tmp/market_data_snapshots";
+pub const DEFAULT_SNAPSHOT_SLOTS: usize = 4096;
+pub const MAX_SYMBOL_LEN: usize = 32;
+
+const SNAPSHOT_MAGIC: u64 = u64::from_le_bytes(*b"OTSNAP01");
+const SNAPSHOT_VERSION: u32 = 2;
+const SLOT_COUNT_OFFSET: usize = 16;
+const SNAPSHOT_HEADER_SIZE: usize = size_of::<Slot>();
+const SYMBOL_WORDS: usize = MAX_SYMBOL_LEN / 8;
+/// Attempts at a consistent copy of a slot before a reader gives up on it
+const MAX_READ_RETRIES: u32 = 100_000;
+
+#[repr(C, align(128))]
+struct Slot {
+    seq: AtomicU64,
+    symbol: [AtomicU64; SYMBOL_WORDS],
+    bid_price: AtomicU64,
+    ask_price: AtomicU64,
+    bid_size: AtomicU64,
+    ask_size: AtomicU64,
+    quote_ts_ns: AtomicU64,
+    last_price: AtomicU64,
+    last_quantity: AtomicU64,
+    trade_ts_ns: AtomicU64,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    InvalidHeader(String),
+    InvalidSymbol(String),
+    TableFull(usize),
+    Contended { slot: usize },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "snapshot table I/O error: {}", e),
+            SnapshotError::InvalidHeader(msg) => {
+                write!(f, "invalid snapshot table header: {}", msg)
+            }
+            SnapshotError::InvalidSymbol(symbol) => write!(
+                f,
+                "symbol '{}' must be 1 to {} bytes",
+                symbol, MAX_SYMBOL_LEN
+            ),
+            SnapshotError::TableFull(slots) => {
+                write!(f, "snapshot table is full ({} symbols)", slots)
+            }
+            SnapshotError::Contended { slot } => write!(
+                f,
+                "no consistent read of slot {} after {} attempts",
+                slot, MAX_READ_RETRIES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(e: std::io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+/// Consistent copy of one symbol's latest quote and trade; timestamps are
+/// Unix nanoseconds and zero until the first update of that kind
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SymbolSnapshot {
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub bid_size: f64,
+    pub ask_size: f64,
+    pub quote_ts_ns: u64,
+    pub last_price: f64,
+    pub last_quantity: f64,
+    pub trade_ts_ns: u64,
+}
+
+impl SymbolSnapshot {
+    pub fn has_quote(&self) -> bool {
+        self.quote_ts_ns != 0
+    }
+
+    pub fn has_trade(&self) -> bool {
+        self.trade_ts_ns != 0
+    }
+
+    /// Midpoint of the quote, if both sides are present
+    pub fn mid(&self) -> Option<f64> {
+        (self.has_quote() && self.bid_price > 0.0 && self.ask_price > 0.0)
+            .then(|| (self.bid_price + self.ask_price) / 2.0)
+    }
+}
+
+/// The only writer of a snapshot table file
+pub struct SnapshotWriter {
+    mmap: MmapMut,
+    slots: usize,
+    index: HashMap<String, usize>,
+}
+
+impl SnapshotWriter {
+    /// Opens the table at `path`, keeping existing entries if it already
+    /// holds a table with the same slot count
+    pub fn create(path: impl AsRef<Path>, slots: usize) -> Result<Self, SnapshotError> {
+        if !slots.is_power_of_two() {
+            return Err(SnapshotError::InvalidHeader(format!(
+                "slot count {} must be a power of two",
+                slots
+            )));
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let file_len = (SNAPSHOT_HEADER_SIZE + slots * size_of::<Slot>()) as u64;
+        let existing = file.metadata()?.len() == file_len;
+        if !existing {
+            file.set_len(file_len)?;
+        }
+
+        let mmap = unsafe { MmapOptions::new().map_mut(&file)? };
+        let mut writer = SnapshotWriter {
+            mmap,
+            slots,
+            index: HashMap::new(),
+        };
+
+        if existing
+            && read_u64(&writer.mmap, MAGIC_OFFSET) == SNAPSHOT_MAGIC
+            && read_u32(&writer.mmap, VERSION_OFFSET) == SNAPSHOT_VERSION
+            && read_u64(&writer.mmap, SLOT_COUNT_OFFSET) == slots as u64
+        {
+            for i in 0..slots {
+                let slot = slot(writer.mmap.as_ptr(), i);
+                // A previous writer died inside `write_locked`; release the
+                // lock so the slot's readers stop waiting for it
+                let seq = slot.seq.load(Ordering::Relaxed);
+                if seq & 1 == 1 {
+                    slot.seq.store(seq + 1, Ordering::Release);
+                }
+                if let Some(symbol) = decode_symbol(&load_symbol(slot)) {
+                    writer.index.insert(symbol, i);
+                }
+            }
+        } else {
+            counter(writer.mmap.as_ptr(), MAGIC_OFFSET).store(0, Ordering::Release);
+            writer.mmap[SNAPSHOT_HEADER_SIZE..].fill(0);
+            writer.mmap[VERSION_OFFSET..VERSION_OFFSET + 4]
+                .copy_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+            writer.mmap[SLOT_COUNT_OFFSET..SLOT_COUNT_OFFSET + 8]
+                .copy_from_slice(&(slots as u64).to_le_bytes());
+            counter(writer.mmap.as_ptr(), MAGIC_OFFSET).store(SNAPSHOT_MAGIC, Ordering::Release);
+        }
+
+        Ok(writer)
+    }
+
+    /// Number of symbols currently in the table
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Records the event if it is a quote or trade; other payloads are ignored
+    pub fn apply(&mut self, event: &MarketEvent) -> Result<(), SnapshotError> {
+        match &event.payload {
+            EventPayload::Quote(quote) => {
+                self.update_quote(&event.symbol, quote, event.exchange_ts_ns)
+            }
+            EventPayload::Trade(trade) => {
+                self.update_trade(&event.symbol, trade, event.exchange_ts_ns)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn update_quote(
+        &mut self,
+        symbol: &str,
+        quote: &Quote,
+        ts_ns: u64,
+    ) -> Result<(), SnapshotError> {
+        let slot = self.slot_for(symbol)?;
+        write_locked(slot, |slot| {
+            store_f64(&slot.bid_price, quote.bid_price);
+            store_f64(&slot.ask_price, quote.ask_price);
+            store_f64(&slot.bid_size, quote.bid_size);
+            store_f64(&slot.ask_size, quote.ask_size);
+            slot.quote_ts_ns.store(ts_ns, Ordering::Relaxed);
+        });
+        Ok(())
+    }
+
+    pub fn update_trade(
+        &mut self,
+        symbol: &str,
+        trade: &Trade,
+        ts_ns: u64,
+    ) -> Result<(), SnapshotError> {
+        let slot = self.slot_for(symbol)?;
+        write_locked(slot, |slot| {
+            store_f64(&slot.last_price, trade.price);
+            store_f64(&slot.last_quantity, trade.quantity);
+            slot.trade_ts_ns.store(ts_ns, Ordering::Relaxed);
+        });
+        Ok(())
+    }
+
+    /// Finds the symbol's slot, claiming a free one on first sight
+    fn slot_for(&mut self, symbol: &str) -> Result<&Slot, SnapshotError> {
+        if let Some(&i) = self.index.get(symbol) {
+            return Ok(slot(self.mmap.as_ptr(), i));
+        }
+
+        let words = encode_symbol(symbol)?;
+        let mask = self.slots - 1;
+        let start = fnv1a(symbol.as_bytes()) as usize;
+        for probe in 0..self.slots {
+            let i = (start + probe) & mask;
+            let slot = slot(self.mmap.as_ptr(), i);
+            if load_symbol(slot) == [0; SYMBOL_WORDS] {
+                write_locked(slot, |slot| {
+                    for (word, value) in slot.symbol.iter().zip(words) {
+                        word.store(value, Ordering::Relaxed);
+                    }
+                });
+                self.index.insert(symbol.to_string(), i);
+                return Ok(slot);
+            }
+        }
+        Err(SnapshotError::TableFull(self.slots))
+    }
+}
+
+/// Lock-free reader of a snapshot table written by another process
+pub struct SnapshotReader {
+    mmap: Mmap,
+    slots: usize,
+}
+
+impl SnapshotReader {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let file = File::open(path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        if mmap.len() < SNAPSHOT_HEADER_SIZE {
+            return Err(SnapshotError::InvalidHeader(
+                "file is shorter than the header".into(),
+            ));
+        }
+        if counter(mmap.as_ptr(), MAGIC_OFFSET).load(Ordering::Acquire) != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidHeader("bad magic".into()));
+        }
+        let version = read_u32(&mmap, VERSION_OFFSET);
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::InvalidHeader(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+        let slots = read_u64(&mmap, SLOT_COUNT_OFFSET) as usize;
+        if !slots.is_power_of_two() || mmap.len() < SNAPSHOT_HEADER_SIZE + slots * size_of::<Slot>()
+        {
+            return Err(SnapshotError::InvalidHeader(format!(
+                "slot count {} does not match file length {}",
+                slots,
+                mmap.len()
+            )));
+        }
+
+        Ok(SnapshotReader { mmap, slots })
+    }
+
+    /// Latest quote and trade for `symbol`, or `None` if it has never been seen
+    pub fn get(&self, symbol: &str) -> Result<Option<SymbolSnapshot>, SnapshotError> {
+        let Ok(words) = encode_symbol(symbol) else {
+            return Ok(None);
+        };
+        let mask = self.slots - 1;
+        let start = fnv1a(symbol.as_bytes()) as usize;
+        for probe in 0..self.slots {
+            let i = (start + probe) & mask;
+            let (slot_symbol, snapshot) = read_consistent(slot(self.mmap.as_ptr(), i))
+                .ok_or(SnapshotError::Contended { slot: i })?;
+            if slot_symbol == words {
+                return Ok(Some(snapshot));
+            }
+            if slot_symbol == [0; SYMBOL_WORDS] {
+                return Ok(None);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Every symbol in the table with its latest snapshot
+    pub fn all(&self) -> Result<Vec<(String, SymbolSnapshot)>, SnapshotError> {
+        let mut snapshots = Vec::new();
+        for i in 0..self.slots {
+            let (words, snapshot) = read_consistent(slot(self.mmap.as_ptr(), i))
+                .ok_or(SnapshotError::Contended { slot: i })?;
+            if let Some(symbol) = decode_symbol(&words) {
+                snapshots.push((symbol, snapshot));
+            }
+        }
+        Ok(snapshots)
+    }
+}
+
+fn write_locked(slot: &Slot, update: impl FnOnce(&Slot)) {
+    let seq = slot.seq.load(Ordering::Relaxed);
+    slot.seq.store(seq + 1, Ordering::Relaxed);
+    fence(Ordering::Release);
+    update(slot);
+    slot.seq.store(seq + 2, Ordering::Release);
+}
+
+/// `None` if the writer held the slot for every one of `MAX_READ_RETRIES` attempts
+fn read_consistent(slot: &Slot) -> Option<([u64; SYMBOL_WORDS], SymbolSnapshot)> {
+    for _ in 0..MAX_READ_RETRIES {
+        let before = slot.seq.load(Ordering::Acquire);
+        if before & 1 == 1 {
+            std::hint::spin_loop();
+            continue;
+        }
+
+        let symbol = load_symbol(slot);
+        let snapshot = SymbolSnapshot {
+            bid_price: load_f64(&slot.bid_price),
+            ask_price: load_f64(&slot.ask_price),
+            bid_size: load_f64(&slot.bid_size),
+            ask_size: load_f64(&slot.ask_size),
+            quote_ts_ns: slot.quote_ts_ns.load(Ordering::Relaxed),
+            last_price: load_f64(&slot.last_price),
+            last_quantity: load_f64(&slot.last_quantity),
+            trade_ts_ns: slot.trade_ts_ns.load(Ordering::Relaxed),
+        };
+
+        fence(Ordering::Acquire);
+        if slot.seq.load(Ordering::Relaxed) == before {
+            return Some((symbol, snapshot));
+        }
+    }
+    None
+}
+
+fn slot<'a>(base: *const u8, index: usize) -> &'a Slot {
+    // The header is one slot long, so every slot keeps the 128-byte alignment
+    // of `Slot` within the page-aligned mapping
+    unsafe { &*(base.add(SNAPSHOT_HEADER_SIZE + index * size_of::<Slot>()) as *const Slot) }
+}
+
+fn load_symbol(slot: &Slot) -> [u64; SYMBOL_WORDS] {
+    std::array::from_fn(|i| slot.symbol[i].load(Ordering::Relaxed))
+}
+
+fn encode_symbol(symbol: &str) -> Result<[u64; SYMBOL_WORDS], SnapshotError> {
+    let bytes = symbol.as_bytes();
+    if bytes.is_empty() || bytes.len() > MAX_SYMBOL_LEN || bytes.contains(&0) {
+        return Err(SnapshotError::InvalidSymbol(symbol.to_string()));
+    }
+    let mut padded = [0u8; MAX_SYMBOL_LEN];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    Ok(std::array::from_fn(|i| read_u64(&padded, i * 8)))
+}
+
+fn decode_symbol(words: &[u64; SYMBOL_WORDS]) -> Option<String> {
+    let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    (len > 0).then(|| String::from_utf8_lossy(&bytes[..len]).into_owned())
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn store_f64(word: &AtomicU64, value: f64) {
+    word.store(value.to_bits(), Ordering::Relaxed);
+}
+
+fn load_f64(word: &AtomicU64) -> f64 {
+    f64::from_bits(word.load(Ordering::Relaxed))
+}
+
+fn align(len: u64) -> u64 {
+    (len + RECORD_ALIGN - 1) & !(RECORD_ALIGN - 1)
+}
+
+fn counter<'a>(base: *const u8, offset: usize) -> &'a AtomicU64 {
+    // Mappings are page aligned and every counter sits on an 8-byte boundary
+    unsafe { &*(base.add(offset) as *const AtomicU64) }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("optitrade_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn ring_delivers_records_in_order_across_wraps() {
+        let path = temp_path("ring_wrap");
+        let mut writer = RingWriter::create(&path, 256).unwrap();
+        let mut reader = RingReader::open(&path).unwrap();
+        for round in 0u8..20 {
+            let payload = vec![round; 40];
+            let seq = writer.write(&payload).unwrap();
+            let record = reader.read_next().unwrap().unwrap();
+            assert_eq!(record.seq, seq);
+            assert_eq!(record.payload, &payload[..]);
+        }
+        assert_eq!(reader.read_next().unwrap(), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ring_reports_a_lapped_reader_and_resumes_at_the_tail() {
+        let path = temp_path("ring_lapped");
+        let mut writer = RingWriter::create(&path, 256).unwrap();
+        let mut reader = RingReader::open(&path).unwrap();
+        for _ in 0..10 {
+            writer.write(&[7; 40]).unwrap();
+        }
+        assert!(matches!(
+            reader.read_next(),
+            Err(RingError::Lapped { expected_seq: 0 })
+        ));
+        let seq = writer.write(b"after").unwrap();
+        assert_eq!(reader.read_next().unwrap().unwrap().seq, seq);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn resumed_ring_skips_a_record_left_half_written() {
+        let path = temp_path("ring_resume");
+        let mut writer = RingWriter::create(&path, 256).unwrap();
+        let mut reader = RingReader::open(&path).unwrap();
+        writer.write(b"complete").unwrap();
+        // Simulate a writer killed after announcing a record but before
+        // finishing it: garbage header, tail_intent ahead of tail
+        let tail = writer.tail;
+        writer.write_record_header(tail % 256, 9_999, 0xdead, 42);
+        writer
+            .counter(TAIL_INTENT_OFFSET)
+            .store(tail + 48, Ordering::Release);
+        drop(writer);
+
+        let mut writer = RingWriter::create(&path, 256).unwrap();
+        assert_eq!(
+            writer.counter(TAIL_OFFSET).load(Ordering::Acquire),
+            tail + 48
+        );
+        let seq = writer.write(b"resumed").unwrap();
+
+        assert_eq!(reader.read_next().unwrap().unwrap().payload, b"complete");
+        let record = reader.read_next().unwrap().unwrap();
+        assert_eq!((record.seq, record.payload), (seq, &b"resumed"[..]));
+        assert_eq!(reader.read_next().unwrap(), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reader_resyncs_past_a_corrupt_record() {
+        let path = temp_path("ring_corrupt");
+        let mut writer = RingWriter::create(&path, 256).unwrap();
+        let mut reader = RingReader::open(&path).unwrap();
+        writer.write(b"first").unwrap();
+        writer.write_record_header(0, 5, 0xdead, 0);
+        assert!(matches!(
+            reader.read_next(),
+            Err(RingError::Corrupt { pos: 0 })
+        ));
+        writer.write(b"second").unwrap();
+        assert_eq!(reader.read_next().unwrap().unwrap().payload, b"second");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn snapshot_slots_are_aligned() {
+        assert_eq!(SNAPSHOT_HEADER_SIZE % std::mem::align_of::<Slot>(), 0);
+    }
+
+    #[test]
+    fn snapshot_round_trips_quotes_and_trades() {
+        let path = temp_path("snapshot_round_trip");
+        let mut writer = SnapshotWriter::create(&path, 16).unwrap();
+        let quote = Quote {
+            bid_price: 189.5,
+            ask_price: 189.75,
+            bid_size: 100.0,
+            ask_size: 200.0,
+        };
+        writer.update_quote("AAPL", &quote, 1_000).unwrap();
+        writer
+            .update_trade(
+                "AAPL",
+                &Trade {
+                    price: 189.6,
+                    quantity: 50.0,
+                    trade_id: 1,
+                },
+                2_000,
+            )
+            .unwrap();
+        writer.update_quote("TSLA", &quote, 3_000).unwrap();
+
+        let reader = SnapshotReader::open(&path).unwrap();
+        let aapl = reader.get("AAPL").unwrap().unwrap();
+        assert_eq!(aapl.bid_price, 189.5);
+        assert_eq!(aapl.ask_size, 200.0);
+        assert_eq!(aapl.quote_ts_ns, 1_000);
+        assert_eq!(aapl.last_price, 189.6);
+        assert_eq!(aapl.trade_ts_ns, 2_000);
+        assert_eq!(aapl.mid(), Some(189.625));
+        assert!(!reader.get("TSLA").unwrap().unwrap().has_trade());
+        assert_eq!(reader.get("NVDA").unwrap(), None);
+        assert_eq!(reader.all().unwrap().len(), 2);
+
+        // Reopening keeps the existing entries
+        drop(writer);
+        let writer = SnapshotWriter::create(&path, 16).unwrap();
+        assert_eq!(writer.len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reopening_releases_slots_left_locked_by_a_dead_writer() {
+        let path = temp_path("snapshot_stale_lock");
+        let mut writer = SnapshotWriter::create(&path, 16).unwrap();
+        let quote = Quote {
+            bid_price: 10.0,
+            ask_price: 10.5,
+            bid_size: 1.0,
+            ask_size: 1.0,
+        };
+        writer.update_quote("AAPL", &quote, 1_000).unwrap();
+        let i = writer.index["AAPL"];
+        // Simulate a writer killed inside `write_locked`
+        let seq = &slot(writer.mmap.as_ptr(), i).seq;
+        seq.fetch_add(1, Ordering::Relaxed);
+        drop(writer);
+
+        let reader = SnapshotReader::open(&path).unwrap();
+        assert!(matches!(
+            reader.get("AAPL"),
+            Err(SnapshotError::Contended { slot }) if slot == i
+        ));
 
-    println!("[MarketData] 📖 Read from mmap: {}", trimmed);
-    
-    trimmed
+        let _writer = SnapshotWriter::create(&path, 16).unwrap();
+        assert_eq!(reader.get("AAPL").unwrap().unwrap().bid_price, 10.0);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -1,5 +1,20 @@
+pub mod calendar;
+pub mod clock;
+pub mod codec;
 pub mod config;
+pub mod corporate_actions;
 pub mod data_loader;
-pub mod kafka_producer;
+pub mod dead_letter;
+pub mod features;
+pub mod instrument;
+#[allow(clippy::all, mismatched_lifetime_syntaxes)]
 pub mod market_data_generated;
+pub mod market_event;
+pub mod message_bus;
+pub mod metrics;
 pub mod mmap_buffer;
+pub mod money;
+pub mod runtime;
+pub mod secrets;
+pub mod subscription;
+pub mod telemetry;
@@ -0,0 +1,370 @@
+use postgres_types::{FromSql, ToSql};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+// Prices, quantities and cash are decimals, so a fill at 187.31 is exactly
+// 187.31 and a day of trades sums to the cent. Each is its own type so a
+// quantity can't be added to a price by accident; `Price * Quantity` is the
+// one way to get `Money`.
+//
+// On the wire they serialize as decimal strings ("187.31") and accept either
+// strings or JSON numbers. In Postgres they map to NUMERIC. Analytics such as
+// moving averages stay `f64` and convert at the edge with `to_f64`.
+
+/// A per-unit price (per share, per contract, per coin)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSql,
+    FromSql,
+)]
+#[serde(transparent)]
+#[postgres(transparent)]
+pub struct Price(Decimal);
+
+/// A signed number of units; fractional for fractional shares and crypto
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSql,
+    FromSql,
+)]
+#[serde(transparent)]
+#[postgres(transparent)]
+pub struct Quantity(Decimal);
+
+/// A signed cash amount in the account currency
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSql,
+    FromSql,
+)]
+#[serde(transparent)]
+#[postgres(transparent)]
+pub struct Money(Decimal);
+
+/// Error parsing a `Price`, `Quantity` or `Money`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAmountError(String);
+
+macro_rules! decimal_newtype {
+    ($name:ident) => {
+        impl $name {
+            pub const ZERO: $name = $name(Decimal::ZERO);
+
+            pub const fn new(value: Decimal) -> Self {
+                $name(value)
+            }
+
+            /// Converts a vendor float, keeping the digits it prints with
+            /// (`187.31_f64` becomes exactly 187.31); `None` for NaN or infinity
+            pub fn from_f64(value: f64) -> Option<Self> {
+                if !value.is_finite() {
+                    return None;
+                }
+                // `Display` gives the shortest string that round-trips, which is what the vendor sent
+                Decimal::from_str(&value.to_string())
+                    .ok()
+                    .or_else(|| Decimal::from_f64(value))
+                    .map(|d| $name(d.normalize()))
+            }
+
+            pub fn as_decimal(&self) -> Decimal {
+                self.0
+            }
+
+            /// Lossy conversion for analytics
+            pub fn to_f64(&self) -> f64 {
+                self.0.to_f64().unwrap_or_default()
+            }
+
+            pub fn is_zero(&self) -> bool {
+                self.0.is_zero()
+            }
+
+            pub fn is_sign_negative(&self) -> bool {
+                self.0.is_sign_negative() && !self.0.is_zero()
+            }
+
+            pub fn abs(&self) -> Self {
+                $name(self.0.abs())
+            }
+        }
+
+        impl From<Decimal> for $name {
+            fn from(value: Decimal) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(value: i64) -> Self {
+                $name(Decimal::from(value))
+            }
+        }
+
+        impl From<$name> for Decimal {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseAmountError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Decimal::from_str_exact(s.trim()).map($name).map_err(|e| {
+                    ParseAmountError(format!(
+                        "invalid {} '{}': {}",
+                        stringify!($name).to_lowercase(),
+                        s,
+                        e
+                    ))
+                })
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, rhs: $name) -> $name {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, rhs: $name) -> $name {
+                $name(self.0 - rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: $name) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: $name) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> $name {
+                iter.fold($name::ZERO, Add::add)
+            }
+        }
+    };
+}
+
+decimal_newtype!(Price);
+decimal_newtype!(Quantity);
+decimal_newtype!(Money);
+
+impl Price {
+    /// One cent, the minimum increment for US equities quoted at $1.00 or more
+    pub const CENT: Price = Price(Decimal::from_parts(1, 0, 0, false, 2));
+    /// The minimum increment for US equities quoted below $1.00
+    pub const SUB_DOLLAR_TICK: Price = Price(Decimal::from_parts(1, 0, 0, false, 4));
+
+    /// The Reg NMS Rule 612 tick for a US equity at this price
+    pub fn us_equity_tick(&self) -> Price {
+        if self.0 >= Decimal::ONE {
+            Price::CENT
+        } else {
+            Price::SUB_DOLLAR_TICK
+        }
+    }
+
+    /// Nearest multiple of `tick`, halves away from zero; unchanged if `tick` is not positive
+    pub fn round_to_tick(&self, tick: Price) -> Price {
+        self.to_tick(tick, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    /// Largest multiple of `tick` not above this price (the side a buy limit rounds to)
+    pub fn floor_to_tick(&self, tick: Price) -> Price {
+        self.to_tick(tick, RoundingStrategy::ToNegativeInfinity)
+    }
+
+    /// Smallest multiple of `tick` not below this price (the side a sell limit rounds to)
+    pub fn ceil_to_tick(&self, tick: Price) -> Price {
+        self.to_tick(tick, RoundingStrategy::ToPositiveInfinity)
+    }
+
+    fn to_tick(self, tick: Price, strategy: RoundingStrategy) -> Price {
+        if tick.0 <= Decimal::ZERO {
+            return self;
+        }
+        let ticks = (self.0 / tick.0).round_dp_with_strategy(0, strategy);
+        let mut rounded = ticks * tick.0;
+        rounded.rescale(tick.0.scale());
+        Price(rounded)
+    }
+}
+
+impl Quantity {
+    /// Whether this is a whole number of units
+    pub fn is_whole(&self) -> bool {
+        self.0.fract().is_zero()
+    }
+}
+
+impl Money {
+    /// Rounded to whole cents, halves away from zero
+    pub fn round_cents(&self) -> Money {
+        Money(
+            self.0
+                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero),
+        )
+    }
+}
+
+impl Mul<Quantity> for Price {
+    type Output = Money;
+
+    /// Exact notional; round with `Money::round_cents` where cash actually moves
+    fn mul(self, rhs: Quantity) -> Money {
+        Money(self.0 * rhs.0)
+    }
+}
+
+impl Mul<Price> for Quantity {
+    type Output = Money;
+
+    fn mul(self, rhs: Price) -> Money {
+        rhs * self
+    }
+}
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(s: &str) -> Price {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn rounds_to_the_tick() {
+        assert_eq!(price("187.315").round_to_tick(Price::CENT), price("187.32"));
+        assert_eq!(price("187.314").round_to_tick(Price::CENT), price("187.31"));
+        assert_eq!(price("187.319").floor_to_tick(Price::CENT), price("187.31"));
+        assert_eq!(price("187.311").ceil_to_tick(Price::CENT), price("187.32"));
+        assert_eq!(price("10.07").round_to_tick(price("0.05")), price("10.05"));
+        assert_eq!(price("10.075").round_to_tick(price("0.05")), price("10.10"));
+        // Rounded prices carry the tick's scale, so they print like the venue quotes them
+        assert_eq!(
+            price("187").round_to_tick(Price::CENT).to_string(),
+            "187.00"
+        );
+        assert_eq!(price("1.2345").round_to_tick(Price::ZERO), price("1.2345"));
+    }
+
+    #[test]
+    fn us_equity_tick_depends_on_price() {
+        assert_eq!(price("1.00").us_equity_tick(), Price::CENT);
+        assert_eq!(price("0.9999").us_equity_tick(), Price::SUB_DOLLAR_TICK);
+        assert_eq!(
+            price("0.12345").round_to_tick(price("0.12345").us_equity_tick()),
+            price("0.1235")
+        );
+    }
+
+    #[test]
+    fn notional_is_exact_until_rounded_to_cents() {
+        let notional = price("187.31") * Quantity::from(3);
+        assert_eq!(notional, "561.93".parse().unwrap());
+        let fractional = price("0.3333") * "0.5".parse::<Quantity>().unwrap();
+        assert_eq!(fractional, "0.16665".parse().unwrap());
+        assert_eq!(fractional.round_cents(), "0.17".parse().unwrap());
+        assert_eq!((-fractional).round_cents(), "-0.17".parse().unwrap());
+    }
+
+    #[test]
+    fn converts_vendor_floats_without_binary_noise() {
+        assert_eq!(Price::from_f64(187.31), Some(price("187.31")));
+        assert_eq!(
+            Price::from_f64(0.1 + 0.2),
+            Some(price("0.30000000000000004"))
+        );
+        assert_eq!(Price::from_f64(f64::NAN), None);
+        assert_eq!(Price::from_f64(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn serializes_as_a_decimal_string() {
+        assert_eq!(
+            serde_json::to_string(&price("187.31")).unwrap(),
+            "\"187.31\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Price>("\"187.31\"").unwrap(),
+            price("187.31")
+        );
+        assert_eq!(
+            serde_json::from_str::<Price>("187.31").unwrap(),
+            price("187.31")
+        );
+        assert!("1.2.3".parse::<Price>().is_err());
+        assert!(!"2.5".parse::<Quantity>().unwrap().is_whole());
+        assert!(Quantity::from(100).is_whole());
+    }
+}
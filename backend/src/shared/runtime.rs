@@ -0,0 +1,253 @@
+use crate::shared::config::Config;
+use crate::shared::metrics;
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde_json::json;
+use std::future::Future;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+// Process plumbing shared by the long-running agents. Each serves, on
+// `[runtime] listen`:
+//
+//   GET /healthz   200 while the process is up; 503 once shutdown has begun
+//   GET /readyz    200 when every registered check passes, otherwise 503
+//                  with {"status": ..., "checks": {name: bool}}
+//   GET /metrics   Prometheus text format, when `[metrics] enabled`
+//
+// SIGTERM or Ctrl-C triggers `Shutdown`. Agents stop taking in work, finish
+// what they hold, commit offsets and flush the bus within
+// `[runtime] shutdown_timeout_secs`, then return from `main`. Kubernetes
+// sends SIGTERM on every rollout, so this keeps rollouts from losing or
+// duplicating data.
+
+/// How often `AgentRuntime::watch` probes a dependency
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Signals the agent to stop; clones share the same state
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+    rx: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(false);
+        Self {
+            tx: Arc::new(tx),
+            rx,
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Resolves once shutdown has been triggered
+    pub async fn wait(&self) {
+        let mut rx = self.rx.clone();
+        // Only fails if the sender is gone, and `self` holds it
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One readiness condition, e.g. "db"; starts failing until first set
+#[derive(Clone)]
+pub struct HealthCheck {
+    name: &'static str,
+    ok: Arc<AtomicBool>,
+}
+
+impl HealthCheck {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn set(&self, ok: bool) {
+        if self.ok.swap(ok, Ordering::Relaxed) != ok {
+            if ok {
+                info!(check = self.name, "Health check passing");
+            } else {
+                warn!(check = self.name, "Health check failing");
+            }
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.ok.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone)]
+struct RuntimeState {
+    checks: Arc<Mutex<Vec<HealthCheck>>>,
+    shutdown: Shutdown,
+    metrics_enabled: bool,
+}
+
+/// Health endpoints and shutdown handling for one agent process
+pub struct AgentRuntime {
+    state: RuntimeState,
+    shutdown_timeout: Duration,
+}
+
+impl AgentRuntime {
+    /// Binds `[runtime] listen` and starts serving; `agent` names the process in logs
+    pub async fn start(agent: &'static str, config: &Config) -> io::Result<Self> {
+        let state = RuntimeState {
+            checks: Arc::new(Mutex::new(Vec::new())),
+            shutdown: Shutdown::new(),
+            metrics_enabled: config.metrics.enabled,
+        };
+
+        let listener = TcpListener::bind(&config.runtime.listen).await?;
+        info!(agent, addr = %listener.local_addr()?, "Serving health endpoints");
+
+        let app = Router::new()
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            .route("/metrics", get(metrics_endpoint))
+            .with_state(state.clone());
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                error!(error = %e, "Health server stopped");
+            }
+        });
+
+        let shutdown = state.shutdown.clone();
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            info!(agent, "Shutting down");
+            shutdown.trigger();
+        });
+
+        Ok(Self {
+            state,
+            shutdown_timeout: config.runtime.shutdown_timeout(),
+        })
+    }
+
+    /// Registers a readiness check the agent sets itself
+    pub fn check(&self, name: &'static str) -> HealthCheck {
+        let check = HealthCheck {
+            name,
+            ok: Arc::new(AtomicBool::new(false)),
+        };
+        if let Ok(mut checks) = self.state.checks.lock() {
+            checks.push(check.clone());
+        }
+        check
+    }
+
+    /// Registers a readiness check updated from `probe` every few seconds until shutdown
+    pub fn watch<F, Fut>(&self, name: &'static str, probe: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = bool> + Send,
+    {
+        let check = self.check(name);
+        let shutdown = self.shutdown();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PROBE_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => check.set(probe().await),
+                    _ = shutdown.wait() => break,
+                }
+            }
+        });
+    }
+
+    pub fn shutdown(&self) -> Shutdown {
+        self.state.shutdown.clone()
+    }
+
+    /// `[runtime] shutdown_timeout_secs`
+    pub fn shutdown_timeout(&self) -> Duration {
+        self.shutdown_timeout
+    }
+}
+
+async fn healthz(State(state): State<RuntimeState>) -> StatusCode {
+    if state.shutdown.is_triggered() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    }
+}
+
+async fn readyz(State(state): State<RuntimeState>) -> Response {
+    let checks: Vec<(&'static str, bool)> = match state.checks.lock() {
+        Ok(checks) => checks.iter().map(|c| (c.name(), c.is_ok())).collect(),
+        Err(_) => Vec::new(),
+    };
+    let status = if state.shutdown.is_triggered() {
+        "shutting_down"
+    } else if checks.iter().all(|(_, ok)| *ok) {
+        "ready"
+    } else {
+        "not_ready"
+    };
+    let code = if status == "ready" {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let checks: serde_json::Map<String, serde_json::Value> = checks
+        .into_iter()
+        .map(|(name, ok)| (name.to_string(), ok.into()))
+        .collect();
+    let body = json!({ "status": status, "checks": checks });
+    (code, [(CONTENT_TYPE, "application/json")], body.to_string()).into_response()
+}
+
+async fn metrics_endpoint(State(state): State<RuntimeState>) -> Response {
+    if state.metrics_enabled {
+        metrics::render().await
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// SIGTERM (sent by Kubernetes and Docker on stop) or Ctrl-C
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+                return;
+            }
+            Err(e) => warn!(error = %e, "Cannot listen for SIGTERM; only Ctrl-C stops the agent"),
+        }
+    }
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        // Without a signal handler the agent runs until killed, as before
+        error!(error = %e, "Cannot listen for Ctrl-C");
+        std::future::pending::<()>().await;
+    }
+}
@@ -0,0 +1,491 @@
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Environment variable holding the keystore passphrase
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "OPTITRADE_KEYSTORE_PASSPHRASE";
+
+const KEYSTORE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+
+/// A credential that is redacted in `Debug`/`Display` and wiped from memory on drop.
+/// Call `expose` only at the point where the raw value is sent to the broker.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.trim().is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
+
+/// Serializes as `"[REDACTED]"` (or `""` when unset) so dumping a config never leaks it
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_empty() {
+            serializer.serialize_str("")
+        } else {
+            serializer.serialize_str("[REDACTED]")
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SecretError {
+    Io { path: PathBuf, source: io::Error },
+    InvalidKey(String),
+    MissingPassphrase,
+    Keystore(String),
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretError::Io { path, source } => {
+                write!(f, "failed to access {}: {}", path.display(), source)
+            }
+            SecretError::InvalidKey(key) => write!(f, "invalid secret key '{}'", key),
+            SecretError::MissingPassphrase => write!(
+                f,
+                "keystore passphrase not set (expected {})",
+                KEYSTORE_PASSPHRASE_ENV
+            ),
+            SecretError::Keystore(message) => write!(f, "keystore error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SecretError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SecretError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Source of credentials, addressed by slash-separated keys such as `alpaca/api_key`
+pub trait SecretsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Returns `Ok(None)` when the provider simply has no value for `key`
+    fn get(&self, key: &str) -> Result<Option<Secret>, SecretError>;
+}
+
+/// Reads `alpaca/api_key` from `<PREFIX>ALPACA_API_KEY`
+pub struct EnvSecrets {
+    prefix: String,
+    vars: Option<HashMap<String, String>>,
+}
+
+impl EnvSecrets {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            vars: None,
+        }
+    }
+
+    /// Looks secrets up in `vars` instead of the process environment
+    pub fn with_vars<I>(prefix: impl Into<String>, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        Self {
+            prefix: prefix.into(),
+            vars: Some(vars.into_iter().collect()),
+        }
+    }
+
+    fn var_name(&self, key: &str) -> String {
+        let suffix: String = key
+            .chars()
+            .map(|c| match c {
+                '/' | '.' | '-' => '_',
+                c => c.to_ascii_uppercase(),
+            })
+            .collect();
+        format!("{}{}", self.prefix, suffix)
+    }
+}
+
+impl SecretsProvider for EnvSecrets {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Secret>, SecretError> {
+        validate_key(key)?;
+        let name = self.var_name(key);
+        let value = match &self.vars {
+            Some(vars) => vars.get(&name).cloned(),
+            None => std::env::var(&name).ok(),
+        };
+        Ok(value.map(Secret::from))
+    }
+}
+
+/// Reads `alpaca/api_key` from `<dir>/alpaca/api_key`, the layout produced by
+/// mounting a Kubernetes Secret as a volume
+pub struct FileSecrets {
+    dir: PathBuf,
+}
+
+impl FileSecrets {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SecretsProvider for FileSecrets {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Secret>, SecretError> {
+        validate_key(key)?;
+        let path = self.dir.join(key);
+        match fs::read_to_string(&path) {
+            Ok(mut contents) => {
+                let secret = Secret::new(contents.trim_end_matches(['\r', '\n']));
+                contents.zeroize();
+                Ok(Some(secret))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(SecretError::Io { path, source }),
+        }
+    }
+}
+
+/// On-disk keystore layout: an Argon2id-derived key encrypts a JSON map of
+/// secrets with ChaCha20-Poly1305
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Passphrase-protected local keystore for developer machines
+pub struct Keystore {
+    path: PathBuf,
+    entries: BTreeMap<String, Secret>,
+}
+
+impl Keystore {
+    /// Starts an empty keystore that will be written to `path` on `save`
+    pub fn create(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Decrypts the keystore at `path`
+    pub fn open(path: impl Into<PathBuf>, passphrase: &Secret) -> Result<Self, SecretError> {
+        let path = path.into();
+        let raw = fs::read_to_string(&path).map_err(|source| SecretError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let file: KeystoreFile = serde_json::from_str(&raw)
+            .map_err(|e| SecretError::Keystore(format!("malformed keystore: {}", e)))?;
+        if file.version != KEYSTORE_VERSION {
+            return Err(SecretError::Keystore(format!(
+                "unsupported keystore version {}",
+                file.version
+            )));
+        }
+
+        let salt = decode_field("salt", &file.salt)?;
+        let nonce = decode_field("nonce", &file.nonce)?;
+        let ciphertext = decode_field("ciphertext", &file.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err(SecretError::Keystore("invalid nonce length".to_string()));
+        }
+
+        let cipher = cipher_for(passphrase, &salt)?;
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+                .map_err(|_| {
+                    SecretError::Keystore("wrong passphrase or corrupted keystore".to_string())
+                })?,
+        );
+        let entries: BTreeMap<String, String> = serde_json::from_slice(&plaintext)
+            .map_err(|e| SecretError::Keystore(format!("malformed keystore contents: {}", e)))?;
+
+        Ok(Self {
+            path,
+            entries: entries
+                .into_iter()
+                .map(|(k, v)| (k, Secret::from(v)))
+                .collect(),
+        })
+    }
+
+    /// Encrypts all entries under a fresh salt and nonce and writes them to disk
+    pub fn save(&self, passphrase: &Secret) -> Result<(), SecretError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = cipher_for(passphrase, &salt)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let plain: BTreeMap<&str, &str> = self
+            .entries
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.expose()))
+            .collect();
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&plain).map_err(|e| SecretError::Keystore(e.to_string()))?,
+        );
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| SecretError::Keystore("encryption failed".to_string()))?;
+
+        let file = KeystoreFile {
+            version: KEYSTORE_VERSION,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| SecretError::Keystore(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|source| SecretError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        write_private(&self.path, json.as_bytes()).map_err(|source| SecretError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    pub fn set(&mut self, key: &str, value: Secret) -> Result<(), SecretError> {
+        validate_key(key)?;
+        self.entries.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Secret> {
+        self.entries.remove(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+}
+
+impl SecretsProvider for Keystore {
+    fn name(&self) -> &'static str {
+        "keystore"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Secret>, SecretError> {
+        validate_key(key)?;
+        Ok(self.entries.get(key).cloned())
+    }
+}
+
+/// Rejects empty keys and anything that could escape a `FileSecrets` directory
+fn validate_key(key: &str) -> Result<(), SecretError> {
+    let valid = !key.is_empty()
+        && Path::new(key)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidKey(key.to_string()))
+    }
+}
+
+fn cipher_for(passphrase: &Secret, salt: &[u8]) -> Result<ChaCha20Poly1305, SecretError> {
+    if passphrase.is_empty() {
+        return Err(SecretError::MissingPassphrase);
+    }
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.expose().as_bytes(), salt, key.as_mut())
+        .map_err(|e| SecretError::Keystore(format!("key derivation failed: {}", e)))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_ref())))
+}
+
+fn decode_field(name: &str, value: &str) -> Result<Vec<u8>, SecretError> {
+    BASE64
+        .decode(value)
+        .map_err(|e| SecretError::Keystore(format!("invalid {}: {}", name, e)))
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("optitrade_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn secrets_never_print_their_value() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(secret.to_string(), "[REDACTED]");
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"[REDACTED]\"");
+        assert_eq!(serde_json::to_string(&Secret::default()).unwrap(), "\"\"");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn env_secrets_map_keys_to_variable_names() {
+        let secrets = EnvSecrets::with_vars(
+            "OPTITRADE_SECRET_",
+            [(
+                "OPTITRADE_SECRET_ALPACA_API_KEY".to_string(),
+                "key".to_string(),
+            )],
+        );
+        assert_eq!(
+            secrets.get("alpaca/api_key").unwrap().unwrap().expose(),
+            "key"
+        );
+        assert_eq!(secrets.get("alpaca/api_secret").unwrap(), None);
+    }
+
+    #[test]
+    fn file_secrets_read_mounted_files() {
+        let dir = temp_path("file_secrets");
+        fs::create_dir_all(dir.join("alpaca")).unwrap();
+        fs::write(dir.join("alpaca/api_key"), "key\n").unwrap();
+
+        let secrets = FileSecrets::new(&dir);
+        assert_eq!(
+            secrets.get("alpaca/api_key").unwrap().unwrap().expose(),
+            "key"
+        );
+        assert_eq!(secrets.get("alpaca/api_secret").unwrap(), None);
+        for key in ["", "../alpaca/api_key", "/etc/passwd"] {
+            assert!(
+                matches!(secrets.get(key), Err(SecretError::InvalidKey(_))),
+                "{:?}",
+                key
+            );
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keystore_round_trips_and_needs_the_passphrase() {
+        let path = temp_path("keystore").join("keystore.json");
+        let passphrase = Secret::new("correct horse");
+        let mut keystore = Keystore::create(&path);
+        keystore.set("alpaca/api_key", Secret::new("key")).unwrap();
+        keystore
+            .set("database/password", Secret::new("pw"))
+            .unwrap();
+        assert!(keystore.set("../escape", Secret::new("x")).is_err());
+        keystore.save(&passphrase).unwrap();
+
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("alpaca/api_key")); // Keys are encrypted along with values
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let opened = Keystore::open(&path, &passphrase).unwrap();
+        assert_eq!(
+            opened.keys().collect::<Vec<_>>(),
+            ["alpaca/api_key", "database/password"]
+        );
+        assert_eq!(
+            opened.get("alpaca/api_key").unwrap().unwrap().expose(),
+            "key"
+        );
+
+        assert!(matches!(
+            Keystore::open(&path, &Secret::new("wrong")),
+            Err(SecretError::Keystore(_))
+        ));
+        assert!(matches!(
+            Keystore::open(&path, &Secret::default()),
+            Err(SecretError::MissingPassphrase)
+        ));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use crate::shared::instrument::Instrument;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Strategies ask `market_data` for the instruments they trade by publishing a
+// `SubscriptionRequest` on the `market_data_control` topic:
+//
+//   {"action": "subscribe", "requester": "momentum", "symbols": ["AAPL", "BTC/USD"]}
+//
+// The agent keeps one reference per requester and instrument, and streams an
+// instrument while anyone holds a reference. Repeating a request is harmless,
+// and one strategy unsubscribing never cuts off another. The agent itself
+// holds the symbols in `[market_data] symbols`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionAction {
+    Subscribe,
+    Unsubscribe,
+}
+
+/// A request on the `market_data_control` topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionRequest {
+    pub action: SubscriptionAction,
+    pub requester: String, // Strategy or agent name; holds one reference per symbol
+    pub symbols: Vec<Instrument>,
+}
+
+/// Reference-counted set of subscribed instruments
+#[derive(Debug, Clone, Default)]
+pub struct Subscriptions {
+    holders: BTreeMap<Instrument, BTreeSet<String>>,
+}
+
+impl Subscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes `requester`'s reference; `true` if nobody held `instrument` before
+    pub fn add(&mut self, requester: &str, instrument: &Instrument) -> bool {
+        let holders = self.holders.entry(instrument.clone()).or_default();
+        let first = holders.is_empty();
+        holders.insert(requester.to_string());
+        first
+    }
+
+    /// Drops `requester`'s reference; `true` if it was the last one
+    pub fn remove(&mut self, requester: &str, instrument: &Instrument) -> bool {
+        let Some(holders) = self.holders.get_mut(instrument) else {
+            return false;
+        };
+        if !holders.remove(requester) || !holders.is_empty() {
+            return false;
+        }
+        self.holders.remove(instrument);
+        true
+    }
+
+    pub fn contains(&self, instrument: &Instrument) -> bool {
+        self.holders.contains_key(instrument)
+    }
+
+    /// Who holds `instrument`, in name order
+    pub fn holders(&self, instrument: &Instrument) -> impl Iterator<Item = &str> {
+        self.holders
+            .get(instrument)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    pub fn instruments(&self) -> impl Iterator<Item = &Instrument> {
+        self.holders.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.holders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.holders.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_add_and_last_remove_change_the_subscription() {
+        let aapl: Instrument = "AAPL".parse().unwrap();
+        let mut subscriptions = Subscriptions::new();
+
+        assert!(subscriptions.add("config", &aapl));
+        assert!(!subscriptions.add("momentum", &aapl));
+        assert!(!subscriptions.add("momentum", &aapl)); // Holding twice is still one reference
+        assert_eq!(
+            subscriptions.holders(&aapl).collect::<Vec<_>>(),
+            ["config", "momentum"]
+        );
+
+        assert!(!subscriptions.remove("momentum", &aapl));
+        assert!(!subscriptions.remove("momentum", &aapl)); // No longer a holder
+        assert!(subscriptions.contains(&aapl));
+        assert!(subscriptions.remove("config", &aapl));
+        assert!(!subscriptions.contains(&aapl));
+        assert!(subscriptions.is_empty());
+    }
+
+    #[test]
+    fn removing_an_unknown_instrument_is_a_no_op() {
+        let mut subscriptions = Subscriptions::new();
+        let btc: Instrument = "BTC/USD".parse().unwrap();
+        assert!(!subscriptions.remove("config", &btc));
+        assert_eq!(subscriptions.holders(&btc).count(), 0);
+
+        subscriptions.add("config", &"TSLA".parse().unwrap());
+        subscriptions.add("config", &btc);
+        assert_eq!(subscriptions.len(), 2);
+        assert_eq!(
+            subscriptions
+                .instruments()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>(),
+            ["TSLA", "BTC/USD"]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::shared::config::{LogFormat, LoggingConfig};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+// Every agent logs through `tracing`. A correlation ID is minted where a
+// market event enters the system and travels in the `optitrade-correlation-id`
+// bus header, so the tick, the signal it triggered, the risk decision, the
+// order and its fill all log the same `correlation_id` field. Filter one trade
+// out of the JSON logs of every agent with that value.
+
+/// Installs the global subscriber configured by `[logging]`; later calls are ignored
+pub fn init_logging(config: &LoggingConfig) {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.filter))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    let _ = match config.format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(false) // Already the last entry of `spans`
+            .try_init(),
+    };
+}
+
+/// Identifies the chain of messages and actions started by one market event
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    /// A new random ID
+    pub fn new() -> Self {
+        CorrelationId(Uuid::new_v4().to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<String> for CorrelationId {
+    fn from(value: String) -> Self {
+        CorrelationId(value)
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
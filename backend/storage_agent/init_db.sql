@@ -0,0 +1,13 @@
+-- Daily/intraday bars replayed by the backtester when [backtest] data_source = "db".
+CREATE TABLE IF NOT EXISTS historical_bars (
+    symbol      TEXT             NOT NULL,
+    timestamp   TIMESTAMPTZ      NOT NULL, -- Bar open time
+    open        DOUBLE PRECISION NOT NULL,
+    high        DOUBLE PRECISION NOT NULL,
+    low         DOUBLE PRECISION NOT NULL,
+    close       DOUBLE PRECISION NOT NULL,
+    volume      DOUBLE PRECISION NOT NULL,
+    vwap        DOUBLE PRECISION,
+    trade_count BIGINT,
+    PRIMARY KEY (symbol, timestamp)
+);
@@ -1,15 +1,18 @@
+use backend::shared::config::DatabaseConfig;
+use backend::shared::metrics::{DB_INSERT_BATCH_SIZE, DB_INSERT_SECONDS};
+use backend::shared::money::Price;
+use tokio_postgres::types::ToSql;
 use tokio_postgres::{Client, NoTls};
+use tracing::{error, info};
 
-pub async fn connect_db() -> Result<Client, tokio_postgres::Error> {
-    let (client, connection) = tokio_postgres::connect(
-        "host=localhost port=5433 user=optitrade password=secret dbname=market_data",
-        NoTls,
-    )
-    .await?;
+const TABLE: &str = "market_data";
+
+pub async fn connect_db(config: &DatabaseConfig) -> Result<Client, tokio_postgres::Error> {
+    let (client, connection) = tokio_postgres::connect(&config.connection_string(), NoTls).await?;
 
     tokio::spawn(async move {
         if let Err(e) = connection.await {
-            eprintln!("Database connection error: {}", e);
+            error!(error = %e, "Database connection error");
         }
     });
 
@@ -24,41 +27,94 @@ async fn create_tables(client: &Client) -> Result<(), tokio_postgres::Error> {
         CREATE TABLE IF NOT EXISTS market_data (
             id SERIAL PRIMARY KEY,
             symbol TEXT NOT NULL,
-            bid_price DOUBLE PRECISION NOT NULL,
-            ask_price DOUBLE PRECISION NOT NULL,
-            last_price DOUBLE PRECISION,
+            bid_price NUMERIC NOT NULL,
+            ask_price NUMERIC NOT NULL,
+            last_price NUMERIC,
             timestamp BIGINT NOT NULL
         );
     ";
 
     client.execute(stmt, &[]).await?;
-    println!("[DB] ✅ Ensured table 'market_data' exists.");
+
+    // Tables created before prices were decimal used DOUBLE PRECISION. Only
+    // alter what is left, since ALTER TYPE locks and may rewrite the table.
+    let stale = client
+        .query(
+            "SELECT column_name::TEXT FROM information_schema.columns
+             WHERE table_schema = current_schema() AND table_name = $1
+               AND column_name IN ('bid_price', 'ask_price', 'last_price')
+               AND data_type <> 'numeric'",
+            &[&TABLE],
+        )
+        .await?;
+    if !stale.is_empty() {
+        let columns: Vec<String> = stale.iter().map(|row| row.get(0)).collect();
+        let alters: Vec<String> = columns
+            .iter()
+            .map(|column| format!("ALTER COLUMN {} TYPE NUMERIC", column))
+            .collect();
+        info!(
+            table = TABLE,
+            ?columns,
+            "Migrating price columns to NUMERIC"
+        );
+        client
+            .batch_execute(&format!("ALTER TABLE {} {};", TABLE, alters.join(", ")))
+            .await?;
+    }
+    info!(table = TABLE, "Ensured table exists");
     Ok(())
 }
 
+/// One row of the `market_data` table
+pub struct QuoteRow {
+    pub symbol: String,
+    pub bid_price: Price,
+    pub ask_price: Price,
+    pub last_price: Option<Price>,
+    pub timestamp: i64, // Unix seconds
+}
+
+/// Inserts `rows` with a single statement
 pub async fn store_market_data(
     client: &Client,
-    symbol: &str,
-    bid_price: f64,
-    ask_price: f64,
-    last_price: Option<f64>,
-    timestamp: u64,
-) {
-    let stmt = "
-        INSERT INTO market_data (symbol, bid_price, ask_price, last_price, timestamp)
-        VALUES ($1, $2, $3, $4, $5)";
+    rows: &[QuoteRow],
+) -> Result<(), tokio_postgres::Error> {
+    if rows.is_empty() {
+        return Ok(());
+    }
 
-    client
-        .execute(
-            stmt,
-            &[
-                &symbol,
-                &bid_price,
-                &ask_price,
-                &last_price,
-                &(timestamp as i64),
-            ],
-        )
-        .await
-        .expect("[DB] ❌ Failed to insert market data");
+    let mut values = Vec::with_capacity(rows.len());
+    let mut params: Vec<&(dyn ToSql + Sync)> = Vec::with_capacity(rows.len() * 5);
+    for (i, row) in rows.iter().enumerate() {
+        let n = i * 5;
+        values.push(format!(
+            "(${}, ${}, ${}, ${}, ${})",
+            n + 1,
+            n + 2,
+            n + 3,
+            n + 4,
+            n + 5
+        ));
+        params.extend([
+            &row.symbol as &(dyn ToSql + Sync),
+            &row.bid_price,
+            &row.ask_price,
+            &row.last_price,
+            &row.timestamp,
+        ]);
+    }
+    let stmt = format!(
+        "INSERT INTO market_data (symbol, bid_price, ask_price, last_price, timestamp)
+        VALUES {}",
+        values.join(", ")
+    );
+
+    let timer = DB_INSERT_SECONDS.with_label_values(&[TABLE]).start_timer();
+    client.execute(&stmt, &params).await?;
+    timer.observe_duration();
+    DB_INSERT_BATCH_SIZE
+        .with_label_values(&[TABLE])
+        .observe(rows.len() as f64);
+    Ok(())
 }
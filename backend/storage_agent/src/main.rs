@@ -1,12 +1,65 @@
 mod db_writer;
-mod kafka_consumer;
+mod market_data_consumer;
 
+use backend::shared::config::load_config;
+use backend::shared::message_bus::Bus;
+use backend::shared::runtime::AgentRuntime;
+use backend::shared::telemetry::init_logging;
 use db_writer::connect_db;
-use kafka_consumer::consume_kafka_messages;
-use tokio_postgres::Client;
+use market_data_consumer::consume_market_data;
+use std::sync::Arc;
+use tracing::{error, info};
 
 #[tokio::main]
 async fn main() {
-    let db_client: Client = connect_db().await.expect("Failed to connect to database");
-    consume_kafka_messages(&db_client).await;
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("[Storage] ❌ {}", err);
+            std::process::exit(1);
+        }
+    };
+    init_logging(&config.logging);
+    let runtime = match AgentRuntime::start("storage_agent", &config).await {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            error!(listen = %config.runtime.listen, error = %err, "Failed to start health server");
+            std::process::exit(1);
+        }
+    };
+
+    let bus = Arc::new(match Bus::connect(&config).await {
+        Ok(bus) => bus,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    });
+    let probe_bus = Arc::clone(&bus);
+    runtime.watch("bus", move || {
+        let bus = Arc::clone(&probe_bus);
+        async move { bus.health_check().await.is_ok() }
+    });
+
+    let db_client = Arc::new(match connect_db(&config.database).await {
+        Ok(client) => client,
+        Err(err) => {
+            error!(error = %err, "Failed to connect to database");
+            std::process::exit(1);
+        }
+    });
+    let probe_client = Arc::clone(&db_client);
+    runtime.watch("db", move || {
+        let closed = probe_client.is_closed();
+        async move { !closed }
+    });
+
+    let result = consume_market_data(&bus, &db_client, &runtime.shutdown()).await;
+    // Dropping the last handle closes the database connection
+    drop(db_client);
+    if let Err(err) = result {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+    info!("Storage agent stopped");
 }
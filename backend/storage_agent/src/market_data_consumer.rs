@@ -0,0 +1,230 @@
+use crate::db_writer::{store_market_data, QuoteRow};
+use backend::shared::dead_letter::DeadLetterQueue;
+use backend::shared::market_event::{EventPayload, MarketEvent};
+use backend::shared::message_bus::{Bus, BusError, Message, TypedSubscription, MARKET_DATA};
+use backend::shared::money::Price;
+use backend::shared::runtime::Shutdown;
+use std::collections::HashMap;
+use std::fmt;
+use tokio::time::{sleep_until, Duration, Instant};
+use tokio_postgres::Client;
+use tracing::{debug, error, info, warn};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const CONSUMER_NAME: &str = "storage_agent";
+/// Quotes written per insert at most
+const BATCH_SIZE: usize = 100;
+/// How long a quote may wait for its batch to fill
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub enum ConsumerError {
+    Bus(BusError),
+    Db(tokio_postgres::Error),
+}
+
+impl fmt::Display for ConsumerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsumerError::Bus(e) => write!(f, "{}", e),
+            ConsumerError::Db(e) => write!(f, "failed to store market data: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConsumerError {}
+
+impl From<BusError> for ConsumerError {
+    fn from(e: BusError) -> Self {
+        ConsumerError::Bus(e)
+    }
+}
+
+impl From<tokio_postgres::Error> for ConsumerError {
+    fn from(e: tokio_postgres::Error) -> Self {
+        ConsumerError::Db(e)
+    }
+}
+
+/// Quotes waiting to be written, and every message received since the last
+/// write. Messages are only committed once the batch is stored, so a crash
+/// redelivers anything not yet in the database.
+struct Batch {
+    rows: Vec<QuoteRow>,
+    messages: Vec<Message>,
+    deadline: Option<Instant>, // When the oldest row must be written
+}
+
+/// Stores quotes until the bus closes or `shutdown` fires, then writes what is
+/// pending and commits it before leaving the consumer group
+pub async fn consume_market_data(
+    bus: &Bus,
+    db_client: &Client,
+    shutdown: &Shutdown,
+) -> Result<(), ConsumerError> {
+    let mut subscription = bus.subscribe(&MARKET_DATA, CONSUMER_NAME).await?;
+    let dead_letters = DeadLetterQueue::new(bus, CONSUMER_NAME);
+
+    // Last trade price per symbol, stored alongside each quote
+    let mut last_prices: HashMap<String, Price> = HashMap::new();
+    let mut batch = Batch {
+        rows: Vec::with_capacity(BATCH_SIZE),
+        messages: Vec::new(),
+        deadline: None,
+    };
+
+    loop {
+        let deadline = batch.deadline;
+        let delivery = tokio::select! {
+            delivery = subscription.recv() => match delivery {
+                Some(delivery) => delivery,
+                None => break,
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                flush(db_client, &mut subscription, &mut batch).await?;
+                continue;
+            }
+            _ = shutdown.wait() => {
+                info!(pending = batch.rows.len(), "Stopping market data consumer");
+                break;
+            }
+        };
+        let delivery = match delivery {
+            Ok(delivery) => delivery,
+            Err(e) => {
+                error!(error = %e, "Message bus error");
+                continue;
+            }
+        };
+        let correlation_id = delivery.message.correlation_id();
+        let correlation_id = correlation_id.as_ref().map(|id| id.as_str());
+
+        match delivery.value {
+            Ok(event) => {
+                if let Some(row) = quote_row(event, &mut last_prices, correlation_id) {
+                    batch.rows.push(row);
+                    batch
+                        .deadline
+                        .get_or_insert_with(|| Instant::now() + FLUSH_INTERVAL);
+                }
+            }
+            Err(e) => {
+                error!(
+                    correlation_id,
+                    error = %e,
+                    payload = %String::from_utf8_lossy(&delivery.message.payload),
+                    "Failed to decode market event"
+                );
+                if let Err(e) = dead_letters.send(&delivery.message, &e).await {
+                    error!(correlation_id, error = %e, "Failed to dead-letter market event");
+                }
+            }
+        }
+        batch.messages.push(delivery.message);
+
+        if batch.rows.len() >= BATCH_SIZE || batch.rows.is_empty() {
+            flush(db_client, &mut subscription, &mut batch).await?;
+        }
+    }
+
+    // A failed final write leaves its messages uncommitted, to be redelivered
+    flush(db_client, &mut subscription, &mut batch).await?;
+    subscription.close().await?;
+    Ok(())
+}
+
+/// The row to store for a quote; trades only update the last price
+fn quote_row(
+    event: MarketEvent,
+    last_prices: &mut HashMap<String, Price>,
+    correlation_id: Option<&str>,
+) -> Option<QuoteRow> {
+    match event.payload {
+        EventPayload::Quote(quote) => {
+            let last_price = last_prices.get(&event.symbol).copied();
+            match (
+                Price::from_f64(quote.bid_price),
+                Price::from_f64(quote.ask_price),
+            ) {
+                (Some(bid_price), Some(ask_price)) => {
+                    // Event timestamps are nanoseconds; the table stores Unix seconds
+                    let timestamp = (event.exchange_ts_ns / NANOS_PER_SECOND) as i64;
+                    debug!(
+                        correlation_id,
+                        symbol = %event.symbol,
+                        bid = %bid_price,
+                        ask = %ask_price,
+                        last = ?last_price,
+                        timestamp,
+                        "Storing quote"
+                    );
+                    Some(QuoteRow {
+                        symbol: event.symbol,
+                        bid_price,
+                        ask_price,
+                        last_price,
+                        timestamp,
+                    })
+                }
+                _ => {
+                    warn!(
+                        correlation_id,
+                        symbol = %event.symbol,
+                        ?quote,
+                        "Skipping quote with invalid prices"
+                    );
+                    None
+                }
+            }
+        }
+        EventPayload::Trade(trade) => {
+            if let Some(price) = Price::from_f64(trade.price) {
+                last_prices.insert(event.symbol, price);
+            }
+            None
+        }
+        EventPayload::Gap(gap) => {
+            warn!(
+                correlation_id,
+                symbol = %event.symbol,
+                since_ns = gap.since_ns,
+                until_ns = event.exchange_ts_ns,
+                "Feed gap: quotes in this window were not received"
+            );
+            None
+        }
+        EventPayload::Status(status) => {
+            info!(
+                correlation_id,
+                symbol = %event.symbol,
+                status = ?status.status,
+                reason = %status.reason,
+                "Trading status changed"
+            );
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Writes the pending rows, then commits the messages they came from
+async fn flush(
+    db_client: &Client,
+    subscription: &mut TypedSubscription<MarketEvent>,
+    batch: &mut Batch,
+) -> Result<(), ConsumerError> {
+    store_market_data(db_client, &batch.rows).await?;
+    batch.rows.clear();
+    batch.deadline = None;
+
+    for message in batch.messages.drain(..) {
+        if let Err(e) = subscription.commit(&message).await {
+            error!(
+                correlation_id = message.correlation_id().as_ref().map(|id| id.as_str()),
+                error = %e,
+                "Failed to commit market event"
+            );
+        }
+    }
+    Ok(())
+}
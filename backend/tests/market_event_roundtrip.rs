@@ -0,0 +1,192 @@
+use backend::shared::codec::{codec_for, decode_market_event, FlatBuffersCodec, WireFormat};
+use backend::shared::market_data_generated::market_data as fb;
+use backend::shared::market_event::{
+    Bar, EventPayload, Gap, Greeks, MarketEvent, OptionQuote, OptionRight, OrderBookUpdate,
+    PriceLevel, Quote, Status, Trade, TradingStatus,
+};
+use flatbuffers::FlatBufferBuilder;
+
+const EXCHANGE_TS: u64 = 1_706_797_800_123_456_789;
+const RECEIVE_TS: u64 = 1_706_797_800_124_000_000;
+
+fn event(symbol: &str, payload: EventPayload) -> MarketEvent {
+    MarketEvent {
+        symbol: symbol.to_string(),
+        exchange_ts_ns: EXCHANGE_TS,
+        receive_ts_ns: RECEIVE_TS,
+        payload,
+    }
+}
+
+fn sample_events() -> Vec<MarketEvent> {
+    vec![
+        event(
+            "AAPL",
+            EventPayload::Quote(Quote {
+                bid_price: 187.42,
+                ask_price: 187.44,
+                bid_size: 300.0,
+                ask_size: 100.0,
+            }),
+        ),
+        event(
+            "BTC/USD",
+            EventPayload::Trade(Trade {
+                price: 43_210.55,
+                quantity: 0.0125,
+                trade_id: 9_007_199_254_740_993,
+            }),
+        ),
+        event(
+            "TSLA",
+            EventPayload::Bar(Bar {
+                open: 190.1,
+                high: 192.75,
+                low: 189.3,
+                close: 191.9,
+                volume: 1_234_567.0,
+                vwap: 191.02,
+                trade_count: 8_421,
+                interval_ns: 60_000_000_000,
+            }),
+        ),
+        event(
+            "NVDA",
+            EventPayload::OrderBook(OrderBookUpdate {
+                sequence: 42,
+                is_snapshot: false,
+                bids: vec![
+                    PriceLevel {
+                        price: 615.10,
+                        quantity: 200.0,
+                    },
+                    PriceLevel {
+                        price: 615.05,
+                        quantity: 0.0,
+                    },
+                ],
+                asks: vec![PriceLevel {
+                    price: 615.20,
+                    quantity: 50.0,
+                }],
+            }),
+        ),
+        event(
+            "AAPL250221C00200000",
+            EventPayload::OptionQuote(OptionQuote {
+                underlying: "AAPL".to_string(),
+                strike: 200.0,
+                expiry: 20250221,
+                right: OptionRight::Call,
+                bid_price: 3.45,
+                ask_price: 3.55,
+                last_price: 3.50,
+                implied_volatility: 0.2731,
+                greeks: Greeks {
+                    delta: 0.41,
+                    gamma: 0.032,
+                    theta: -0.087,
+                    vega: 0.215,
+                    rho: 0.051,
+                },
+            }),
+        ),
+        event(
+            "AAPL",
+            EventPayload::Gap(Gap {
+                since_ns: EXCHANGE_TS - 30_000_000_000,
+            }),
+        ),
+        event(
+            "TSLA",
+            EventPayload::Status(Status {
+                status: TradingStatus::Halted,
+                reason: "LUDP: volatility trading pause".to_string(),
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn every_payload_round_trips_through_both_codecs() {
+    for format in [WireFormat::Json, WireFormat::Flatbuffers] {
+        let codec = codec_for(format);
+        for original in sample_events() {
+            let bytes = codec.encode(&original).unwrap();
+            let decoded = codec.decode(&bytes).unwrap();
+            assert_eq!(decoded, original, "{} round trip", format);
+        }
+    }
+}
+
+#[test]
+fn decoding_without_header_detects_the_format() {
+    for format in [WireFormat::Json, WireFormat::Flatbuffers] {
+        for original in sample_events() {
+            let bytes = codec_for(format).encode(&original).unwrap();
+            assert_eq!(WireFormat::sniff(&bytes), format);
+            assert_eq!(decode_market_event(None, &bytes).unwrap(), original);
+            assert_eq!(
+                decode_market_event(Some(format.header_value().as_bytes()), &bytes).unwrap(),
+                original
+            );
+        }
+    }
+}
+
+#[test]
+fn flatbuffers_view_reads_fields_without_copying() {
+    let original = &sample_events()[3];
+    let bytes = codec_for(WireFormat::Flatbuffers).encode(original).unwrap();
+
+    let view = FlatBuffersCodec::view(&bytes).unwrap();
+    assert_eq!(view.symbol(), Some("NVDA"));
+    assert_eq!(view.exchange_ts_ns(), EXCHANGE_TS);
+    assert_eq!(view.receive_ts_ns(), RECEIVE_TS);
+    assert_eq!(view.payload_type(), fb::Payload::OrderBookUpdate);
+
+    let book = view.payload_as_order_book_update().unwrap();
+    assert_eq!(book.sequence(), 42);
+    let bids = book.bids().unwrap();
+    assert_eq!(bids.len(), 2);
+    assert_eq!(bids.get(1).quantity(), 0.0);
+}
+
+#[test]
+fn json_uses_a_type_tag_for_the_payload() {
+    let bytes = codec_for(WireFormat::Json)
+        .encode(&sample_events()[4])
+        .unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(value["payload"]["type"], "option_quote");
+    assert_eq!(value["payload"]["right"], "call");
+    assert_eq!(value["exchange_ts_ns"], EXCHANGE_TS);
+}
+
+#[test]
+fn flatbuffers_event_without_payload_is_rejected() {
+    let mut builder = FlatBufferBuilder::new();
+    let symbol = builder.create_string("AAPL");
+    let root = fb::MarketEvent::create(
+        &mut builder,
+        &fb::MarketEventArgs {
+            symbol: Some(symbol),
+            exchange_ts_ns: EXCHANGE_TS,
+            receive_ts_ns: RECEIVE_TS,
+            payload_type: fb::Payload::NONE,
+            payload: None,
+        },
+    );
+    fb::finish_market_event_buffer(&mut builder, root);
+
+    let result = codec_for(WireFormat::Flatbuffers).decode(builder.finished_data());
+    assert!(result.is_err());
+}
+
+#[test]
+fn unknown_codec_header_is_an_error() {
+    let bytes = codec_for(WireFormat::Json)
+        .encode(&sample_events()[0])
+        .unwrap();
+    assert!(decode_market_event(Some(b"avro".as_slice()), &bytes).is_err());
+}